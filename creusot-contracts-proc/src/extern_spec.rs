@@ -174,6 +174,12 @@ impl FlatSpec {
                 escape_self_in_type(ty, self_ty.clone());
             }
 
+            for param in &mut self.generics.params {
+                if let GenericParam::Type(param) = param {
+                    escape_self_in_bounds(&mut param.bounds, &self_ty);
+                }
+            }
+
             match data.self_ty {
                 TraitOrImpl::Trait(trait_name, generics) => {
                     where_clause.predicates.push(parse_quote! { Self_ : #trait_name #generics });
@@ -224,6 +230,7 @@ fn escape_self_in_type(t: &mut Type, self_ty: Type) {
     match t {
         Type::Reference(TypeReference { elem, .. }) => escape_self_in_type(elem, self_ty),
         Type::Path(TypePath { path, qself }) => {
+            escape_self_in_path_args(path, &self_ty);
             if path.segments[0].ident == "Self" {
                 if self_ty == parse_quote! { Self_ } {
                     let mut ident: Ident = parse_quote! { Self_ };
@@ -247,11 +254,50 @@ fn escape_self_in_type(t: &mut Type, self_ty: Type) {
             }
         }
 
+        Type::Tuple(TypeTuple { elems, .. }) => {
+            for elem in elems {
+                escape_self_in_type(elem, self_ty.clone())
+            }
+        }
+
         // I'm lazy
         _ => {}
     };
 }
 
+// Escape `Self` in the generic arguments of a path, like in `Option<Self::Item>` or
+// `FnMut(Self::Item) -> B`
+fn escape_self_in_path_args(path: &mut Path, self_ty: &Type) {
+    for segment in &mut path.segments {
+        match &mut segment.arguments {
+            PathArguments::AngleBracketed(args) => {
+                for arg in &mut args.args {
+                    if let GenericArgument::Type(ty) = arg {
+                        escape_self_in_type(ty, self_ty.clone())
+                    }
+                }
+            }
+            PathArguments::Parenthesized(args) => {
+                for input in &mut args.inputs {
+                    escape_self_in_type(input, self_ty.clone())
+                }
+                if let ReturnType::Type(_, ty) = &mut args.output {
+                    escape_self_in_type(ty, self_ty.clone())
+                }
+            }
+            PathArguments::None => {}
+        }
+    }
+}
+
+fn escape_self_in_bounds(bounds: &mut Punctuated<TypeParamBound, Add>, self_ty: &Type) {
+    for bound in bounds {
+        if let TypeParamBound::Trait(TraitBound { path, .. }) = bound {
+            escape_self_in_path_args(path, self_ty)
+        }
+    }
+}
+
 fn escape_self_in_contracts(attrs: &mut Vec<Attribute>) -> Result<()> {
     for attr in attrs {
         if let Some(id) = attr.path.get_ident() {
@@ -365,8 +411,15 @@ fn escape_self_in_term(t: &mut Term) {
             escape_self_in_term(hyp);
             escape_self_in_term(cons);
         }
-        Term::Forall(TermForall { term, .. }) => escape_self_in_term(term),
-        Term::Exists(TermExists { term, .. }) => escape_self_in_term(term),
+        Term::Forall(TermForall { trigger, term, .. })
+        | Term::Exists(TermExists { trigger, term, .. }) => {
+            if let Some(trigger) = trigger {
+                for pat in &mut trigger.terms {
+                    escape_self_in_term(pat)
+                }
+            }
+            escape_self_in_term(term)
+        }
         Term::Absurd(TermAbsurd { .. }) => {}
         Term::Pearlite(TermPearlite { term, .. }) => escape_self_in_term(term),
         Term::Lit(TermLit { .. }) => {}
@@ -0,0 +1,105 @@
+// Desugaring of the `for` loops carrying invariants.
+//
+// The invariants of a `for` loop cannot be attached to the loop of its MIR, as this loop
+// only exists once the `for` has been lowered. Instead, we perform the lowering ourselves:
+//
+//     #[invariant(name, inv)]
+//     for pat in iter_exp { body }
+//
+// becomes
+//
+//     let mut iter = iter_exp;
+//     let iter_old = ghost! { iter };
+//     let mut produced = ghost! { Seq::EMPTY };
+//     #[invariant(for_loop, IteratorSpec::produces(iter_old.inner(), produced.inner(), iter))]
+//     #[invariant(name, inv)]
+//     loop {
+//         match Iterator::next(&mut iter) {
+//             Some(elem) => {
+//                 produced = ghost! { produced.inner().push(elem) };
+//                 let pat = elem;
+//                 body
+//             }
+//             None => break,
+//         }
+//     }
+//
+// so that the invariants of the user may refer to `produced`, the sequence of the elements
+// already visited by the loop. As we do not call `IntoIterator::into_iter`, `iter_exp` must
+// already be an iterator.
+//
+// `produced` is deliberately unhygienic, since the invariants must see it: it shadows any
+// variable of the same name in the invariants and the body of the loop, but not after it.
+
+use crate::{is_invariant, pretyping, Invariant};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse::Result, *};
+
+/// Desugar `for_loop` with the invariant `inv` and those still attached to the loop.
+pub fn desugar_for_loop(inv: Invariant, mut for_loop: ExprForLoop) -> Result<TokenStream> {
+    let mut invariants = vec![inv];
+    let mut attrs = Vec::new();
+    for attr in std::mem::take(&mut for_loop.attrs) {
+        if is_invariant(&attr) {
+            invariants.push(attr.parse_args()?);
+        } else {
+            attrs.push(attr);
+        }
+    }
+
+    let invariants = invariants
+        .into_iter()
+        .map(|inv| {
+            let inv_body =
+                pretyping::encode_term(inv.invariant).unwrap_or_else(|e| e.into_tokens());
+            let invariant_name = inv.name.to_string();
+            quote! {
+                #[allow(unused_must_use)]
+                let _ = {
+                    #[creusot::no_translate]
+                    #[creusot::decl::spec]
+                    #[creusot::spec::invariant=#invariant_name]
+                    ||{ #inv_body }
+                };
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let ExprForLoop { label, pat, expr, body, .. } = for_loop;
+    let iter_inv = pretyping::encode_term(parse_quote! {
+        creusot_contracts::std::iter::IteratorSpec::produces(
+            __creusot_iter_old.inner(), produced.inner(), __creusot_iter
+        )
+    })
+    .unwrap_or_else(|e| e.into_tokens());
+
+    Ok(quote! {
+        {
+            let mut __creusot_iter = #expr;
+            let __creusot_iter_old = creusot_contracts::ghost! { __creusot_iter };
+            let mut produced = creusot_contracts::ghost! { creusot_contracts::Seq::EMPTY };
+            #[allow(unused_must_use)]
+            let _ = {
+                #[creusot::no_translate]
+                #[creusot::decl::spec]
+                #[creusot::spec::invariant="for_loop"]
+                ||{ #iter_inv }
+            };
+            #(#invariants)*
+            #(#attrs)*
+            #label loop {
+                match ::std::iter::Iterator::next(&mut __creusot_iter) {
+                    Some(__creusot_elem) => {
+                        produced = creusot_contracts::ghost! {
+                            produced.inner().push(__creusot_elem)
+                        };
+                        let #pat = __creusot_elem;
+                        #body
+                    }
+                    None => break,
+                }
+            }
+        }
+    })
+}
@@ -14,8 +14,10 @@ use syn::{
 };
 
 mod extern_spec;
+mod for_loop;
 mod maintains;
 mod pretyping;
+mod type_invariant;
 
 trait FilterAttrs<'a> {
     type Ret: Iterator<Item = &'a Attribute>;
@@ -285,7 +287,10 @@ pub fn variant(attr: TS1, tokens: TS1) -> TS1 {
 fn variant_inner(attr: TS1, tokens: TS1) -> Result<TS1> {
     let p: pearlite_syn::Term = parse(attr)?;
 
-    let mut f: ItemFn = parse(tokens)?;
+    let mut f: ItemFn = match parse(tokens.clone()) {
+        Ok(f) => f,
+        Err(_) => return Ok(loop_variant(p, tokens)),
+    };
 
     let var_name = generate_unique_ident(&f.sig.ident.to_string());
     let mut var_sig = f.sig.clone();
@@ -315,6 +320,25 @@ fn variant_inner(attr: TS1, tokens: TS1) -> Result<TS1> {
     }))
 }
 
+// A variant attached to a loop rather than a function, checked to decrease at every iteration
+fn loop_variant(p: pearlite_syn::Term, loopb: TS1) -> TS1 {
+    let var_body = pretyping::encode_term(p).unwrap_or_else(|e| e.into_tokens());
+    let loopb = proc_macro2::TokenStream::from(loopb);
+
+    TS1::from(quote! {
+        {
+            #[allow(unused_must_use)]
+            let _ = {
+                #[creusot::no_translate]
+                #[creusot::decl::spec]
+                #[creusot::spec::loop_variant]
+                ||{ creusot_contracts::stubs::variant_check(#var_body) }
+            };
+            #loopb
+        }
+    })
+}
+
 struct Invariant {
     name: syn::Ident,
     invariant: pearlite_syn::Term,
@@ -329,9 +353,32 @@ impl syn::parse::Parse for Invariant {
         Ok(Invariant { name, invariant })
     }
 }
+
+// Whether `attr` is an `#[invariant]` attribute still attached to the item it applies to, which
+// happens when several invariants are written on the same loop or type
+fn is_invariant(attr: &syn::Attribute) -> bool {
+    attr.path.segments.last().map_or(false, |seg| seg.ident == "invariant")
+}
+
 #[proc_macro_attribute]
 pub fn invariant(invariant: TS1, loopb: TS1) -> TS1 {
     let inv: Invariant = parse_macro_input!(invariant);
+
+    // The invariants of a `for` loop are attached to the loop we desugar it into
+    if let Ok(for_expr) = syn::parse::<ExprForLoop>(loopb.clone()) {
+        return match for_loop::desugar_for_loop(inv, for_expr) {
+            Ok(r) => TS1::from(r),
+            Err(err) => TS1::from(err.to_compile_error()),
+        };
+    }
+
+    if let Ok(item @ (Item::Struct(_) | Item::Enum(_))) = syn::parse::<Item>(loopb.clone()) {
+        return match type_invariant::type_invariant(inv, item) {
+            Ok(r) => TS1::from(r),
+            Err(err) => TS1::from(err.to_compile_error()),
+        };
+    }
+
     let term = inv.invariant;
 
     let inv_body = pretyping::encode_term(term).unwrap();
@@ -525,6 +572,15 @@ pub fn trusted(_: TS1, tokens: TS1) -> TS1 {
     })
 }
 
+#[proc_macro_attribute]
+pub fn inline_body(_: TS1, tokens: TS1) -> TS1 {
+    let tokens = TokenStream::from(tokens);
+    TS1::from(quote! {
+        #[creusot::decl::inline_body]
+        #tokens
+    })
+}
+
 #[proc_macro]
 pub fn pearlite(tokens: TS1) -> TS1 {
     let term: Term = parse_macro_input!(tokens);
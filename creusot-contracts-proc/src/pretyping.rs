@@ -1,6 +1,6 @@
 use pearlite_syn::Term as RT;
 use proc_macro2::{Span, TokenStream};
-use syn::{spanned::Spanned, Pat};
+use syn::{punctuated::Punctuated, spanned::Spanned, Pat, PatIdent, Token};
 
 use pearlite_syn::term::*;
 use quote::{quote, quote_spanned, ToTokens};
@@ -176,29 +176,11 @@ pub fn encode_term(term: RT) -> Result<TokenStream, EncodeError> {
                 creusot_contracts::stubs::implication(#hyp, #cons)
             })
         }
-        RT::Forall(TermForall { args, term, .. }) => {
-            let mut ts = encode_term(*term)?;
-            for arg in args {
-                ts = quote! {
-                    creusot_contracts::stubs::forall(
-                        #[creusot::no_translate]
-                        |#arg|{ #ts }
-                    )
-                }
-            }
-            Ok(ts)
+        RT::Forall(TermForall { args, trigger, term, .. }) => {
+            encode_quant(quote! { forall }, args, trigger, *term)
         }
-        RT::Exists(TermExists { args, term, .. }) => {
-            let mut ts = encode_term(*term)?;
-            for arg in args {
-                ts = quote! {
-                    creusot_contracts::stubs::exists(
-                        #[creusot::no_translate]
-                        |#arg|{ #ts }
-                    )
-                }
-            }
-            Ok(ts)
+        RT::Exists(TermExists { args, trigger, term, .. }) => {
+            encode_quant(quote! { exists }, args, trigger, *term)
         }
         RT::Absurd(_) => Ok(quote! { creusot_contracts::stubs::abs() }),
         RT::Pearlite(term) => Ok(quote! { (#term) }),
@@ -230,6 +212,55 @@ fn encode_stmt(stmt: TermStmt) -> Result<TokenStream, EncodeError> {
     }
 }
 
+// A quantifier over several binders is encoded as nested quantifiers, the first binder being the
+// outermost one. Binders which are not simple identifiers are destructured by a `let`.
+fn encode_quant(
+    quant: TokenStream,
+    args: Punctuated<QuantArg, Token![,]>,
+    trigger: Option<QuantTrigger>,
+    term: RT,
+) -> Result<TokenStream, EncodeError> {
+    let destructured = |arg: &QuantArg| {
+        !matches!(&arg.pat, Pat::Ident(PatIdent { by_ref: None, subpat: None, .. }))
+    };
+    if let (Some(trigger), Some(arg)) = (&trigger, args.last()) {
+        if destructured(arg) {
+            let msg = "triggers on a quantifier whose last binder is destructured".into();
+            return Err(EncodeError::Unsupported(trigger.bracket_token.span, msg));
+        }
+    }
+
+    let mut ts = encode_trigger(trigger, encode_term(term)?)?;
+    for arg in args.into_iter().rev() {
+        let QuantArg { pat, ty, .. } = &arg;
+        let closure = if destructured(&arg) {
+            quote! { |__creusot_quant : #ty| { let #pat = __creusot_quant; #ts } }
+        } else {
+            quote! { |#pat : #ty| { #ts } }
+        };
+        ts = quote! {
+            creusot_contracts::stubs::#quant(
+                #[creusot::no_translate]
+                #closure
+            )
+        }
+    }
+    Ok(ts)
+}
+
+// The trigger of a quantifier is attached to the body of its innermost closure
+fn encode_trigger(
+    trigger: Option<QuantTrigger>,
+    body: TokenStream,
+) -> Result<TokenStream, EncodeError> {
+    let trigger = match trigger {
+        Some(trigger) => trigger,
+        None => return Ok(body),
+    };
+    let pats: Vec<_> = trigger.terms.into_iter().map(encode_term).collect::<Result<_, _>>()?;
+    Ok(quote! { creusot_contracts::stubs::trigger((#(#pats,)*), #body) })
+}
+
 fn encode_pattern(pat: Pat) -> Result<TokenStream, EncodeError> {
     Ok(quote! { #pat })
 }
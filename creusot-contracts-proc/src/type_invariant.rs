@@ -0,0 +1,52 @@
+// Invariants attached to the declaration of a type.
+//
+//     #[invariant(name, inv)]
+//     struct S { ... }
+//
+// becomes
+//
+//     struct S { ... }
+//     impl TypeInvariant for S {
+//         #[predicate]
+//         fn invariant(self) -> bool { pearlite! { inv } }
+//     }
+//
+// Several invariants on the same type are joined into a single predicate.
+
+use crate::{is_invariant, Invariant};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse::Result, *};
+
+pub fn type_invariant(inv: Invariant, mut item: Item) -> Result<TokenStream> {
+    let (attrs, ident, generics) = match &mut item {
+        Item::Struct(ItemStruct { attrs, ident, generics, .. })
+        | Item::Enum(ItemEnum { attrs, ident, generics, .. }) => (attrs, ident.clone(), generics),
+        _ => unreachable!(),
+    };
+
+    let mut invariants = vec![inv.invariant];
+    let mut other_attrs = Vec::new();
+    for attr in std::mem::take(attrs) {
+        if is_invariant(&attr) {
+            invariants.push(attr.parse_args::<Invariant>()?.invariant);
+        } else {
+            other_attrs.push(attr);
+        }
+    }
+    *attrs = other_attrs;
+
+    let generics = generics.clone();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        #item
+
+        impl #impl_generics creusot_contracts::TypeInvariant for #ident #ty_generics #where_clause {
+            #[creusot_contracts::predicate]
+            fn invariant(self) -> bool {
+                creusot_contracts::pearlite! { #((#invariants))&&* }
+            }
+        }
+    })
+}
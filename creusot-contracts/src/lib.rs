@@ -2,7 +2,7 @@
     feature = "contracts",
     feature(unsized_locals, fn_traits, unboxed_closures, min_specialization, allocator_api),
     allow(incomplete_features),
-    feature(slice_take)
+    feature(slice_take, step_trait)
 )]
 #![cfg_attr(feature = "typechecker", feature(rustc_private), feature(box_patterns, box_syntax))]
 
@@ -44,6 +44,10 @@ mod macros {
     /// valid.
     pub use creusot_contracts_proc::trusted;
 
+    /// Instructs Creusot to inline the body of a small function at its call sites instead of
+    /// calling it through its contract. The function must not have preconditions.
+    pub use creusot_contracts_proc::inline_body;
+
     /// Declares a variant for a function, this is primarily used in combination with logical functions
     /// The variant must be an expression which returns a type implementing [WellFounded]
     pub use creusot_contracts_proc::variant;
@@ -102,6 +106,10 @@ mod macros {
     /// valid.
     pub use creusot_contracts_dummy::trusted;
 
+    /// Instructs Creusot to inline the body of a small function at its call sites instead of
+    /// calling it through its contract. The function must not have preconditions.
+    pub use creusot_contracts_dummy::inline_body;
+
     /// Declares a variant for a function, this is primarily used in combination with logical functions
     /// The variant must be an expression which returns a type implementing [WellFounded]
     pub use creusot_contracts_dummy::variant;
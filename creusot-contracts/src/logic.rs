@@ -1,17 +1,29 @@
+mod bag;
+mod float;
+mod fmap;
 mod ghost;
 mod int;
 mod mapping;
 mod model;
+mod nat;
 pub mod ord;
 mod resolve;
 mod seq;
+mod set;
+mod type_invariant;
 pub mod well_founded;
 
+pub use bag::*;
+pub use float::*;
+pub use fmap::*;
 pub use ghost::*;
 pub use int::*;
 pub use mapping::*;
 pub use model::*;
+pub use nat::*;
 pub use ord::*;
 pub use resolve::*;
 pub use seq::*;
+pub use set::*;
+pub use type_invariant::*;
 pub use well_founded::*;
@@ -0,0 +1,108 @@
+use crate as creusot_contracts;
+use creusot_contracts_proc::*;
+
+use crate::Int;
+
+/// A finite multiset, mapped to the `bag.Bag` theory of Why3
+#[creusot::builtins = "bag.Bag.bag"]
+pub struct FBag<T: ?Sized>(std::marker::PhantomData<T>);
+
+impl<T: ?Sized> Copy for FBag<T> {}
+impl<T: ?Sized> Clone for FBag<T> {
+    #[logic]
+    fn clone(&self) -> Self {
+        pearlite! { *self }
+    }
+}
+
+impl<T> FBag<T> {
+    #[trusted]
+    #[creusot::builtins = "bag.Bag.empty_bag"]
+    pub const EMPTY: Self = { FBag(std::marker::PhantomData) };
+
+    #[logic]
+    pub fn new() -> Self {
+        Self::EMPTY
+    }
+
+    // The functions of Why3 take the element before the bag, so they are wrapped to be methods
+    /// The number of occurrences of `e` in the bag
+    #[logic]
+    pub fn occ(self, e: T) -> Int {
+        Self::nb_occ(e, self)
+    }
+
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "bag.Bag.nb_occ"]
+    fn nb_occ(_: T, _: Self) -> Int {
+        absurd
+    }
+
+    #[predicate]
+    pub fn contains(self, e: T) -> bool {
+        Self::mem(e, self)
+    }
+
+    #[trusted]
+    #[predicate]
+    #[creusot::builtins = "bag.Bag.mem"]
+    fn mem(_: T, _: Self) -> bool {
+        absurd
+    }
+
+    /// Adds one occurrence of `e` to the bag
+    #[logic]
+    pub fn insert(self, e: T) -> Self {
+        Self::add(e, self)
+    }
+
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "bag.Bag.add"]
+    fn add(_: T, _: Self) -> Self {
+        absurd
+    }
+
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "bag.Bag.singleton"]
+    pub fn singleton(_: T) -> Self {
+        absurd
+    }
+
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "bag.Bag.union"]
+    pub fn union(self, _: Self) -> Self {
+        absurd
+    }
+
+    /// Removes from `self` as many occurrences of each element as there are in the other bag
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "bag.Bag.diff"]
+    pub fn difference(self, _: Self) -> Self {
+        absurd
+    }
+
+    /// The total number of occurrences of elements in the bag
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "bag.Bag.card"]
+    pub fn len(self) -> Int {
+        absurd
+    }
+
+    #[predicate]
+    pub fn is_empty(self) -> bool {
+        pearlite! { self.len() == 0 }
+    }
+
+    #[trusted]
+    #[predicate]
+    #[creusot::builtins = "bag.Bag.eq_bag"]
+    pub fn ext_eq(self, _: Self) -> bool {
+        absurd
+    }
+}
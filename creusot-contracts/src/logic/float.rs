@@ -0,0 +1,48 @@
+use crate as creusot_contracts;
+use creusot_contracts_proc::*;
+
+/// Classification of floating point values, only usable in specifications
+///
+/// The methods are not named after `f32::is_finite` and friends, which are program functions and
+/// would take precedence over them in method calls.
+pub trait FloatClass {
+    /// Neither infinite nor NaN
+    #[predicate]
+    fn finite(self) -> bool;
+
+    #[predicate]
+    fn infinite(self) -> bool;
+
+    #[predicate]
+    fn nan(self) -> bool;
+}
+
+macro_rules! float_class {
+    ($t:ty, $module:expr) => {
+        impl FloatClass for $t {
+            #[trusted]
+            #[predicate]
+            #[creusot::builtins = concat!($module, ".is_finite")]
+            fn finite(self) -> bool {
+                absurd
+            }
+
+            #[trusted]
+            #[predicate]
+            #[creusot::builtins = concat!($module, ".is_infinite")]
+            fn infinite(self) -> bool {
+                absurd
+            }
+
+            #[trusted]
+            #[predicate]
+            #[creusot::builtins = concat!($module, ".is_nan")]
+            fn nan(self) -> bool {
+                absurd
+            }
+        }
+    };
+}
+
+float_class!(f32, "prelude.Float32");
+float_class!(f64, "prelude.Float64");
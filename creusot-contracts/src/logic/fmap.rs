@@ -0,0 +1,108 @@
+use crate as creusot_contracts;
+use creusot_contracts_proc::*;
+
+use crate::Int;
+
+/// A finite map, mapped to the `fmap.Fmap` theory of Why3
+#[creusot::builtins = "fmap.Fmap.fmap"]
+pub struct FMap<K, V: ?Sized>(std::marker::PhantomData<(K, V)>);
+
+impl<K, V: ?Sized> Copy for FMap<K, V> {}
+impl<K, V: ?Sized> Clone for FMap<K, V> {
+    #[logic]
+    fn clone(&self) -> Self {
+        pearlite! { *self }
+    }
+}
+
+impl<K, V> FMap<K, V> {
+    #[trusted]
+    #[creusot::builtins = "fmap.Fmap.empty"]
+    pub const EMPTY: Self = { FMap(std::marker::PhantomData) };
+
+    #[logic]
+    pub fn new() -> Self {
+        Self::EMPTY
+    }
+
+    /// The value associated with `k`, if there is one
+    #[logic]
+    pub fn get(self, k: K) -> Option<V> {
+        if self.contains(k) {
+            Some(self.lookup(k))
+        } else {
+            None
+        }
+    }
+
+    // The functions of Why3 take the key before the map, so they are wrapped to be methods
+    #[predicate]
+    pub fn contains(self, k: K) -> bool {
+        Self::mem(k, self)
+    }
+
+    #[trusted]
+    #[predicate]
+    #[creusot::builtins = "fmap.Fmap.mem"]
+    fn mem(_: K, _: Self) -> bool {
+        absurd
+    }
+
+    /// The value associated with `k`, unspecified if there is none
+    #[logic]
+    pub fn lookup(self, k: K) -> V {
+        Self::find(k, self)
+    }
+
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "fmap.Fmap.find"]
+    fn find(_: K, _: Self) -> V {
+        absurd
+    }
+
+    #[logic]
+    pub fn insert(self, k: K, v: V) -> Self {
+        Self::add(k, v, self)
+    }
+
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "fmap.Fmap.add"]
+    fn add(_: K, _: V, _: Self) -> Self {
+        absurd
+    }
+
+    #[logic]
+    pub fn remove(self, k: K) -> Self {
+        Self::rem(k, self)
+    }
+
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "fmap.Fmap.remove"]
+    fn rem(_: K, _: Self) -> Self {
+        absurd
+    }
+
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "fmap.Fmap.size"]
+    pub fn len(self) -> Int {
+        absurd
+    }
+
+    #[trusted]
+    #[predicate]
+    #[creusot::builtins = "fmap.Fmap.is_empty"]
+    pub fn is_empty(self) -> bool {
+        absurd
+    }
+
+    #[trusted]
+    #[predicate]
+    #[creusot::builtins = "fmap.Fmap.(==)"]
+    pub fn ext_eq(self, _: Self) -> bool {
+        absurd
+    }
+}
@@ -30,6 +30,8 @@ macro_rules! mach_int {
             }
         }
 
+        impl EqModel for $t {}
+
         impl From<$t> for Int {
             #[logic]
             #[trusted]
@@ -38,6 +40,36 @@ macro_rules! mach_int {
                 absurd
             }
         }
+
+        mixed_int_op!($t, Add, add, "add_int");
+        mixed_int_op!($t, Sub, sub, "sub_int");
+        mixed_int_op!($t, Mul, mul, "mul_int");
+        mixed_int_op!($t, Div, div, "div_int");
+        mixed_int_op!($t, Rem, rem, "rem_int");
+    };
+}
+
+// Arithmetic mixing an `Int` and a machine integer coerces the latter to its model, so that
+// `x + 1` is an `Int` which cannot overflow when `x: u32`
+macro_rules! mixed_int_op {
+    ($t:ty, $trait:ident, $method:ident, $builtin:expr) => {
+        impl $trait<$t> for Int {
+            type Output = Int;
+            #[creusot::no_translate]
+            #[creusot::builtins = $builtin]
+            fn $method(self, _: $t) -> Int {
+                panic!()
+            }
+        }
+
+        impl $trait<Int> for $t {
+            type Output = Int;
+            #[creusot::no_translate]
+            #[creusot::builtins = $builtin]
+            fn $method(self, _: Int) -> Int {
+                panic!()
+            }
+        }
     };
 }
 
@@ -55,6 +87,19 @@ mach_int!(i64, "mach.int.Int64");
 mach_int!(i128, "prelude.Int128");
 mach_int!(isize, "mach.int.Int64");
 
+// Chars are modeled by their code, a Unicode scalar value
+impl Model for char {
+    type ModelTy = Int;
+    #[logic]
+    #[trusted]
+    #[creusot::builtins = "prelude.Char.code"]
+    fn model(self) -> Self::ModelTy {
+        absurd
+    }
+}
+
+impl EqModel for char {}
+
 impl Add<Int> for Int {
     type Output = Int;
     #[creusot::no_translate]
@@ -7,6 +7,11 @@ pub trait Model {
     fn model(self) -> Self::ModelTy;
 }
 
+/// Types whose `Eq` implementation agrees with the equality of their models: two values are
+/// equal exactly when their models are. The models of collections which are keyed by the models
+/// of their elements, such as `HashMap`, rely on it.
+pub trait EqModel: Model {}
+
 impl<T: Model + ?Sized> Model for &T {
     type ModelTy = T::ModelTy;
     #[logic]
@@ -15,6 +20,8 @@ impl<T: Model + ?Sized> Model for &T {
     }
 }
 
+impl<T: EqModel + ?Sized> EqModel for &T {}
+
 impl<T: Model + ?Sized> Model for &mut T {
     type ModelTy = T::ModelTy;
     #[logic]
@@ -42,6 +49,8 @@ impl<T: Model, U: Model> Model for (T, U) {
     }
 }
 
+impl<T: EqModel, U: EqModel> EqModel for (T, U) {}
+
 impl Model for bool {
     type ModelTy = bool;
 
@@ -51,6 +60,8 @@ impl Model for bool {
     }
 }
 
+impl EqModel for bool {}
+
 impl<T: Model> Model for Option<T> {
     type ModelTy = Option<T::ModelTy>;
 
@@ -62,3 +73,5 @@ impl<T: Model> Model for Option<T> {
         }
     }
 }
+
+impl<T: EqModel> EqModel for Option<T> {}
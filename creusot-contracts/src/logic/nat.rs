@@ -0,0 +1,31 @@
+use super::model::*;
+use crate as creusot_contracts;
+use crate::logic::*;
+use creusot_contracts_proc::*;
+
+/// Mathematical natural numbers, only usable in specifications
+///
+/// Machine integers are coerced to [Int] when combined with one by an arithmetic operator, as in
+/// `x + 1`; use [Nat::from_int] to get a `Nat`.
+#[creusot::builtins = "prelude.Nat.nat"]
+pub struct Nat(*mut ());
+
+impl Nat {
+    /// The natural number `i`, which is only specified when `i` is non-negative
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "prelude.Nat.of_int"]
+    pub fn from_int(_: Int) -> Nat {
+        absurd
+    }
+}
+
+impl Model for Nat {
+    type ModelTy = Int;
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "prelude.Nat.to_int"]
+    fn model(self) -> Self::ModelTy {
+        absurd
+    }
+}
@@ -88,6 +88,11 @@ impl<T> Seq<T> {
         absurd
     }
 
+    #[predicate]
+    pub fn contains(self, e: T) -> bool {
+        pearlite! { exists<i : Int> 0 <= i && i < self.len() && self[i] == e }
+    }
+
     #[predicate]
     pub fn permutation_of(self, o: Self) -> bool {
         self.permut(o, 0, self.len())
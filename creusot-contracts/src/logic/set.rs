@@ -0,0 +1,228 @@
+use crate as creusot_contracts;
+use creusot_contracts_proc::*;
+
+use crate::Int;
+
+/// A finite set, mapped to the `set.Fset` theory of Why3
+#[creusot::builtins = "set.Fset.fset"]
+pub struct FSet<T: ?Sized>(std::marker::PhantomData<T>);
+
+impl<T: ?Sized> Copy for FSet<T> {}
+impl<T: ?Sized> Clone for FSet<T> {
+    #[logic]
+    fn clone(&self) -> Self {
+        pearlite! { *self }
+    }
+}
+
+impl<T> FSet<T> {
+    #[trusted]
+    #[creusot::builtins = "set.Fset.empty"]
+    pub const EMPTY: Self = { FSet(std::marker::PhantomData) };
+
+    #[logic]
+    pub fn new() -> Self {
+        Self::EMPTY
+    }
+
+    // The functions of Why3 take the element before the set, so they are wrapped to be methods
+    #[predicate]
+    pub fn contains(self, e: T) -> bool {
+        Self::mem(e, self)
+    }
+
+    #[trusted]
+    #[predicate]
+    #[creusot::builtins = "set.Fset.mem"]
+    fn mem(_: T, _: Self) -> bool {
+        absurd
+    }
+
+    #[logic]
+    pub fn insert(self, e: T) -> Self {
+        Self::add(e, self)
+    }
+
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "set.Fset.add"]
+    fn add(_: T, _: Self) -> Self {
+        absurd
+    }
+
+    #[logic]
+    pub fn remove(self, e: T) -> Self {
+        Self::rem(e, self)
+    }
+
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "set.Fset.remove"]
+    fn rem(_: T, _: Self) -> Self {
+        absurd
+    }
+
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "set.Fset.singleton"]
+    pub fn singleton(_: T) -> Self {
+        absurd
+    }
+
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "set.Fset.union"]
+    pub fn union(self, _: Self) -> Self {
+        absurd
+    }
+
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "set.Fset.inter"]
+    pub fn intersection(self, _: Self) -> Self {
+        absurd
+    }
+
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "set.Fset.diff"]
+    pub fn difference(self, _: Self) -> Self {
+        absurd
+    }
+
+    #[trusted]
+    #[predicate]
+    #[creusot::builtins = "set.Fset.subset"]
+    pub fn is_subset(self, _: Self) -> bool {
+        absurd
+    }
+
+    #[trusted]
+    #[predicate]
+    #[creusot::builtins = "set.Fset.is_empty"]
+    pub fn is_empty(self) -> bool {
+        absurd
+    }
+
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "set.Fset.cardinal"]
+    pub fn len(self) -> Int {
+        absurd
+    }
+
+    #[trusted]
+    #[predicate]
+    #[creusot::builtins = "set.Fset.(==)"]
+    pub fn ext_eq(self, _: Self) -> bool {
+        absurd
+    }
+}
+
+/// A possibly infinite set, mapped to the `set.Set` theory of Why3
+#[creusot::builtins = "set.Set.set"]
+pub struct Set<T: ?Sized>(std::marker::PhantomData<T>);
+
+impl<T: ?Sized> Copy for Set<T> {}
+impl<T: ?Sized> Clone for Set<T> {
+    #[logic]
+    fn clone(&self) -> Self {
+        pearlite! { *self }
+    }
+}
+
+impl<T> Set<T> {
+    #[trusted]
+    #[creusot::builtins = "set.Set.empty"]
+    pub const EMPTY: Self = { Set(std::marker::PhantomData) };
+
+    #[logic]
+    pub fn new() -> Self {
+        Self::EMPTY
+    }
+
+    #[predicate]
+    pub fn contains(self, e: T) -> bool {
+        Self::mem(e, self)
+    }
+
+    #[trusted]
+    #[predicate]
+    #[creusot::builtins = "set.Set.mem"]
+    fn mem(_: T, _: Self) -> bool {
+        absurd
+    }
+
+    #[logic]
+    pub fn insert(self, e: T) -> Self {
+        Self::add(e, self)
+    }
+
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "set.Set.add"]
+    fn add(_: T, _: Self) -> Self {
+        absurd
+    }
+
+    #[logic]
+    pub fn remove(self, e: T) -> Self {
+        Self::rem(e, self)
+    }
+
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "set.Set.remove"]
+    fn rem(_: T, _: Self) -> Self {
+        absurd
+    }
+
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "set.Set.singleton"]
+    pub fn singleton(_: T) -> Self {
+        absurd
+    }
+
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "set.Set.union"]
+    pub fn union(self, _: Self) -> Self {
+        absurd
+    }
+
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "set.Set.inter"]
+    pub fn intersection(self, _: Self) -> Self {
+        absurd
+    }
+
+    #[trusted]
+    #[logic]
+    #[creusot::builtins = "set.Set.diff"]
+    pub fn difference(self, _: Self) -> Self {
+        absurd
+    }
+
+    #[trusted]
+    #[predicate]
+    #[creusot::builtins = "set.Set.subset"]
+    pub fn is_subset(self, _: Self) -> bool {
+        absurd
+    }
+
+    #[trusted]
+    #[predicate]
+    #[creusot::builtins = "set.Set.is_empty"]
+    pub fn is_empty(self) -> bool {
+        absurd
+    }
+
+    #[trusted]
+    #[predicate]
+    #[creusot::builtins = "set.Set.(==)"]
+    pub fn ext_eq(self, _: Self) -> bool {
+        absurd
+    }
+}
@@ -0,0 +1,22 @@
+use crate as creusot_contracts;
+use crate::logic::model::*;
+use creusot_contracts_proc::*;
+
+/// The invariant of a type, declared with `#[invariant(name, ...)]` on the type.
+///
+/// Program functions assume the invariants of their arguments and must establish the invariant
+/// of their result, behind mutable borrows both before and after the call.
+#[rustc_diagnostic_item = "creusot_type_invariant"]
+pub trait TypeInvariant {
+    #[predicate]
+    #[rustc_diagnostic_item = "creusot_type_invariant_method"]
+    fn invariant(self) -> bool;
+}
+
+// The sequence modelling an array does not record its length, which is only known from its type
+impl<T, const N: usize> TypeInvariant for [T; N] {
+    #[predicate]
+    fn invariant(self) -> bool {
+        pearlite! { (@self).len() == @N }
+    }
+}
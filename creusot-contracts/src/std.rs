@@ -1,14 +1,19 @@
 pub mod clone;
 pub mod cmp;
+pub mod collections;
 pub mod default;
 pub mod eq;
 mod fun;
+pub mod iter;
 pub mod mem;
 pub mod option;
+pub mod result;
 mod slice;
+mod string;
 pub mod vec;
 
 pub use clone::*;
 pub use eq::*;
 pub use fun::*;
+pub use iter::*;
 pub use vec::*;
@@ -0,0 +1,222 @@
+use crate as creusot_contracts;
+use crate::logic::*;
+use crate::{EqModel, Model, Seq};
+use creusot_contracts_proc::*;
+
+use std::alloc::Allocator;
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, VecDeque};
+use std::hash::{BuildHasher, Hash};
+
+// Maps are modeled as finite maps over the models of their keys, so two keys must be the same
+// entry of the map exactly when their models are equal. This is what `EqModel` guarantees.
+impl<K: EqModel, V, S> Model for HashMap<K, V, S> {
+    type ModelTy = FMap<K::ModelTy, V>;
+
+    #[logic]
+    #[trusted]
+    fn model(self) -> Self::ModelTy {
+        pearlite! { absurd }
+    }
+}
+
+impl<K: EqModel, V> Model for BTreeMap<K, V> {
+    type ModelTy = FMap<K::ModelTy, V>;
+
+    #[logic]
+    #[trusted]
+    fn model(self) -> Self::ModelTy {
+        pearlite! { absurd }
+    }
+}
+
+impl<T, A: Allocator> Model for VecDeque<T, A> {
+    type ModelTy = Seq<T>;
+
+    #[logic]
+    #[trusted]
+    #[ensures(result.len() <= @usize::MAX)]
+    fn model(self) -> Self::ModelTy {
+        pearlite! { absurd }
+    }
+}
+
+// The order of the elements of a heap is unspecified, so it is modeled as a multiset.
+impl<T> Model for BinaryHeap<T> {
+    type ModelTy = FBag<T>;
+
+    #[logic]
+    #[trusted]
+    #[ensures(result.len() <= @usize::MAX)]
+    fn model(self) -> Self::ModelTy {
+        pearlite! { absurd }
+    }
+}
+
+/// `e` is a greatest element of the heap `h`, according to the models of its elements
+#[predicate]
+pub fn is_max<T: Model>(h: FBag<T>, e: T) -> bool
+where
+    T::ModelTy: OrdLogic,
+{
+    pearlite! { h.contains(e) && forall<x : T> h.contains(x) ==> @x <= @e }
+}
+
+extern_spec! {
+    mod std {
+        mod collections {
+            impl<K : EqModel, V> HashMap<K, V> {
+                #[ensures((@result).is_empty())]
+                fn new() -> HashMap<K, V>;
+
+                #[ensures((@result).is_empty())]
+                fn with_capacity(capacity: usize) -> HashMap<K, V>;
+            }
+
+            impl<K : EqModel, V, S> HashMap<K, V, S> {
+                #[ensures(@result == (@*self).len())]
+                fn len(&self) -> usize;
+
+                #[ensures(result == (@*self).is_empty())]
+                fn is_empty(&self) -> bool;
+            }
+
+            impl<K : EqModel + Eq + Hash, V, S : BuildHasher> HashMap<K, V, S> {
+                #[ensures(@^self == (@*self).insert(@k, v))]
+                #[ensures(result == (@*self).get(@k))]
+                fn insert(&mut self, k: K, v: V) -> Option<V>;
+
+                #[ensures(match result {
+                    Some(v) => (@*self).get(@k) == Some(*v),
+                    None => !(@*self).contains(@k),
+                })]
+                fn get<Q : ?Sized>(&self, k: &Q) -> Option<&V>
+                    where K : Borrow<Q>, Q : Hash + Eq + EqModel<ModelTy = K::ModelTy>;
+
+                #[ensures(result == (@*self).contains(@k))]
+                fn contains_key<Q : ?Sized>(&self, k: &Q) -> bool
+                    where K : Borrow<Q>, Q : Hash + Eq + EqModel<ModelTy = K::ModelTy>;
+
+                #[ensures(@^self == (@*self).remove(@k))]
+                #[ensures(result == (@*self).get(@k))]
+                fn remove<Q : ?Sized>(&mut self, k: &Q) -> Option<V>
+                    where K : Borrow<Q>, Q : Hash + Eq + EqModel<ModelTy = K::ModelTy>;
+            }
+
+            impl<K : EqModel, V> BTreeMap<K, V> {
+                #[ensures((@result).is_empty())]
+                fn new() -> BTreeMap<K, V>;
+
+                #[ensures(@result == (@*self).len())]
+                fn len(&self) -> usize;
+
+                #[ensures(result == (@*self).is_empty())]
+                fn is_empty(&self) -> bool;
+
+                #[ensures(@^self == (@*self).insert(@k, v))]
+                #[ensures(result == (@*self).get(@k))]
+                fn insert(&mut self, k: K, v: V) -> Option<V> where K : Ord;
+
+                #[ensures(match result {
+                    Some(v) => (@*self).get(@k) == Some(*v),
+                    None => !(@*self).contains(@k),
+                })]
+                fn get<Q : ?Sized>(&self, k: &Q) -> Option<&V>
+                    where K : Borrow<Q> + Ord, Q : Ord + EqModel<ModelTy = K::ModelTy>;
+
+                #[ensures(result == (@*self).contains(@k))]
+                fn contains_key<Q : ?Sized>(&self, k: &Q) -> bool
+                    where K : Borrow<Q> + Ord, Q : Ord + EqModel<ModelTy = K::ModelTy>;
+
+                #[ensures(@^self == (@*self).remove(@k))]
+                #[ensures(result == (@*self).get(@k))]
+                fn remove<Q : ?Sized>(&mut self, k: &Q) -> Option<V>
+                    where K : Borrow<Q> + Ord, Q : Ord + EqModel<ModelTy = K::ModelTy>;
+            }
+
+            impl<T> VecDeque<T> {
+                #[ensures((@result).len() == 0)]
+                fn new() -> VecDeque<T>;
+
+                #[ensures((@result).len() == 0)]
+                fn with_capacity(capacity: usize) -> VecDeque<T>;
+            }
+
+            impl<T, A : Allocator> VecDeque<T, A> {
+                #[ensures(@result == (@*self).len())]
+                fn len(&self) -> usize;
+
+                #[ensures(result == ((@*self).len() == 0))]
+                fn is_empty(&self) -> bool;
+
+                #[ensures((@^self).len() == 0)]
+                fn clear(&mut self);
+
+                #[ensures(@^self == (@*self).push(v))]
+                fn push_back(&mut self, v: T);
+
+                #[ensures(@^self == Seq::singleton(v).concat(@*self))]
+                fn push_front(&mut self, v: T);
+
+                #[ensures(match result {
+                    Some(t) =>
+                        (@^self) == (@*self).subsequence(0, (@*self).len() - 1) &&
+                        (@*self) == (@^self).push(t),
+                    None => *self == ^self && (@*self).len() == 0
+                })]
+                fn pop_back(&mut self) -> Option<T>;
+
+                #[ensures(match result {
+                    Some(t) =>
+                        (@^self) == (@*self).tail() &&
+                        (@*self) == Seq::singleton(t).concat(@^self),
+                    None => *self == ^self && (@*self).len() == 0
+                })]
+                fn pop_front(&mut self) -> Option<T>;
+
+                #[ensures(match result {
+                    Some(t) => (@*self).len() > 0 && *t == (@*self)[0],
+                    None => (@*self).len() == 0
+                })]
+                fn front(&self) -> Option<&T>;
+
+                #[ensures(match result {
+                    Some(t) => (@*self).len() > 0 && *t == (@*self)[(@*self).len() - 1],
+                    None => (@*self).len() == 0
+                })]
+                fn back(&self) -> Option<&T>;
+            }
+
+            impl<T : Ord + Model> BinaryHeap<T> where T::ModelTy : OrdLogic {
+                #[ensures((@result).is_empty())]
+                fn new() -> BinaryHeap<T>;
+
+                #[ensures((@result).is_empty())]
+                fn with_capacity(capacity: usize) -> BinaryHeap<T>;
+
+                #[ensures(@^self == (@*self).insert(v))]
+                fn push(&mut self, v: T);
+
+                #[ensures(match result {
+                    Some(t) => is_max(@*self, t) && (@*self) == (@^self).insert(t),
+                    None => *self == ^self && (@*self).is_empty()
+                })]
+                fn pop(&mut self) -> Option<T>;
+            }
+
+            impl<T : Model> BinaryHeap<T> where T::ModelTy : OrdLogic {
+                #[ensures(match result {
+                    Some(t) => is_max(@*self, *t),
+                    None => (@*self).is_empty()
+                })]
+                fn peek(&self) -> Option<&T>;
+
+                #[ensures(@result == (@*self).len())]
+                fn len(&self) -> usize;
+
+                #[ensures(result == (@*self).is_empty())]
+                fn is_empty(&self) -> bool;
+            }
+        }
+    }
+}
@@ -0,0 +1,290 @@
+use crate as creusot_contracts;
+use crate::std::fun::FnMutSpec;
+use crate::{Int, Model, Resolve, Seq};
+use creusot_contracts_proc::*;
+use std::iter::{Enumerate, Map, Step};
+use std::ops::Range;
+use std::slice::{Iter, IterMut};
+
+/// Specification of an iterator, in terms of the sequences of elements it produces.
+///
+/// Calls to [Iterator::next] on an iterator implementing this trait either return `None` once
+/// the iterator is `completed`, or an element `v` such that the iterator `produces` the
+/// singleton `v` while going from its current state to its final state.
+///
+/// The laws are proved from the definition of `produces` of each implementation, which is why
+/// their bodies are empty.
+pub trait IteratorSpec: Iterator {
+    /// The iterator will return `None` from now on, and `next` leaves it unchanged
+    #[predicate]
+    fn completed(&mut self) -> bool;
+
+    /// Calling `next` on `self` until it becomes `o` returns the elements of `visited`
+    #[predicate]
+    fn produces(self, visited: Seq<Self::Item>, o: Self) -> bool;
+
+    #[law]
+    #[ensures(a.produces(Seq::EMPTY, a))]
+    fn produces_refl(a: Self);
+
+    #[law]
+    #[requires(a.produces(ab, b))]
+    #[requires(b.produces(bc, c))]
+    #[ensures(a.produces(ab.concat(bc), c))]
+    fn produces_trans(a: Self, ab: Seq<Self::Item>, b: Self, bc: Seq<Self::Item>, c: Self);
+}
+
+/// Iterating over `a` until it becomes `b` produces the elements of `visited`.
+/// This is how ranges implement [IteratorSpec::produces].
+#[predicate]
+pub fn range_produces<A: Model<ModelTy = Int>>(
+    a: Range<A>,
+    visited: Seq<A>,
+    b: Range<A>,
+) -> bool {
+    pearlite! {
+        a.end == b.end && @a.start <= @b.start
+        && visited.len() == @b.start - @a.start
+        && (visited.len() > 0 ==> @b.start <= @b.end)
+        && forall<i : Int> 0 <= i && i < visited.len() ==> @(visited[i]) == @a.start + i
+    }
+}
+
+impl<A: Step + Model<ModelTy = Int>> IteratorSpec for Range<A> {
+    #[predicate]
+    fn completed(&mut self) -> bool {
+        pearlite! { ^self == *self && @(*self).start >= @(*self).end }
+    }
+
+    #[predicate]
+    fn produces(self, visited: Seq<A>, o: Self) -> bool {
+        range_produces(self, visited, o)
+    }
+
+    #[law]
+    #[ensures(a.produces(Seq::EMPTY, a))]
+    fn produces_refl(a: Self) {}
+
+    #[law]
+    #[requires(a.produces(ab, b))]
+    #[requires(b.produces(bc, c))]
+    #[ensures(a.produces(ab.concat(bc), c))]
+    fn produces_trans(a: Self, ab: Seq<A>, b: Self, bc: Seq<A>, c: Self) {}
+}
+
+impl<'a, T> Model for Iter<'a, T> {
+    type ModelTy = &'a [T];
+
+    #[logic]
+    #[trusted]
+    fn model(self) -> Self::ModelTy {
+        pearlite! { absurd }
+    }
+}
+
+impl<'a, T> IteratorSpec for Iter<'a, T> {
+    #[predicate]
+    fn completed(&mut self) -> bool {
+        pearlite! { ^self == *self && (@@*self).len() == 0 }
+    }
+
+    #[predicate]
+    fn produces(self, visited: Seq<&'a T>, o: Self) -> bool {
+        pearlite! {
+            (@@self).len() == visited.len() + (@@o).len()
+            && (@@self).subsequence(visited.len(), (@@self).len()).ext_eq(@@o)
+            && forall<i : Int> 0 <= i && i < visited.len() ==> (@@self)[i] == *visited[i]
+        }
+    }
+
+    #[law]
+    #[ensures(a.produces(Seq::EMPTY, a))]
+    fn produces_refl(a: Self) {}
+
+    #[law]
+    #[requires(a.produces(ab, b))]
+    #[requires(b.produces(bc, c))]
+    #[ensures(a.produces(ab.concat(bc), c))]
+    fn produces_trans(a: Self, ab: Seq<&'a T>, b: Self, bc: Seq<&'a T>, c: Self) {}
+}
+
+impl<'a, T> Model for IterMut<'a, T> {
+    type ModelTy = &'a mut [T];
+
+    #[logic]
+    #[trusted]
+    fn model(self) -> Self::ModelTy {
+        pearlite! { absurd }
+    }
+}
+
+impl<'a, T> IteratorSpec for IterMut<'a, T> {
+    #[predicate]
+    fn completed(&mut self) -> bool {
+        pearlite! { ^self == *self && (@*@*self).len() == 0 }
+    }
+
+    #[predicate]
+    fn produces(self, visited: Seq<&'a mut T>, o: Self) -> bool {
+        pearlite! {
+            (@*@self).len() == visited.len() + (@*@o).len()
+            && (@^@self).len() == visited.len() + (@^@o).len()
+            && (@*@self).subsequence(visited.len(), (@*@self).len()).ext_eq(@*@o)
+            && (@^@self).subsequence(visited.len(), (@^@self).len()).ext_eq(@^@o)
+            && forall<i : Int> 0 <= i && i < visited.len() ==>
+                (@*@self)[i] == *visited[i] && (@^@self)[i] == ^visited[i]
+        }
+    }
+
+    #[law]
+    #[ensures(a.produces(Seq::EMPTY, a))]
+    fn produces_refl(a: Self) {}
+
+    #[law]
+    #[requires(a.produces(ab, b))]
+    #[requires(b.produces(bc, c))]
+    #[ensures(a.produces(ab.concat(bc), c))]
+    fn produces_trans(a: Self, ab: Seq<&'a mut T>, b: Self, bc: Seq<&'a mut T>, c: Self) {}
+}
+
+/// The iterator wrapped by an [Enumerate]
+#[logic]
+#[trusted]
+pub fn enumerate_iter<I>(_: Enumerate<I>) -> I {
+    pearlite! { absurd }
+}
+
+/// The index of the next element returned by an [Enumerate]
+#[logic]
+#[trusted]
+pub fn enumerate_count<I>(_: Enumerate<I>) -> Int {
+    pearlite! { absurd }
+}
+
+impl<I: IteratorSpec> IteratorSpec for Enumerate<I> {
+    #[predicate]
+    fn completed(&mut self) -> bool {
+        pearlite! {
+            exists<inner : &mut I> *inner == enumerate_iter(*self)
+                && ^inner == enumerate_iter(^self)
+                && inner.completed()
+                && enumerate_count(^self) == enumerate_count(*self)
+        }
+    }
+
+    #[predicate]
+    fn produces(self, visited: Seq<(usize, I::Item)>, o: Self) -> bool {
+        pearlite! {
+            visited.len() == enumerate_count(o) - enumerate_count(self)
+            && exists<s : Seq<I::Item>> enumerate_iter(self).produces(s, enumerate_iter(o))
+                && visited.len() == s.len()
+                && forall<i : Int> 0 <= i && i < s.len() ==>
+                    @(visited[i].0) == enumerate_count(self) + i && visited[i].1 == s[i]
+        }
+    }
+
+    #[law]
+    #[ensures(a.produces(Seq::EMPTY, a))]
+    fn produces_refl(a: Self) {}
+
+    #[law]
+    #[requires(a.produces(ab, b))]
+    #[requires(b.produces(bc, c))]
+    #[ensures(a.produces(ab.concat(bc), c))]
+    fn produces_trans(
+        a: Self,
+        ab: Seq<(usize, I::Item)>,
+        b: Self,
+        bc: Seq<(usize, I::Item)>,
+        c: Self,
+    ) {
+    }
+}
+
+/// The iterator wrapped by a [Map]
+#[logic]
+#[trusted]
+pub fn map_iter<I, F>(_: Map<I, F>) -> I {
+    pearlite! { absurd }
+}
+
+/// The closure applied by a [Map] to the elements of its iterator
+#[logic]
+#[trusted]
+pub fn map_func<I, F>(_: Map<I, F>) -> F {
+    pearlite! { absurd }
+}
+
+impl<B, I: IteratorSpec, F: FnMut(I::Item) -> B> IteratorSpec for Map<I, F> {
+    #[predicate]
+    fn completed(&mut self) -> bool {
+        pearlite! {
+            exists<inner : &mut I> *inner == map_iter(*self)
+                && ^inner == map_iter(^self)
+                && inner.completed()
+                && map_func(^self) == map_func(*self)
+        }
+    }
+
+    // The closure goes through one state per produced element, each call to the closure
+    // mapping an element of the inner iterator and moving the closure to its next state.
+    #[predicate]
+    fn produces(self, visited: Seq<B>, o: Self) -> bool {
+        pearlite! {
+            exists<s : Seq<I::Item>, fs : Seq<&mut F>>
+                map_iter(self).produces(s, map_iter(o))
+                && s.len() == visited.len() && fs.len() == visited.len()
+                && (visited.len() == 0 ==> map_func(self) == map_func(o))
+                && (visited.len() > 0 ==>
+                    *fs[0] == map_func(self) && ^fs[visited.len() - 1] == map_func(o))
+                && (forall<i : Int> 0 <= i && i < visited.len() - 1 ==> ^fs[i] == *fs[i + 1])
+                && forall<i : Int> 0 <= i && i < visited.len() ==>
+                    fs[i].postcondition_mut((s[i],), visited[i])
+        }
+    }
+
+    #[law]
+    #[ensures(a.produces(Seq::EMPTY, a))]
+    fn produces_refl(a: Self) {}
+
+    #[law]
+    #[requires(a.produces(ab, b))]
+    #[requires(b.produces(bc, c))]
+    #[ensures(a.produces(ab.concat(bc), c))]
+    fn produces_trans(a: Self, ab: Seq<B>, b: Self, bc: Seq<B>, c: Self) {}
+}
+
+extern_spec! {
+    mod std {
+        mod iter {
+            trait Iterator where Self : IteratorSpec {
+                #[ensures(match result {
+                    None => self.completed(),
+                    Some(v) => (*self).produces(Seq::singleton(v), ^self),
+                })]
+                fn next(&mut self) -> Option<Self::Item>;
+
+                #[ensures(enumerate_iter(result) == self && enumerate_count(result) == 0)]
+                fn enumerate(self) -> Enumerate<Self>;
+
+                // `Map` does not track the preconditions of the closure, so it must have none
+                #[requires(forall<g : F, x : _> g.precondition((x,)))]
+                #[ensures(map_iter(result) == self && map_func(result) == f)]
+                fn map<B, F : FnMut(Self::Item) -> B>(self, f: F) -> Map<Self, F>;
+
+                // The closure is called on each element in turn, going through one state per
+                // element, and is dropped once the iterator is completed.
+                #[requires(forall<g : F, x : _> g.precondition((x,)))]
+                #[ensures(exists<done : &mut _, s : Seq<_>, fs : Seq<&mut F>>
+                    done.completed() && self.produces(s, *done) && fs.len() == s.len()
+                    && (s.len() == 0 ==> f.resolve())
+                    && (s.len() > 0 ==> *fs[0] == f && (^fs[s.len() - 1]).resolve())
+                    && (forall<i : Int> 0 <= i && i < s.len() - 1 ==> ^fs[i] == *fs[i + 1])
+                    && forall<i : Int> 0 <= i && i < s.len() ==>
+                        fs[i].postcondition_mut((s[i],), ())
+                )]
+                fn for_each<F : FnMut(Self::Item)>(self, f: F);
+            }
+        }
+    }
+}
@@ -1,15 +1,50 @@
 use crate as creusot_contracts;
 use crate::std::default::DefaultSpec;
+use crate::std::fun::FnOnceSpec;
 use creusot_contracts_proc::*;
 
 extern_spec! {
     mod std {
         mod option {
             impl<T> Option<T> {
+                #[ensures(result == (*self != None))]
+                fn is_some(&self) -> bool;
+
+                #[ensures(result == (*self == None))]
+                fn is_none(&self) -> bool;
+
                 #[requires(self != None)]
                 #[ensures(Some(result) == self)]
                 fn unwrap(self) -> T;
 
+                #[requires(self != None)]
+                #[ensures(Some(result) == self)]
+                fn expect(self, msg: &str) -> T;
+
+                #[ensures(self == None ==> result == default)]
+                #[ensures(self == None || self == Some(result))]
+                fn unwrap_or(self, default: T) -> T;
+
+                #[requires(match self {
+                    None => true,
+                    Some(t) => f.precondition((t,)),
+                })]
+                #[ensures(match self {
+                    None => result == None,
+                    Some(t) => exists<r: U> result == Some(r) && f.postcondition_once((t,), r),
+                })]
+                fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Option<U>;
+
+                #[requires(match self {
+                    None => true,
+                    Some(t) => f.precondition((t,)),
+                })]
+                #[ensures(match self {
+                    None => result == None,
+                    Some(t) => f.postcondition_once((t,), result),
+                })]
+                fn and_then<U, F: FnOnce(T) -> Option<U>>(self, f: F) -> Option<U>;
+
                 #[ensures(*self == None ==> result == None && ^self == None)]
                 #[ensures(*self == None || exists<r: &mut T> result == Some(r) && *self == Some(*r) && ^self == Some(^r))]
                 fn as_mut(&mut self) -> Option<&mut T>;
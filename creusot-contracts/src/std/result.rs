@@ -0,0 +1,35 @@
+use crate as creusot_contracts;
+use creusot_contracts_proc::*;
+use std::fmt::Debug;
+
+extern_spec! {
+    mod std {
+        mod result {
+            impl<T, E> Result<T, E> {
+                #[ensures(result == exists<t: T> *self == Ok(t))]
+                fn is_ok(&self) -> bool;
+
+                #[ensures(result == exists<e: E> *self == Err(e))]
+                fn is_err(&self) -> bool;
+
+                #[ensures(forall<t: T> self == Ok(t) ==> result == Some(t))]
+                #[ensures((exists<e: E> self == Err(e)) ==> result == None)]
+                fn ok(self) -> Option<T>;
+
+                #[ensures((exists<t: T> self == Ok(t)) ==> result == None)]
+                #[ensures(forall<e: E> self == Err(e) ==> result == Some(e))]
+                fn err(self) -> Option<E>;
+            }
+
+            impl<T, E: Debug> Result<T, E> {
+                #[requires(exists<t: T> self == Ok(t))]
+                #[ensures(Ok(result) == self)]
+                fn unwrap(self) -> T;
+
+                #[requires(exists<t: T> self == Ok(t))]
+                #[ensures(Ok(result) == self)]
+                fn expect(self, msg: &str) -> T;
+            }
+        }
+    }
+}
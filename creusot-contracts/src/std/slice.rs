@@ -89,6 +89,12 @@ extern_spec! {
         #[ensures((@^self).exchange(@*self, @i, @j))]
         fn swap(&mut self, i: usize, j: usize);
 
+        #[ensures(@result == self)]
+        fn iter(&self) -> std::slice::Iter<'_, T>;
+
+        #[ensures(@result == self)]
+        fn iter_mut(&mut self) -> std::slice::IterMut<'_, T>;
+
         #[requires(ix.in_bounds(@*self))]
         #[ensures(match result {
               Some(r) => ix.in_bounds(@*self_) && ix.has_value(@*self_, *r),
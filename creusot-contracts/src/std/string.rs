@@ -0,0 +1,74 @@
+use crate as creusot_contracts;
+use crate::logic::*;
+use crate::{EqModel, Int, Model, Seq};
+use creusot_contracts_proc::*;
+use std::ops::{Add, Deref};
+
+impl Model for str {
+    type ModelTy = Seq<char>;
+
+    #[logic]
+    #[trusted]
+    fn model(self) -> Self::ModelTy {
+        pearlite! { absurd }
+    }
+}
+
+impl EqModel for str {}
+
+impl Model for String {
+    type ModelTy = Seq<char>;
+
+    #[logic]
+    #[trusted]
+    fn model(self) -> Self::ModelTy {
+        pearlite! { absurd }
+    }
+}
+
+impl EqModel for String {}
+
+// Lengths are counted in bytes, which is at least the number of characters
+extern_spec! {
+    impl str {
+        #[ensures(@result >= (@self).len())]
+        fn len(&self) -> usize;
+
+        #[ensures(result == ((@self).len() == 0))]
+        fn is_empty(&self) -> bool;
+    }
+
+    mod std {
+        mod string {
+            impl String {
+                #[ensures((@result).len() == 0)]
+                fn new() -> String;
+
+                #[ensures(@result >= (@self).len())]
+                fn len(&self) -> usize;
+
+                #[ensures(result == ((@self).len() == 0))]
+                fn is_empty(&self) -> bool;
+
+                #[ensures(@^self == (@*self).push(ch))]
+                fn push(&mut self, ch: char);
+
+                #[ensures(@^self == (@*self).concat(@string))]
+                fn push_str(&mut self, string: &str);
+
+                #[ensures(@result == @*self)]
+                fn as_str(&self) -> &str;
+            }
+
+            impl Deref for String {
+                #[ensures(@*result == @*self)]
+                fn deref(&self) -> &str;
+            }
+
+            impl Add<&str> for String {
+                #[ensures(@result == (@self).concat(@other))]
+                fn add(self, other: &str) -> String;
+            }
+        }
+    }
+}
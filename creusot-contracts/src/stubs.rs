@@ -34,6 +34,13 @@ pub fn forall<T, F: Fn(T) -> bool>(_: F) -> bool {
     panic!()
 }
 
+// Attaches the tuple of patterns `_pats` as a trigger to the quantifier whose body is `_body`
+#[creusot::no_translate]
+#[rustc_diagnostic_item = "trigger"]
+pub fn trigger<T>(_pats: T, _body: bool) -> bool {
+    panic!()
+}
+
 #[creusot::no_translate]
 #[rustc_diagnostic_item = "implication"]
 pub fn implication(_: bool, _: bool) -> bool {
@@ -50,6 +50,35 @@ impl<'tcx> Visitor<'tcx> for NeverLive {
     }
 }
 
+pub struct StorageOnly(BitSet<Local>);
+
+/// Locals which are only mentioned by `StorageLive` and `StorageDead` markers: their storage is
+/// never used so they don't need to be declared or resolved.
+///
+/// The eager resolver also treats a `StorageDead` as the latest point a local can be resolved at.
+/// MLCFG variables have no block scope, so the markers cannot bound their declarations.
+impl StorageOnly {
+    pub fn for_body(body: &mir::Body) -> BitSet<Local> {
+        let mut storage_only = StorageOnly(BitSet::new_filled(body.local_decls.len()));
+        storage_only.0.remove(mir::RETURN_PLACE);
+        body.args_iter().for_each(|arg| {
+            storage_only.0.remove(arg);
+        });
+        storage_only.visit_body(body);
+        storage_only.0
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for StorageOnly {
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _location: Location) {
+        if !context.is_storage_marker()
+            && context != PlaceContext::NonUse(NonUseContext::VarDebugInfo)
+        {
+            self.0.remove(local);
+        }
+    }
+}
+
 // This Source Code Form is subject to the terms of the Mozilla Public
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
@@ -126,8 +155,8 @@ pub fn categorize(context: PlaceContext) -> Option<DefUse> {
         // Debug info is neither def nor use.
         PlaceContext::NonUse(NonUseContext::VarDebugInfo) => None,
 
-        PlaceContext::MutatingUse(MutatingUseContext::Deinit | MutatingUseContext::SetDiscriminant) => {
-            unreachable!("These statements are not allowed in this MIR phase")
-        }
+        // Only present in optimized MIR, where they (re)initialize a place piece by piece.
+        PlaceContext::MutatingUse(MutatingUseContext::Deinit | MutatingUseContext::SetDiscriminant) =>
+            Some(DefUse::Def),
     }
 }
@@ -63,7 +63,12 @@ struct DefaultCallbacks;
 impl rustc_driver::Callbacks for DefaultCallbacks {}
 
 fn main() {
-    rustc_driver::init_rustc_env_logger();
+    // `CREUSOT_LOG` takes a tracing filter, e.g. `CREUSOT_LOG=creusot=info`
+    if env::var_os("CREUSOT_LOG").is_some() {
+        rustc_driver::init_env_logger("CREUSOT_LOG");
+    } else {
+        rustc_driver::init_rustc_env_logger();
+    }
     env_logger::init();
 
     let mut args = env::args().skip(1).collect::<Vec<_>>();
@@ -71,6 +76,7 @@ fn main() {
     let sysroot = sysroot_path();
 
     let opts = Options::from_args_and_env(&args);
+    args.retain(|arg| arg != "--report" && !arg.starts_with("--int-model="));
 
     args.push(format!("--sysroot={}", sysroot));
 
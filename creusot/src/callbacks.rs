@@ -10,6 +10,7 @@ pub struct ToWhy {
 
 impl ToWhy {
     pub fn new(opts: Options) -> Self {
+        why3::reserve_identifiers(opts.reserved_idents.clone());
         ToWhy { opts }
     }
 }
@@ -11,7 +11,9 @@ use rustc_middle::ty::{
 };
 use rustc_middle::ty::{DefIdTree, ProjectionTy, Ty, TyKind};
 use rustc_span::{Symbol, DUMMY_SP};
-use why3::declaration::{CloneKind, CloneSubst, Decl, DeclClone, Use};
+use why3::declaration::{
+    CloneKind, CloneSubst, Contract, Decl, DeclClone, Logic, Signature, Use,
+};
 use why3::{Ident, QName};
 
 use crate::ctx::{self, *};
@@ -33,8 +35,8 @@ pub enum PreludeModule {
     UInt64,
     UInt128,
     Char,
-    Single,
-    Double,
+    Float32,
+    Float64,
     Prelude,
     Ref,
     Seq,
@@ -55,9 +57,9 @@ impl PreludeModule {
             PreludeModule::UInt32 => QName::from_string("mach.int.UInt32").unwrap(),
             PreludeModule::UInt64 => QName::from_string("mach.int.UInt64").unwrap(),
             PreludeModule::UInt128 => QName::from_string("prelude.UInt128").unwrap(),
-            PreludeModule::Char => QName::from_string("string.Char").unwrap(),
-            PreludeModule::Single => QName::from_string("floating_point.Single").unwrap(),
-            PreludeModule::Double => QName::from_string("floating_point.Double").unwrap(),
+            PreludeModule::Char => QName::from_string("prelude.Char").unwrap(),
+            PreludeModule::Float32 => QName::from_string("prelude.Float32").unwrap(),
+            PreludeModule::Float64 => QName::from_string("prelude.Float64").unwrap(),
             PreludeModule::Prelude => QName::from_string("prelude.Prelude").unwrap(),
             PreludeModule::Ref => QName::from_string("Ref").unwrap(),
             PreludeModule::Seq => QName::from_string("seq.Seq").unwrap(),
@@ -94,6 +96,9 @@ pub struct CloneMap<'tcx> {
 
     // Internal state to determine whether clones should be public or not
     public: bool,
+
+    // Number of definitions introduced for the const arguments of clones
+    const_args: usize,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -216,6 +221,7 @@ impl<'tcx> CloneMap<'tcx> {
             clone_graph: DiGraphMap::new(),
             last_cloned: 0,
             public: false,
+            const_args: 0,
         }
     }
 
@@ -533,7 +539,8 @@ impl<'tcx> CloneMap<'tcx> {
             let DepNode::Dep(node @ (def_id, subst)) = node else { continue };
 
             // Though we pass in a &mut ref, it shouldn't actually be possible to add any new entries..
-            let mut clone_subst = base_subst(ctx, self, def_id, subst);
+            let mut const_defs = Vec::new();
+            let mut clone_subst = base_subst(ctx, self, def_id, subst, &mut const_defs);
 
             if self.names[&node].cloned {
                 continue;
@@ -543,6 +550,7 @@ impl<'tcx> CloneMap<'tcx> {
             if self.names[&node].kind == Kind::Hidden {
                 continue;
             }
+            decls.extend(const_defs);
 
             let inbound_nodes: Vec<_> =
                 self.clone_graph.neighbors_directed(DepNode::Dep(node), Incoming).collect();
@@ -606,11 +614,13 @@ impl<'tcx> CloneMap<'tcx> {
 }
 
 // Create the substitution used to clone `def_id` with the rustc substitution `subst`.
+// Definitions of the const arguments of the substitution are pushed to `const_defs`.
 pub fn base_subst<'tcx>(
     ctx: &mut TranslationCtx<'_, 'tcx>,
     names: &mut CloneMap<'tcx>,
     mut def_id: DefId,
     subst: SubstsRef<'tcx>,
+    const_defs: &mut Vec<Decl>,
 ) -> Vec<CloneSubst> {
     use heck::SnakeCase;
     use rustc_middle::ty::GenericParamDefKind;
@@ -631,15 +641,57 @@ pub fn base_subst<'tcx>(
     for ix in 0..trait_params.count() {
         let p = trait_params.param_at(ix, ctx.tcx);
         let ty = subst[ix];
-        if let GenericParamDefKind::Type { .. } = p.kind {
-            let ty = super::ty::translate_ty(ctx, names, rustc_span::DUMMY_SP, ty.expect_ty());
-            clone_subst.push(CloneSubst::Type(p.name.to_string().to_snake_case().into(), ty));
+        match p.kind {
+            GenericParamDefKind::Type { .. } => {
+                let ty = super::ty::translate_ty(ctx, names, rustc_span::DUMMY_SP, ty.expect_ty());
+                clone_subst.push(CloneSubst::Type(p.name.to_string().to_snake_case().into(), ty));
+            }
+            GenericParamDefKind::Const { .. } => {
+                let arg = match ty.expect_const().val() {
+                    ty::ConstKind::Param(arg) => ident_of(arg.name),
+                    // Why3 can only substitute a symbol for a constant, so other const arguments
+                    // are first bound to a definition of their own
+                    _ => {
+                        let def = const_arg_def(ctx, names, p.def_id, ty.expect_const());
+                        let name = def.sig.name.clone();
+                        const_defs.push(Decl::LogicDecl(def));
+                        name
+                    }
+                };
+                clone_subst.push(CloneSubst::Function(ident_of(p.name).into(), arg.into()));
+            }
+            GenericParamDefKind::Lifetime => {}
         }
     }
 
     clone_subst
 }
 
+// Define a logical constant equal to the argument `arg` of the const parameter `param`
+fn const_arg_def<'tcx>(
+    ctx: &mut TranslationCtx<'_, 'tcx>,
+    names: &mut CloneMap<'tcx>,
+    param: DefId,
+    arg: ty::Const<'tcx>,
+) -> Logic {
+    let span = ctx.tcx.def_span(param);
+    let param_env = ctx.param_env(names.self_id);
+    let body = crate::constant::from_ty_const(ctx, names, arg, param_env, span);
+    let ty = ctx.tcx.type_of(param);
+    let retty = super::ty::translate_ty(ctx, names, span, ty);
+
+    let name: Ident = format!("const_arg{}", names.const_args).into();
+    names.const_args += 1;
+    let sig = Signature {
+        name,
+        attrs: Vec::new(),
+        args: Vec::new(),
+        retty: Some(retty),
+        contract: Contract::new(),
+    };
+    Logic { sig, body }
+}
+
 fn cloneable_name(tcx: TyCtxt, def_id: DefId, interface: bool) -> QName {
     use util::ItemType::*;
 
@@ -715,6 +767,10 @@ fn refineable_symbol<'tcx>(tcx: TyCtxt<'tcx>, def_id: DefId) -> Option<SymbolKin
             ty::TraitContainer(_) => Some(SymbolKind::Type(tcx.item_name(def_id))),
             ty::ImplContainer(_) => None,
         },
+        Constant => match tcx.opt_associated_item(def_id).map(|item| item.container) {
+            Some(ty::TraitContainer(_)) => Some(SymbolKind::Function(tcx.item_name(def_id))),
+            _ => None,
+        },
         Trait | Impl => unreachable!("trait blocks have no refinable symbols"),
         Type => unreachable!("types have no refinable symbols"),
         _ => unreachable!(),
@@ -1,11 +1,12 @@
 use std::collections::HashMap;
 use std::ops::Deref;
+use std::panic::{self, AssertUnwindSafe};
 
 pub use crate::clone_map::*;
 use crate::creusot_items::{self, CreusotItems};
 use crate::error::CreusotResult;
 use crate::metadata::{BinaryMetadata, Metadata};
-use crate::options::SpanMode;
+use crate::options::{MirSource, SpanMode};
 use crate::translation::external::{extract_extern_specs_from_item, ExternSpec};
 use crate::translation::interface::interface_for;
 use crate::translation::specification::typing::Term;
@@ -19,11 +20,13 @@ use rustc_data_structures::captures::Captures;
 use rustc_errors::{DiagnosticBuilder, DiagnosticId};
 use rustc_hir::def::DefKind;
 use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_index::vec::IndexVec;
+use rustc_middle::mir::{Body, Promoted};
 use rustc_middle::ty::subst::InternalSubsts;
-use rustc_middle::ty::{ParamEnv, TyCtxt};
+use rustc_middle::ty::{ParamEnv, TyCtxt, WithOptConstParam};
 use rustc_span::{Span, Symbol, DUMMY_SP};
 pub use util::{item_name, module_name, ItemType};
-use why3::declaration::{Module, TyDecl};
+use why3::declaration::{Metrics, Module, TyDecl};
 use why3::exp::Exp;
 
 pub use crate::translated_item::*;
@@ -41,6 +44,11 @@ pub struct TranslationCtx<'sess, 'tcx> {
     creusot_items: CreusotItems,
     extern_specs: HashMap<DefId, ExternSpec<'tcx>>,
     extern_spec_items: HashMap<LocalDefId, DefId>,
+    metrics: IndexMap<DefId, Metrics>,
+    // Promoted MIR kept for items whose optimized MIR, which steals it, may be requested
+    promoted_bodies: HashMap<LocalDefId, (Body<'tcx>, IndexVec<Promoted, Body<'tcx>>)>,
+    // MIR as built from THIR, kept before the later phases steal it
+    built_bodies: HashMap<LocalDefId, Body<'tcx>>,
 }
 
 impl<'tcx> Deref for TranslationCtx<'_, 'tcx> {
@@ -67,6 +75,9 @@ impl<'tcx, 'sess> TranslationCtx<'sess, 'tcx> {
             ty_binding_groups: Default::default(),
             extern_specs: Default::default(),
             extern_spec_items: Default::default(),
+            metrics: Default::default(),
+            promoted_bodies: Default::default(),
+            built_bodies: Default::default(),
         }
     }
 
@@ -74,6 +85,7 @@ impl<'tcx, 'sess> TranslationCtx<'sess, 'tcx> {
         self.externs.load(&self.opts.extern_paths);
     }
 
+    #[tracing::instrument(level = "info", skip(self))]
     pub fn translate(&mut self, def_id: DefId) {
         if self.translated_items.contains(&def_id) {
             return;
@@ -149,7 +161,7 @@ impl<'tcx, 'sess> TranslationCtx<'sess, 'tcx> {
             TranslatedItem::Extern { interface, body: ext_modl.0, dependencies: ext_modl.1 }
         } else {
             debug!("translating {def_id:?} as program");
-            let modl = crate::translation::translate_function(self, def_id);
+            let modl = self.translate_program_isolated(def_id);
             TranslatedItem::Program {
                 interface,
                 modl,
@@ -161,6 +173,40 @@ impl<'tcx, 'sess> TranslationCtx<'sess, 'tcx> {
         self.functions.insert(def_id, translated);
     }
 
+    // Translate the body of a program function in isolation: when its translation panics or
+    // reports errors, even fatal ones, a diagnostic names the function and a trusted stub with its
+    // signature and contract takes the place of the body, so that the remaining items of the crate
+    // are still translated. The reported errors still make the compilation fail in the end.
+    fn translate_program_isolated(&mut self, def_id: DefId) -> Module {
+        let errors = self.tcx.sess.err_count();
+        let res = panic::catch_unwind(AssertUnwindSafe(|| {
+            crate::translation::translate_function(self, def_id)
+        }));
+
+        let path = self.tcx.def_path_str(def_id);
+        match res {
+            Ok(modl) if self.tcx.sess.err_count() == errors => return modl,
+            Err(payload) if self.tcx.sess.err_count() == errors => {
+                let msg = payload
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| String::from("unknown panic"));
+                self.error(
+                    self.def_span(def_id),
+                    &format!("internal error while translating `{}`: {}", path, msg),
+                );
+            }
+            // Errors were reported, fatal ones being raised as panics afterwards
+            _ => self.warn(
+                self.def_span(def_id),
+                &format!("the body of `{}` is replaced by a trusted stub", path),
+            ),
+        }
+
+        crate::translation::function::translate_trusted(self.tcx, self, def_id)
+    }
+
     pub fn translate_accessor(&mut self, field_id: DefId) {
         use rustc_middle::ty::DefIdTree;
 
@@ -199,6 +245,40 @@ impl<'tcx, 'sess> TranslationCtx<'sess, 'tcx> {
         }
     }
 
+    /// The MIR checked by borrowck for `def_id`, along with its promoted constants
+    pub fn body_with_promoted(
+        &mut self,
+        def_id: LocalDefId,
+    ) -> (Body<'tcx>, IndexVec<Promoted, Body<'tcx>>) {
+        if let Some(bodies) = self.promoted_bodies.get(&def_id) {
+            return bodies.clone();
+        }
+
+        let (body, promoted) = self.tcx.mir_promoted(WithOptConstParam::unknown(def_id));
+        let bodies = (body.borrow().clone(), promoted.borrow().clone());
+        if self.opts.mir_source == MirSource::Optimized {
+            self.promoted_bodies.insert(def_id, bodies.clone());
+        }
+        bodies
+    }
+
+    /// Keep the MIR built for `def_id`, which must be done before borrowck steals it
+    pub fn keep_built_body(&mut self, def_id: LocalDefId) {
+        let body = self.tcx.mir_built(WithOptConstParam::unknown(def_id)).borrow().clone();
+        self.built_bodies.insert(def_id, body);
+    }
+
+    /// The MIR built for `def_id`, in which constants are not promoted
+    pub fn built_body(
+        &mut self,
+        def_id: LocalDefId,
+    ) -> (Body<'tcx>, IndexVec<Promoted, Body<'tcx>>) {
+        match self.built_bodies.get(&def_id) {
+            Some(body) => (body.clone(), IndexVec::new()),
+            None => self.crash_and_error(self.def_span(def_id), "the built MIR was not kept"),
+        }
+    }
+
     pub fn crash_and_error(&self, span: Span, msg: &str) -> ! {
         self.tcx.sess.span_fatal_with_code(span, msg, DiagnosticId::Error(String::from("creusot")))
     }
@@ -270,6 +350,15 @@ impl<'tcx, 'sess> TranslationCtx<'sess, 'tcx> {
         self.functions.values().flat_map(|m| m.modules())
     }
 
+    /// Proof effort estimates for every program function translated so far
+    pub fn metrics(&self) -> impl Iterator<Item = (DefId, Metrics)> + '_ {
+        self.metrics.iter().map(|(id, metrics)| (*id, *metrics))
+    }
+
+    pub(crate) fn record_metrics(&mut self, def_id: DefId, metrics: Metrics) {
+        self.metrics.insert(def_id, metrics);
+    }
+
     pub(crate) fn metadata(&self) -> BinaryMetadata<'tcx> {
         BinaryMetadata::from_parts(
             self.tcx,
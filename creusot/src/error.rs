@@ -16,6 +16,10 @@ impl Error {
         Error { span, msg: msg.into() }
     }
 
+    pub fn msg(&self) -> &str {
+        &self.msg
+    }
+
     pub fn emit(self, sess: &Session) -> ! {
         sess.span_fatal_with_code(
             self.span,
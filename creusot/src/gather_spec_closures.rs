@@ -4,7 +4,7 @@ use rustc_data_structures::graph::WithSuccessors;
 use rustc_hir::def_id::DefId;
 use rustc_middle::mir::Operand;
 use rustc_middle::mir::{visit::Visitor, AggregateKind, BasicBlock, Body, Location, Rvalue};
-use rustc_middle::ty::{TyCtxt, TyKind};
+use rustc_middle::ty::{Ty, TyCtxt, TyKind};
 use rustc_span::Symbol;
 
 use why3::exp::Exp;
@@ -19,13 +19,18 @@ pub fn corrected_invariant_names_and_locations<'tcx>(
     names: &mut CloneMap<'tcx>,
     def_id: DefId,
     body: &Body<'tcx>,
-) -> (IndexMap<BasicBlock, Vec<(Symbol, Exp)>>, IndexMap<DefId, Exp>) {
+) -> (
+    IndexMap<BasicBlock, Vec<(Symbol, Exp)>>,
+    IndexMap<DefId, Exp>,
+    IndexMap<BasicBlock, (Exp, Ty<'tcx>)>,
+) {
     let mut visitor = InvariantClosures::new(ctx.tcx, def_id);
     visitor.visit_body(&body);
 
     let mut assertions: IndexMap<_, _> = Default::default();
     // let mut ghosts: IndexMap<_, _> = Default::default();
     let mut invariants: IndexMap<_, _> = Default::default();
+    let mut variants: IndexMap<_, _> = Default::default();
     let param_env = ctx.param_env(def_id);
     for clos in visitor.closures.into_iter() {
         if let Some(name) = util::invariant_name(ctx.tcx, clos) {
@@ -33,6 +38,12 @@ pub fn corrected_invariant_names_and_locations<'tcx>(
             let exp = lower_pure(ctx, names, clos, param_env, term);
 
             invariants.insert(clos, (name, exp));
+        } else if util::is_loop_variant(ctx.tcx, clos) {
+            let term = ctx.term(clos).unwrap().clone();
+            let ty = term.ty;
+            let exp = lower_pure(ctx, names, clos, param_env, term);
+
+            variants.insert(clos, (exp, ty));
         } else if util::is_assertion(ctx.tcx, clos) {
             let term = ctx.term(clos).unwrap().clone();
             let exp = lower_pure(ctx, names, clos, param_env, term);
@@ -49,22 +60,25 @@ pub fn corrected_invariant_names_and_locations<'tcx>(
 
     let locations = invariant_locations(ctx.tcx, body);
 
-    let correct_inv = locations
-        .into_iter()
-        .map(|(loc, invs)| {
-            let inv_exps: Vec<_> = invs
-                .into_iter()
-                .map(|id| {
-                    let mut inv = invariants.remove(&id.1).unwrap();
-                    let inv_subst = inv_subst(ctx.tcx, body, id.0);
-                    inv.1.subst(&inv_subst);
-                    inv
-                })
-                .collect();
-
-            (loc, inv_exps)
-        })
-        .collect();
+    let mut loop_variants = IndexMap::new();
+    let mut correct_inv = IndexMap::new();
+    for (loc, invs) in locations {
+        let mut inv_exps = Vec::new();
+        for (clos_loc, id) in invs {
+            let inv_subst = inv_subst(ctx.tcx, body, clos_loc);
+            if let Some((mut exp, ty)) = variants.remove(&id) {
+                exp.subst(&inv_subst);
+                if loop_variants.insert(loc, (exp, ty)).is_some() {
+                    ctx.crash_and_error(ctx.def_span(id), "a loop can only have one variant");
+                }
+            } else {
+                let mut inv = invariants.remove(&id).unwrap();
+                inv.1.subst(&inv_subst);
+                inv_exps.push(inv);
+            }
+        }
+        correct_inv.insert(loc, inv_exps);
+    }
 
     let mut ass_loc = ClosureLocations { locations: IndexMap::new() };
     ass_loc.visit_body(body);
@@ -80,8 +94,8 @@ pub fn corrected_invariant_names_and_locations<'tcx>(
         })
         .collect();
 
-    assert!(invariants.is_empty());
-    (correct_inv, assertions)
+    assert!(invariants.is_empty() && variants.is_empty());
+    (correct_inv, assertions, loop_variants)
 }
 
 // Collect the closures in thir, so that we can do typechecking ourselves, and
@@ -149,7 +163,7 @@ struct InvariantLocations<'tcx> {
 impl<'tcx> Visitor<'tcx> for InvariantLocations<'tcx> {
     fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, loc: Location) {
         if let Rvalue::Aggregate(box AggregateKind::Closure(id, _), _) = rvalue {
-            if util::is_invariant(self.tcx, *id) {
+            if util::is_invariant(self.tcx, *id) || util::is_loop_variant(self.tcx, *id) {
                 self.invariants.insert(loc, *id);
             }
         }
@@ -1,7 +1,8 @@
 #![feature(rustc_private, register_tool)]
 #![feature(box_syntax, box_patterns, control_flow_enum, drain_filter)]
-#![feature(let_else, let_chains, never_type, try_blocks)]
+#![feature(let_else, let_chains, never_type, scoped_threads, try_blocks)]
 
+extern crate polonius_engine;
 extern crate rustc_ast;
 extern crate rustc_borrowck;
 extern crate rustc_data_structures;
@@ -25,6 +26,7 @@ extern crate rustc_target;
 extern crate rustc_trait_selection;
 extern crate rustc_typeck;
 extern crate smallvec;
+extern crate tracing;
 #[macro_use]
 extern crate log;
 
@@ -40,6 +42,7 @@ mod debug;
 mod extended_location;
 mod gather_spec_closures;
 pub mod options;
+pub mod prover;
 mod resolve;
 // #[allow(dead_code)]
 mod rustc_extensions;
@@ -18,6 +18,10 @@ use std::io::Write;
 use std::path::{Path, PathBuf};
 use why3::declaration::Module;
 
+/// Version of the binary metadata format, bumped whenever `BinaryMetadata` changes so that
+/// metadata written by an incompatible version of Creusot is rejected instead of misread
+const METADATA_VERSION: u32 = 1;
+
 type CloneMetadata<'tcx> = HashMap<DefId, CloneSummary<'tcx>>;
 type ExternSpecs<'tcx> = HashMap<DefId, ExternSpec<'tcx>>;
 
@@ -229,6 +233,7 @@ fn dump_binary_metadata<'tcx>(
     dep_info: BinaryMetadata<'tcx>,
 ) -> Result<(), std::io::Error> {
     let mut encoder = MetadataEncoder::new(tcx);
+    METADATA_VERSION.encode(&mut encoder).unwrap();
     dep_info.encode(&mut encoder).unwrap();
 
     File::create(path).and_then(|mut file| file.write(&encoder.into_inner())).map_err(|err| {
@@ -246,11 +251,24 @@ fn load_binary_metadata<'tcx>(
 ) -> Option<BinaryMetadata<'tcx>> {
     let metadata = MetadataBlob::from_file(&path).and_then(|blob| {
         let mut decoder = MetadataDecoder::new(tcx, &blob);
-        Ok(BinaryMetadata::decode(&mut decoder))
+        let version = u32::decode(&mut decoder);
+        if version != METADATA_VERSION {
+            return Ok(Err(version));
+        }
+        Ok(Ok(BinaryMetadata::decode(&mut decoder)))
     });
 
     match metadata {
-        Ok(b) => Some(b),
+        Ok(Ok(b)) => Some(b),
+        Ok(Err(version)) => {
+            tcx.sess.err(&format!(
+                "metadata for crate `{}` has version {} but version {} was expected, rebuild it with this version of Creusot",
+                cstore.crate_name(cnum),
+                version,
+                METADATA_VERSION
+            ));
+            None
+        }
         Err(e) => {
             warn!("could not read metadata for crate `{:?}`: {:?}", cstore.crate_name(cnum), e);
             return None;
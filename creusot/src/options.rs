@@ -10,9 +10,25 @@ pub struct Options {
     pub export_metadata: bool,
     pub should_output: bool,
     pub output_file: Option<OutputFile>,
+    pub output_dir: Option<String>,
     pub bounds_check: bool,
     pub in_cargo: bool,
     pub span_mode: Option<SpanMode>,
+    pub polonius: bool,
+    pub mir_source: MirSource,
+    pub reserved_idents: Vec<String>,
+    pub share_terms: bool,
+    pub report: bool,
+    pub transparent_newtypes: bool,
+    pub diverging_panics: bool,
+    pub int_model: IntModel,
+    pub check_statements: bool,
+    pub width: usize,
+    pub emit: Emit,
+    pub prover: Option<String>,
+    pub prover_timeout: u32,
+    pub why3_path: String,
+    pub prelude_path: String,
 }
 
 pub enum SpanMode {
@@ -20,6 +36,36 @@ pub enum SpanMode {
     Absolute,
 }
 
+/// Which phase of MIR should be translated
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MirSource {
+    /// The MIR as built from THIR, before promoted constants are extracted
+    Built,
+    /// The MIR checked by borrowck, with promoted constants extracted
+    Promoted,
+    /// The MIR after drop elaboration and optimizations
+    Optimized,
+}
+
+/// How the operations of machine integers are translated
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum IntModel {
+    /// Bounded mathematical integers, which have no bitwise operations
+    Math,
+    /// Bounded mathematical integers, which go through the Why3 bitvectors of their width for
+    /// bitwise operations and shifts
+    Bitvector,
+}
+
+/// Format of the translated crate
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Emit {
+    /// MLCFG text, as read by Why3
+    Mlcfg,
+    /// The MLCFG modules serialized as JSON, for external tools
+    Json,
+}
+
 #[derive(Debug)]
 pub enum OutputFile {
     File(String),
@@ -54,6 +100,11 @@ impl Options {
             (false, None) => None,
         };
 
+        let output_dir = output_dir();
+        if output_dir.is_some() && output_file.is_some() {
+            panic!("cannot set an output file and an output directory at the same time")
+        }
+
         let extern_paths = match creusot_externs() {
             Some(val) => from_str(&val).expect("could not parse CREUSOT_EXTERNS"),
             None => HashMap::new(),
@@ -61,6 +112,24 @@ impl Options {
 
         let bounds_check = !creusot_unbounded();
         let span_mode = creusot_spans();
+        let polonius = creusot_polonius();
+        let mir_source = creusot_mir();
+        let reserved_idents = creusot_reserved();
+        let share_terms = creusot_share_terms();
+        let report = args.iter().any(|a| a == "--report") || creusot_report();
+        let transparent_newtypes = creusot_transparent_newtypes();
+        let diverging_panics = creusot_diverging_panics();
+        let check_statements = creusot_check_statements();
+        let int_model = match args.iter().find_map(|a| a.strip_prefix("--int-model=")) {
+            Some(model) => parse_int_model(model),
+            None => creusot_int_model(),
+        };
+        let width = creusot_width();
+        let emit = creusot_emit();
+        let prover = creusot_prove();
+        let prover_timeout = creusot_prove_timeout();
+        let why3_path = std::env::var("WHY3_PATH").unwrap_or_else(|_| "why3".into());
+        let prelude_path = creusot_prelude();
 
         Options {
             has_contracts,
@@ -68,12 +137,28 @@ impl Options {
             export_metadata,
             should_output,
             output_file,
+            output_dir,
             continue_compilation: continue_compiler(),
             metadata_path: creusot_metadata_path(),
             extern_paths,
             bounds_check,
             in_cargo: cargo_creusot,
             span_mode,
+            polonius,
+            mir_source,
+            reserved_idents,
+            share_terms,
+            report,
+            transparent_newtypes,
+            diverging_panics,
+            int_model,
+            check_statements,
+            width,
+            emit,
+            prover,
+            prover_timeout,
+            why3_path,
+            prelude_path,
         }
     }
 }
@@ -101,6 +186,10 @@ fn output_file() -> Option<String> {
     std::env::var_os("CREUSOT_OUTPUT_FILE").map(|m| m.to_string_lossy().to_string())
 }
 
+fn output_dir() -> Option<String> {
+    std::env::var_os("CREUSOT_OUTPUT_DIR").map(|m| m.to_string_lossy().to_string())
+}
+
 fn creusot_externs() -> Option<String> {
     std::env::var_os("CREUSOT_EXTERNS").map(|m| m.to_string_lossy().to_string())
 }
@@ -119,3 +208,90 @@ fn export_metadata() -> bool {
 fn creusot_unbounded() -> bool {
     std::env::var_os("CREUSOT_UNBOUNDED").is_some()
 }
+
+fn creusot_polonius() -> bool {
+    std::env::var_os("CREUSOT_POLONIUS").is_some()
+}
+
+fn creusot_mir() -> MirSource {
+    match std::env::var_os("CREUSOT_MIR") {
+        Some(opt) if opt == "built" => MirSource::Built,
+        Some(opt) if opt == "optimized" => MirSource::Optimized,
+        _ => MirSource::Promoted,
+    }
+}
+
+fn creusot_share_terms() -> bool {
+    std::env::var_os("CREUSOT_SHARE_TERMS").is_some()
+}
+
+fn creusot_report() -> bool {
+    std::env::var_os("CREUSOT_REPORT").is_some()
+}
+
+fn creusot_transparent_newtypes() -> bool {
+    std::env::var_os("CREUSOT_TRANSPARENT_NEWTYPES").is_some()
+}
+
+fn creusot_diverging_panics() -> bool {
+    std::env::var_os("CREUSOT_DIVERGING_PANICS").is_some()
+}
+
+fn creusot_check_statements() -> bool {
+    std::env::var_os("CREUSOT_CHECK_STATEMENTS").is_some()
+}
+
+fn creusot_int_model() -> IntModel {
+    match std::env::var("CREUSOT_INT_MODEL") {
+        Ok(model) => parse_int_model(&model),
+        Err(_) => IntModel::Math,
+    }
+}
+
+fn parse_int_model(model: &str) -> IntModel {
+    match model {
+        "math" => IntModel::Math,
+        "bv" => IntModel::Bitvector,
+        _ => panic!("unknown integer model `{}`, expected `math` or `bv`", model),
+    }
+}
+
+fn creusot_width() -> usize {
+    match std::env::var("CREUSOT_WIDTH") {
+        Ok(width) => width.parse().expect("could not parse CREUSOT_WIDTH"),
+        Err(_) => 120,
+    }
+}
+
+// Not a command line flag, since rustc already has an `--emit` option
+fn creusot_emit() -> Emit {
+    match std::env::var("CREUSOT_EMIT").as_deref() {
+        Err(_) | Ok("mlcfg") => Emit::Mlcfg,
+        Ok("json") => Emit::Json,
+        Ok(emit) => panic!("unknown output format `{}`, expected `mlcfg` or `json`", emit),
+    }
+}
+
+fn creusot_prove() -> Option<String> {
+    std::env::var_os("CREUSOT_PROVE").map(|m| m.to_string_lossy().to_string())
+}
+
+fn creusot_prove_timeout() -> u32 {
+    match std::env::var("CREUSOT_PROVE_TIMEOUT") {
+        Ok(timeout) => timeout.parse().expect("could not parse CREUSOT_PROVE_TIMEOUT"),
+        Err(_) => 5,
+    }
+}
+
+// Defaults to the prelude of the source tree Creusot was built from
+fn creusot_prelude() -> String {
+    std::env::var("CREUSOT_PRELUDE")
+        .unwrap_or_else(|_| concat!(env!("CARGO_MANIFEST_DIR"), "/../prelude").into())
+}
+
+fn creusot_reserved() -> Vec<String> {
+    match std::env::var("CREUSOT_RESERVED") {
+        Ok(idents) => idents.split(',').map(|id| id.trim().to_owned()).collect(),
+        Err(_) => Vec::new(),
+    }
+}
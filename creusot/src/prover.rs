@@ -0,0 +1,194 @@
+// Runs Why3 on the generated MLCFG and collects the answer of the prover on each goal, so that
+// users get a summary without driving Why3 by hand.
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::Command;
+
+/// Answer of a prover on a single goal, as reported by `why3 prove`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Valid,
+    Invalid,
+    Timeout,
+    OutOfMemory,
+    StepLimitExceeded,
+    Unknown,
+    Failure(String),
+}
+
+impl Answer {
+    fn parse(answer: &str) -> Self {
+        let answer = answer.trim().trim_end_matches('.');
+        // Drop the statistics, e.g. `Valid (0.01s, 12 steps)`
+        let kind = answer.split(" (").next().unwrap_or(answer);
+        match kind {
+            "Valid" => Answer::Valid,
+            "Invalid" => Answer::Invalid,
+            "Timeout" => Answer::Timeout,
+            "Out Of Memory" => Answer::OutOfMemory,
+            "Step limit exceeded" => Answer::StepLimitExceeded,
+            "Unknown" => Answer::Unknown,
+            _ => Answer::Failure(answer.to_string()),
+        }
+    }
+}
+
+/// The result of proving a verification condition, or one of the contract clauses it was split
+/// into
+#[derive(Debug, Clone)]
+pub struct GoalResult {
+    /// Name of the goal, `f'vc` for the body of the function `f`
+    pub goal: String,
+    /// Explanation of the sub-goal, e.g. `postcondition`, when the goal was split
+    pub clause: Option<String>,
+    /// Source location of the goal, which points to the Rust code when spans are enabled
+    pub location: Option<String>,
+    pub answer: Answer,
+}
+
+impl GoalResult {
+    /// Name of the Rust function the goal was generated for
+    pub fn function(&self) -> &str {
+        self.goal.strip_suffix("'vc").unwrap_or(&self.goal)
+    }
+}
+
+/// How to invoke Why3
+pub struct ProverConfig<'a> {
+    pub why3: &'a str,
+    pub prelude: &'a str,
+    pub prover: &'a str,
+    pub timeout: u32,
+}
+
+/// Prove every goal of `file` with a single prover, after splitting them into one sub-goal per
+/// contract clause
+pub fn prove(config: &ProverConfig, file: &Path) -> io::Result<Vec<GoalResult>> {
+    let output = Command::new(config.why3)
+        .arg("prove")
+        .args(&["-L", config.prelude, "-F", "mlcfg", "-a", "split_vc"])
+        .args(&["-P", config.prover, "-t", &config.timeout.to_string()])
+        .arg(file)
+        .output()?;
+
+    if output.stdout.is_empty() && !output.status.success() {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+        ));
+    }
+    Ok(parse_results(&String::from_utf8_lossy(&output.stdout)))
+}
+
+// `why3 prove` reports each goal as
+//
+//   File "../f.rs", line 4, characters 0-24:
+//   Verification condition f'vc.
+//   Prover result is: Valid (0.01s, 12 steps).
+//
+// Split goals are named `Sub-goal postcondition of goal f'vc.` and are located at the clause
+// they come from. The location line is omitted for goals without a location, and older versions
+// of Why3 print `Goal f'vc.` instead of `Verification condition f'vc.`.
+pub fn parse_results(output: &str) -> Vec<GoalResult> {
+    let mut results = Vec::new();
+    let mut location = None;
+    let mut current = None;
+
+    for line in output.lines().map(str::trim) {
+        if let Some(loc) = line.strip_prefix("File ") {
+            location = Some(loc.trim_end_matches(':').to_string());
+        } else if let Some((goal, clause)) = parse_goal(line) {
+            // A location only applies to the goal which immediately follows it
+            current = Some(GoalResult {
+                goal,
+                clause,
+                location: location.take(),
+                answer: Answer::Unknown,
+            });
+        } else if let Some(answer) = line.strip_prefix("Prover result is:") {
+            if let Some(mut res) = current.take() {
+                res.answer = Answer::parse(answer);
+                results.push(res);
+            }
+        }
+    }
+
+    results
+}
+
+fn parse_goal(line: &str) -> Option<(String, Option<String>)> {
+    if let Some(sub) = line.strip_prefix("Sub-goal ") {
+        let (clause, goal) = sub.rsplit_once(" of goal ")?;
+        return Some((goal.trim_end_matches('.').to_string(), Some(clause.to_string())));
+    }
+    let goal = line.strip_prefix("Verification condition ").or_else(|| line.strip_prefix("Goal "))?;
+    Some((goal.trim_end_matches('.').to_string(), None))
+}
+
+/// Print the goals which were not proved, grouped by function, followed by a count of the goals
+/// of each answer
+pub fn print_summary(results: &[GoalResult], out: &mut dyn Write) -> io::Result<()> {
+    let mut failed: BTreeMap<&str, Vec<&GoalResult>> = BTreeMap::new();
+    for res in results.iter().filter(|res| res.answer != Answer::Valid) {
+        failed.entry(res.function()).or_default().push(res);
+    }
+
+    for (function, goals) in &failed {
+        writeln!(out, "{}:", function)?;
+        for res in goals {
+            let location = res.location.as_deref().unwrap_or("unknown location");
+            match &res.clause {
+                Some(clause) => writeln!(out, "  {}: {:?} at {}", clause, res.answer, location)?,
+                None => writeln!(out, "  {:?} at {}", res.answer, location)?,
+            }
+        }
+    }
+
+    let valid = results.iter().filter(|res| res.answer == Answer::Valid).count();
+    writeln!(out, "{} of {} goals proved", valid, results.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_answers() {
+        assert_eq!(Answer::parse(" Valid (0.01s, 12 steps)."), Answer::Valid);
+        assert_eq!(Answer::parse(" Timeout (5.00s)."), Answer::Timeout);
+        assert_eq!(Answer::parse(" Out Of Memory."), Answer::OutOfMemory);
+        assert_eq!(Answer::parse(" Step limit exceeded (1000 steps)."), Answer::StepLimitExceeded);
+        assert_eq!(Answer::parse(" HighFailure."), Answer::Failure("HighFailure".into()));
+    }
+
+    #[test]
+    fn parse_goals() {
+        let output = "\
+File \"../f.rs\", line 4, characters 0-24:
+Sub-goal postcondition of goal f'vc.
+Prover result is: Timeout (5.00s).
+Verification condition g'vc.
+Prover result is: Valid (0.01s, 12 steps).
+Goal h'vc.
+Prover result is: Unknown (unknown).
+";
+        let results = parse_results(output);
+        assert_eq!(results.len(), 3);
+
+        assert_eq!(results[0].function(), "f");
+        assert_eq!(results[0].clause.as_deref(), Some("postcondition"));
+        assert_eq!(results[0].location.as_deref(), Some("\"../f.rs\", line 4, characters 0-24"));
+        assert_eq!(results[0].answer, Answer::Timeout);
+
+        // Locations don't carry over to the following goals
+        assert_eq!(results[1].function(), "g");
+        assert_eq!(results[1].location, None);
+        assert_eq!(results[1].answer, Answer::Valid);
+
+        assert_eq!(results[2].function(), "h");
+        assert_eq!(results[2].clause, None);
+        assert_eq!(results[2].answer, Answer::Unknown);
+    }
+}
@@ -1,15 +1,20 @@
-use std::rc::Rc;
+use std::{collections::HashMap, rc::Rc};
 
 use crate::analysis::uninit_locals::MaybeUninitializedLocals;
-use rustc_borrowck::borrow_set::{BorrowSet, TwoPhaseActivation};
+use polonius_engine::{Algorithm, Output};
+use rustc_borrowck::{
+    borrow_set::{BorrowSet, TwoPhaseActivation},
+    consumers::{BodyWithBorrowckFacts, LocationTable, RustcFacts},
+};
 use rustc_index::bit_set::BitSet;
 use rustc_middle::{
-    mir::{BasicBlock, Body, Local, Location},
+    mir::{BasicBlock, Body, BorrowKind, Local, Location, StatementKind},
     ty::TyCtxt,
 };
 use rustc_mir_dataflow::{
     self,
     impls::{MaybeInitializedLocals, MaybeLiveLocals},
+    move_paths::MoveData,
     Analysis, ResultsCursor,
 };
 
@@ -29,10 +34,18 @@ pub struct EagerResolver<'body, 'tcx> {
     body: &'body Body<'tcx>,
 
     borrows: Rc<BorrowSet<'tcx>>,
+
+    // Precise loan liveness, when borrow-check facts are available
+    loans: Option<LoanLiveness>,
 }
 
 impl<'body, 'tcx> EagerResolver<'body, 'tcx> {
-    pub fn new(tcx: TyCtxt<'tcx>, body: &'body Body<'tcx>, borrows: Rc<BorrowSet<'tcx>>) -> Self {
+    pub fn new(
+        tcx: TyCtxt<'tcx>,
+        body: &'body Body<'tcx>,
+        borrows: Rc<BorrowSet<'tcx>>,
+        loans: Option<LoanLiveness>,
+    ) -> Self {
         let local_init = MaybeInitializedLocals
             .into_engine(tcx, body)
             .iterate_to_fixpoint()
@@ -48,7 +61,7 @@ impl<'body, 'tcx> EagerResolver<'body, 'tcx> {
         let local_live =
             MaybeLiveLocals.into_engine(tcx, body).iterate_to_fixpoint().into_results_cursor(body);
         let never_live = crate::analysis::NeverLive::for_body(body);
-        EagerResolver { local_live, local_init, local_uninit, never_live, body, borrows }
+        EagerResolver { local_live, local_init, local_uninit, never_live, body, borrows, loans }
     }
 
     fn unactivated_borrows(&self, loc: Location) -> BitSet<Local> {
@@ -59,15 +72,22 @@ impl<'body, 'tcx> EagerResolver<'body, 'tcx> {
         self.borrows
             .location_map
             .iter()
-            .filter(|(_, bd)| {
+            .enumerate()
+            .filter(|(_, (_, bd))| {
+                // Region inference tells us exactly where the loan is live, otherwise approximate
+                // it by the locations dominated by the reservation.
                 let res_loc = bd.reserve_location;
+                if let Some(live) = self.loans.as_ref().and_then(|l| l.live_at(res_loc, loc)) {
+                    return live;
+                }
+
                 if res_loc.block == loc.block {
                     res_loc.statement_index <= loc.statement_index
                 } else {
                     dom.is_dominated_by(loc.block, res_loc.block)
                 }
             })
-            .filter(|(_, bd)| {
+            .filter(|(_, (_, bd))| {
                 if let TwoPhaseActivation::ActivatedAt(act_loc) = bd.activation_location {
                     if act_loc.block == loc.block {
                         loc.statement_index <= act_loc.statement_index
@@ -78,6 +98,26 @@ impl<'body, 'tcx> EagerResolver<'body, 'tcx> {
                     false
                 }
             })
+            .for_each(|(_, (_, bd))| {
+                bits.insert(bd.borrowed_place.local);
+            });
+
+        bits
+    }
+
+    // Locals mutably borrowed by a loan live at `loc`: their value may still change through the
+    // loan, so they must not be resolved yet. Borrows through a reference are excluded, since the
+    // reborrowed reference is already given the prophecy of the new borrow.
+    fn loaned_locals(&self, loc: Location) -> BitSet<Local> {
+        let mut bits = BitSet::new_empty(self.body.local_decls.len());
+        let Some(loans) = &self.loans else { return bits };
+
+        self.borrows
+            .location_map
+            .iter()
+            .filter(|(_, bd)| matches!(bd.kind, BorrowKind::Mut { .. }))
+            .filter(|(_, bd)| !bd.borrowed_place.is_indirect())
+            .filter(|(res_loc, _)| loans.live_at(**res_loc, loc).unwrap_or(false))
             .for_each(|(_, bd)| {
                 bits.insert(bd.borrowed_place.local);
             });
@@ -85,12 +125,26 @@ impl<'body, 'tcx> EagerResolver<'body, 'tcx> {
         bits
     }
 
+    // The local whose storage ends with the statement at `loc`, if any
+    fn storage_dead_at(&self, loc: Location) -> BitSet<Local> {
+        let mut bits = BitSet::new_empty(self.body.local_decls.len());
+        let block = &self.body.basic_blocks()[loc.block];
+        if let Some(StatementKind::StorageDead(local)) =
+            block.statements.get(loc.statement_index).map(|s| &s.kind)
+        {
+            bits.insert(*local);
+        }
+        bits
+    }
+
     pub fn locals_resolved_at_loc(&mut self, loc: Location) -> BitSet<Local> {
+        let storage_dead = self.storage_dead_at(loc);
         self.locals_resolved_between(
             ExtendedLocation::Start(loc),
             ExtendedLocation::Mid(loc),
             loc,
             loc.successor_within_block(),
+            storage_dead,
         )
     }
 
@@ -107,6 +161,7 @@ impl<'body, 'tcx> EagerResolver<'body, 'tcx> {
             ExtendedLocation::Start(start),
             term,
             start,
+            BitSet::new_empty(self.body.local_decls.len()),
         )
     }
 
@@ -116,6 +171,7 @@ impl<'body, 'tcx> EagerResolver<'body, 'tcx> {
         end: ExtendedLocation,
         two_phase_start: Location,
         two_phase_end: Location,
+        storage_dead: BitSet<Local>,
     ) -> BitSet<Local> {
         start.seek_to(&mut self.local_live);
         let mut live_at_start = self.local_live.get().clone();
@@ -125,10 +181,14 @@ impl<'body, 'tcx> EagerResolver<'body, 'tcx> {
         }
 
         live_at_start.union(&self.unactivated_borrows(two_phase_start));
+        live_at_start.union(&self.loaned_locals(two_phase_start));
 
         end.seek_to(&mut self.local_live);
         let mut live_at_end = self.local_live.get().clone();
         live_at_end.union(&self.unactivated_borrows(two_phase_end));
+        live_at_end.union(&self.loaned_locals(two_phase_end));
+        // Nothing outlives its storage, whatever the approximations above say
+        live_at_end.subtract(&storage_dead);
 
         start.seek_to(&mut self.local_init);
         let init_at_start = self.local_init.get().clone();
@@ -154,6 +214,9 @@ impl<'body, 'tcx> EagerResolver<'body, 'tcx> {
         def_init_at_start.subtract(&uninit_at_start);
         trace!("def_init_at_start: {:?}", def_init_at_start);
 
+        let mut live_at_start_init = live_at_start.clone();
+        live_at_start_init.intersect(&def_init_at_start);
+
         let mut def_init_at_end = init_at_end.clone();
         def_init_at_end.subtract(&uninit_at_end);
         trace!("def_init_at_end: {:?}", def_init_at_end);
@@ -195,8 +258,58 @@ impl<'body, 'tcx> EagerResolver<'body, 'tcx> {
             dying.union(&zombies);
         }
 
+        // A local still held by a borrow when its storage ends must be resolved there: the marker
+        // also uninitializes it, so the rules above would never consider it again.
+        let mut storage_ended = storage_dead;
+        storage_ended.intersect(&live_at_start_init);
+        dying.union(&storage_ended);
+
         trace!("dying: {:?}", dying);
 
         dying
     }
 }
+
+/// The points at which each loan of a body is live, as computed by Polonius from the facts
+/// produced by rustc's borrow checker.
+pub struct LoanLiveness {
+    location_table: LocationTable,
+    output: Output<RustcFacts>,
+    // The original index of each block of the translated body
+    blocks: Vec<BasicBlock>,
+    // The index of the loans in the facts, by the original location of their reservation
+    loans: HashMap<Location, usize>,
+}
+
+impl LoanLiveness {
+    pub fn new<'tcx>(
+        tcx: TyCtxt<'tcx>,
+        facts: BodyWithBorrowckFacts<'tcx>,
+        blocks: Vec<BasicBlock>,
+    ) -> Self {
+        // rustc doesn't keep `loan_live_at` in its own results so we must recompute them with dumping enabled.
+        let output = Output::compute(&facts.input_facts, Algorithm::DatafrogOpt, true);
+
+        // Loans are numbered by the borrow set of the body the facts were computed on, which
+        // still has the blocks and false edges removed from the translated body.
+        let param_env = tcx.param_env(facts.body.source.def_id());
+        let move_data = MoveData::gather_moves(&facts.body, tcx, param_env)
+            .unwrap_or_else(|(move_data, _)| move_data);
+        let borrows = BorrowSet::build(tcx, &facts.body, true, &move_data);
+        let loans = borrows.location_map.keys().enumerate().map(|(ix, loc)| (*loc, ix)).collect();
+
+        LoanLiveness { location_table: facts.location_table, output, blocks, loans }
+    }
+
+    // Whether the loan reserved at `reserve` is live at `loc`, both being locations of the
+    // translated body, if it is known to the facts
+    fn live_at(&self, reserve: Location, loc: Location) -> Option<bool> {
+        let borrow = *self.loans.get(&self.original_location(reserve))?;
+        let point = self.location_table.mid_index(self.original_location(loc));
+        Some(self.output.loans_in_scope_at(point).iter().any(|loan| loan.index() == borrow))
+    }
+
+    fn original_location(&self, loc: Location) -> Location {
+        Location { block: self.blocks[loc.block.index()], ..loc }
+    }
+}
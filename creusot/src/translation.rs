@@ -12,7 +12,8 @@ use crate::ctx::load_extern_specs;
 use crate::ctx::TypeDeclaration;
 use crate::error::CrErr;
 use crate::metadata;
-use crate::options::OutputFile;
+use crate::options::{Emit, MirSource, OutputFile};
+use crate::prover::{self, ProverConfig};
 use crate::validate::validate_traits;
 use ctx::TranslationCtx;
 pub use function::translate_function;
@@ -21,14 +22,26 @@ use heck::CamelCase;
 pub use logic::*;
 use rustc_hir::def::DefKind;
 use rustc_hir::def_id::LOCAL_CRATE;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::io::Write;
-use why3::mlcfg;
+use std::path::{Path, PathBuf};
 use why3::{
-    declaration::{Decl, Module, Use},
+    declaration::{Decl, DeclClone, Module, Scope, Use},
     Print, QName,
 };
 
+/// Version of the generated MLCFG, bumped whenever tools consuming the output have to adapt
+pub const MLCFG_FORMAT_VERSION: u32 = 1;
+
+/// Version of `prelude/prelude.mlw` that the generated MLCFG relies on
+pub const PRELUDE_VERSION: u32 = 4;
+
+/// First line of every generated file, read by tools to reject output they cannot handle
+pub fn version_header() -> String {
+    format!("(* Creusot MLCFG format {}, prelude {} *)", MLCFG_FORMAT_VERSION, PRELUDE_VERSION)
+}
+
 pub fn before_analysis(ctx: &mut TranslationCtx) -> Result<(), Box<dyn Error>> {
     let start = Instant::now();
     ctx.load_metadata();
@@ -47,6 +60,23 @@ pub fn before_analysis(ctx: &mut TranslationCtx) -> Result<(), Box<dyn Error>> {
     // Check that all trait laws are well-formed
     validate_traits(ctx);
 
+    // Built MIR is stolen by borrowck, and Polonius needs the promoted MIR its facts are about
+    if ctx.opts.mir_source == MirSource::Built && !ctx.opts.polonius {
+        for def_id in ctx.tcx.hir().body_owners() {
+            let kind = ctx.def_kind(def_id);
+            if matches!(kind, DefKind::Fn | DefKind::AssocFn | DefKind::Closure)
+                && crate::util::should_translate(ctx.tcx, def_id.to_def_id())
+                && !crate::util::is_spec(ctx.tcx, def_id.to_def_id())
+                && !crate::util::is_logic(ctx.tcx, def_id.to_def_id())
+                && !crate::util::is_predicate(ctx.tcx, def_id.to_def_id())
+                && !crate::util::is_trusted(ctx.tcx, def_id.to_def_id())
+                && !crate::util::is_extern_spec(ctx.tcx, def_id.to_def_id())
+            {
+                ctx.keep_built_body(def_id);
+            }
+        }
+    }
+
     debug!("before_analysis: {:?}", start.elapsed());
     Ok(())
 }
@@ -54,6 +84,15 @@ pub fn before_analysis(ctx: &mut TranslationCtx) -> Result<(), Box<dyn Error>> {
 use std::time::Instant;
 // TODO: Move the main loop out of `translation.rs`
 pub fn after_analysis(ctx: &mut TranslationCtx) -> Result<(), Box<dyn Error>> {
+    // Optimized MIR steals the promoted MIR of an item, which is still needed to inline it
+    if ctx.opts.mir_source == MirSource::Optimized {
+        for def_id in ctx.tcx.hir().body_owners() {
+            if matches!(ctx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn | DefKind::Closure) {
+                ctx.body_with_promoted(def_id);
+            }
+        }
+    }
+
     for tr in ctx.tcx.traits_in_crate(LOCAL_CRATE) {
         ctx.translate_trait(*tr);
     }
@@ -92,17 +131,39 @@ pub fn after_analysis(ctx: &mut TranslationCtx) -> Result<(), Box<dyn Error>> {
         metadata::dump_exports(&ctx, &ctx.opts.metadata_path);
     }
 
-    if ctx.should_compile() {
+    if let (true, Some(dir), Emit::Mlcfg) =
+        (ctx.should_compile(), &ctx.opts.output_dir, ctx.opts.emit)
+    {
+        print_crate_files(
+            Path::new(dir),
+            ctx.tcx.crate_name(LOCAL_CRATE).as_str(),
+            ctx.types.values(),
+            ctx.modules(),
+            ctx.opts.width,
+        )?;
+    } else if ctx.should_compile() {
         use std::fs::File;
+        let mut out_path = None;
         let mut out: Box<dyn Write> = match ctx.opts.output_file {
-            Some(OutputFile::File(ref f)) => Box::new(std::io::BufWriter::new(File::create(f)?)),
+            Some(OutputFile::File(ref f)) => {
+                out_path = Some(PathBuf::from(f));
+                Box::new(std::io::BufWriter::new(File::create(f)?))
+            }
             Some(OutputFile::Stdout) => Box::new(std::io::stdout()),
             None => {
                 let outputs = ctx.tcx.output_filenames(());
                 let crate_name = ctx.tcx.crate_name(LOCAL_CRATE);
 
-                let libname =
-                    format!("{}-{}.mlcfg", crate_name.as_str(), ctx.sess.crate_types()[0]);
+                let extension = match ctx.opts.emit {
+                    Emit::Mlcfg => "mlcfg",
+                    Emit::Json => "json",
+                };
+                let libname = format!(
+                    "{}-{}.{}",
+                    crate_name.as_str(),
+                    ctx.sess.crate_types()[0],
+                    extension
+                );
 
                 let directory = if ctx.opts.in_cargo {
                     let mut dir = outputs.out_directory.clone();
@@ -111,20 +172,68 @@ pub fn after_analysis(ctx: &mut TranslationCtx) -> Result<(), Box<dyn Error>> {
                 } else {
                     outputs.out_directory.clone()
                 };
-                let out_path = directory.join(&libname);
-                Box::new(std::io::BufWriter::new(File::create(out_path)?))
+                let path = directory.join(&libname);
+                let file = File::create(&path)?;
+                out_path = Some(path);
+                Box::new(std::io::BufWriter::new(file))
             }
         };
 
-        print_crate(
-            &mut out,
-            ctx.tcx.crate_name(LOCAL_CRATE).to_string().to_camel_case(),
-            ctx.types.values(),
-            ctx.modules(),
-        )?;
+        match ctx.opts.emit {
+            Emit::Mlcfg => print_crate(
+                &mut out,
+                ctx.tcx.crate_name(LOCAL_CRATE).to_string().to_camel_case(),
+                ctx.types.values(),
+                ctx.modules(),
+                ctx.opts.width,
+            )?,
+            Emit::Json => print_crate_json(&mut out, ctx.types.values(), ctx.modules())?,
+        }
+        out.flush()?;
+        drop(out);
+
+        if let (Some(prover), Some(path), Emit::Mlcfg) =
+            (&ctx.opts.prover, out_path, ctx.opts.emit)
+        {
+            let config = ProverConfig {
+                why3: &ctx.opts.why3_path,
+                prelude: &ctx.opts.prelude_path,
+                prover,
+                timeout: ctx.opts.prover_timeout,
+            };
+            let results = prover::prove(&config, &path)?;
+            prover::print_summary(&results, &mut std::io::stderr())?;
+        }
     }
     debug!("after_analysis_dump: {:?}", start.elapsed());
 
+    if ctx.opts.report {
+        print_report(ctx, &mut std::io::stderr())?;
+    }
+
+    Ok(())
+}
+
+// Lists program functions from the hardest to the easiest to prove, so users know what to
+// split or simplify first
+fn print_report(ctx: &TranslationCtx, out: &mut dyn Write) -> std::io::Result<()> {
+    let mut rows: Vec<_> =
+        ctx.metrics().map(|(def_id, metrics)| (ctx.tcx.def_path_str(def_id), metrics)).collect();
+    rows.sort_by(|(n1, m1), (n2, m2)| {
+        (m2.obligations, m2.quantifier_depth, m2.nodes)
+            .cmp(&(m1.obligations, m1.quantifier_depth, m1.nodes))
+            .then_with(|| n1.cmp(n2))
+    });
+
+    let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0).max("function".len());
+    writeln!(out, "{:width$}  obligations  quantifier depth  size", "function")?;
+    for (name, metrics) in rows {
+        writeln!(
+            out,
+            "{:width$}  {:>11}  {:>16}  {:>4}",
+            name, metrics.obligations, metrics.quantifier_depth, metrics.nodes
+        )?;
+    }
     Ok(())
 }
 
@@ -166,9 +275,9 @@ pub fn prelude_imports(type_import: bool) -> Vec<Decl> {
         Decl::UseDecl(Use { name: QName::from_string("prelude.UInt16").unwrap() }),
         Decl::UseDecl(Use { name: QName::from_string("mach.int.UInt32").unwrap() }),
         Decl::UseDecl(Use { name: QName::from_string("mach.int.UInt64").unwrap() }),
-        Decl::UseDecl(Use { name: QName::from_string("string.Char").unwrap() }),
-        Decl::UseDecl(Use { name: QName::from_string("floating_point.Single").unwrap() }),
-        Decl::UseDecl(Use { name: QName::from_string("floating_point.Double").unwrap() }),
+        Decl::UseDecl(Use { name: QName::from_string("prelude.Char").unwrap() }),
+        Decl::UseDecl(Use { name: QName::from_string("prelude.Float32").unwrap() }),
+        Decl::UseDecl(Use { name: QName::from_string("prelude.Float64").unwrap() }),
         Decl::UseDecl(Use { name: QName::from_string("seq.Seq").unwrap() }),
         Decl::UseDecl(Use { name: QName::from_string("prelude.Prelude").unwrap() }),
     ];
@@ -179,34 +288,196 @@ pub fn prelude_imports(type_import: bool) -> Vec<Decl> {
     imports
 }
 
+fn type_module<'a>(types: impl Iterator<Item = &'a TypeDeclaration>) -> Module {
+    Module {
+        name: "Type".into(),
+        decls: prelude_imports(false)
+            .into_iter()
+            .chain(types.flat_map(|ty| {
+                std::iter::once(Decl::TyDecl(ty.ty_decl.clone())).chain(ty.accessors().cloned())
+            }))
+            .collect(),
+    }
+}
+
 fn print_crate<'a, W, I: Iterator<Item = &'a Module>>(
     out: &mut W,
     _name: String,
     types: impl Iterator<Item = &'a TypeDeclaration>,
     functions: I,
+    width: usize,
 ) -> std::io::Result<()>
 where
     W: Write,
 {
-    let (alloc, mut pe) = mlcfg::printer::PrintEnv::new();
+    let type_mod = type_module(types);
 
-    let type_mod = Module {
-        name: "Type".into(),
-        decls: prelude_imports(false)
-            .into_iter()
-            .chain(types.flat_map(|ty| {
-                std::iter::once(Decl::TyDecl(ty.ty_decl.clone())).chain(ty.accessors().cloned())
-            }))
-            .collect(),
-    };
-
-    type_mod.pretty(&alloc, &mut pe).1.render(120, out)?;
+    writeln!(out, "{}", version_header())?;
+    type_mod.render(width, out)?;
     writeln!(out)?;
 
-    for modl in functions {
-        modl.pretty(&alloc, &mut pe).1.render(120, out)?;
-        writeln!(out)?;
+    let functions = dependency_order(functions.collect());
+    for rendered in render_modules(&functions, width) {
+        out.write_all(&rendered?)?;
     }
 
     Ok(())
 }
+
+// The same modules as the MLCFG output, in the same order, along with the versions found in its
+// header
+fn print_crate_json<'a>(
+    out: &mut dyn Write,
+    types: impl Iterator<Item = &'a TypeDeclaration>,
+    functions: impl Iterator<Item = &'a Module>,
+) -> std::io::Result<()> {
+    let type_mod = type_module(types);
+    let modules: Vec<_> =
+        std::iter::once(&type_mod).chain(dependency_order(functions.collect())).collect();
+
+    let json = serde_json::json!({
+        "format": MLCFG_FORMAT_VERSION,
+        "prelude": PRELUDE_VERSION,
+        "modules": modules,
+    });
+    serde_json::to_writer_pretty(&mut *out, &json)?;
+    writeln!(out)
+}
+
+// Items are recorded once their translation finishes, so a module can be recorded after one
+// which clones it, as happens with mutually recursive functions. Why3 rejects such forward
+// references, so modules are emitted after every module they clone or use, keeping the original
+// order otherwise.
+fn dependency_order<'a>(modules: Vec<&'a Module>) -> Vec<&'a Module> {
+    fn dependencies<'a>(decls: &'a [Decl], deps: &mut Vec<&'a str>) {
+        for decl in decls {
+            match decl {
+                Decl::Clone(DeclClone { name, .. }) | Decl::UseDecl(Use { name })
+                    if name.module.is_empty() =>
+                {
+                    deps.push(&*name.name)
+                }
+                Decl::Scope(Scope { decls, .. }) | Decl::Module(Module { decls, .. }) => {
+                    dependencies(decls, deps)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn visit<'a>(
+        ix: usize,
+        modules: &[&'a Module],
+        index: &HashMap<&str, usize>,
+        visited: &mut [bool],
+        order: &mut Vec<&'a Module>,
+    ) {
+        if std::mem::replace(&mut visited[ix], true) {
+            return;
+        }
+
+        let mut deps = Vec::new();
+        dependencies(&modules[ix].decls, &mut deps);
+        for dep in deps {
+            if let Some(&dep_ix) = index.get(dep) {
+                visit(dep_ix, modules, index, visited, order);
+            }
+        }
+        order.push(modules[ix]);
+    }
+
+    let index: HashMap<&str, usize> =
+        modules.iter().enumerate().map(|(ix, modl)| (&*modl.name, ix)).collect();
+    let mut visited = vec![false; modules.len()];
+    let mut order = Vec::with_capacity(modules.len());
+    for ix in 0..modules.len() {
+        visit(ix, &modules, &index, &mut visited, &mut order);
+    }
+    order
+}
+
+// Printing dominates the time spent emitting crates with many items, so modules are rendered
+// in parallel, each with its own printing environment, and then written out in order
+fn render_modules<'a>(modules: &'a [&'a Module], width: usize) -> Vec<std::io::Result<Vec<u8>>> {
+    in_parallel(modules, move |modl| {
+        let mut buf = Vec::new();
+        modl.render(width, &mut buf)?;
+        writeln!(buf)?;
+        Ok(buf)
+    })
+}
+
+// Writes every module to its own file of `dir`, from as many threads as there are cores. The file
+// of a module is named after the crate and the module, and references to the other modules of the
+// crate are qualified with their file name, so that Why3 finds them once `dir` is in its load path.
+fn print_crate_files<'a>(
+    dir: &Path,
+    crate_name: &str,
+    types: impl Iterator<Item = &'a TypeDeclaration>,
+    functions: impl Iterator<Item = &'a Module>,
+    width: usize,
+) -> std::io::Result<()> {
+    fn qualify(decls: &mut [Decl], crate_name: &str, local: &HashSet<String>) {
+        for decl in decls {
+            match decl {
+                Decl::Clone(DeclClone { name, .. }) | Decl::UseDecl(Use { name })
+                    if name.module.is_empty() && local.contains(&*name.name) =>
+                {
+                    name.module = vec![module_file_stem(crate_name, &name.name).into()];
+                }
+                Decl::Scope(Scope { decls, .. }) | Decl::Module(Module { decls, .. }) => {
+                    qualify(decls, crate_name, local)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    std::fs::create_dir_all(dir)?;
+    let type_mod = type_module(types);
+    let modules: Vec<_> =
+        std::iter::once(&type_mod).chain(dependency_order(functions.collect())).collect();
+    let local: HashSet<_> = modules.iter().map(|modl| String::from(&*modl.name)).collect();
+
+    let files: Vec<_> = modules
+        .into_iter()
+        .map(|modl| {
+            let mut modl = modl.clone();
+            qualify(&mut modl.decls, crate_name, &local);
+            (dir.join(format!("{}.mlcfg", module_file_stem(crate_name, &modl.name))), modl)
+        })
+        .collect();
+
+    let written = in_parallel(&files, move |(path, modl)| {
+        let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writeln!(out, "{}", version_header())?;
+        modl.render(width, &mut out)?;
+        writeln!(out)?;
+        out.flush()
+    });
+    written.into_iter().collect()
+}
+
+// Why3 takes the first lowercase components of a qualified name as a file name. Prefixing the
+// module with the crate name makes sure it starts with one and isn't a keyword such as `type`.
+fn module_file_stem(crate_name: &str, module: &str) -> String {
+    format!("{}_{}", crate_name.to_lowercase(), module)
+}
+
+// Applies `f` to every item, splitting them in one chunk per core, and returns the results in order
+fn in_parallel<'a, T: Sync, R: Send>(
+    items: &'a [T],
+    f: impl Fn(&'a T) -> R + Send + Copy + 'a,
+) -> Vec<R> {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = std::cmp::max(1, (items.len() + threads - 1) / threads);
+
+    // Without the explicit lifetime, borrowing `items` from the spawned threads is rejected
+    std::thread::scope::<'a, _, _>(|s| {
+        let handles: Vec<_> = items
+            .chunks(chunk_size)
+            .map(|chunk| s.spawn(move || chunk.iter().map(f).collect::<Vec<_>>()))
+            .collect();
+        handles.into_iter().flat_map(|h| h.join().unwrap()).collect()
+    })
+}
@@ -1,13 +1,17 @@
+use rustc_hir::def::DefKind;
 use rustc_hir::def_id::DefId;
-use rustc_middle::mir::interpret::{AllocRange, ConstValue};
+use rustc_middle::mir::interpret::{read_target_uint, AllocRange, ConstValue, Scalar};
 use rustc_middle::{
     mir::ConstantKind,
-    ty::{Const, ConstKind, ParamEnv, Ty, TyCtxt, Unevaluated},
+    ty::{
+        subst::InternalSubsts, AssocItemContainer::TraitContainer, Const, ConstKind, ParamEnv, Ty,
+        TyCtxt, Unevaluated,
+    },
 };
 use rustc_span::Span;
 use rustc_target::abi::Size;
 use why3::{
-    declaration::Module,
+    declaration::{Contract, Decl, Logic, Module, Signature, ValKind},
     exp::{Constant, Exp},
     QName,
 };
@@ -15,17 +19,75 @@ use why3::{
 use crate::{
     clone_map::CloneMap,
     ctx::{module_name, CloneSummary, TranslationCtx},
-    translation::ty,
-    util::get_builtin,
+    function::all_generic_decls_for,
+    translation::{traits, ty},
+    util::{get_builtin, ident_of, item_name},
 };
 
 impl<'tcx> TranslationCtx<'_, 'tcx> {
+    // Constants and immutable statics are translated as logical constants. Those which can be
+    // evaluated to a literal are defined by their value, the others (associated constants of
+    // traits, constants depending on generic parameters or of a compound type) are left abstract,
+    // and trait constants are refined by the implementations of their trait.
     pub fn translate_constant(&mut self, def_id: DefId) -> (Module, CloneSummary<'tcx>) {
-        let names = CloneMap::new(self.tcx, def_id, false);
+        let mut names = CloneMap::new(self.tcx, def_id, true);
+        self.translated_items.insert(def_id);
 
-        let modl = Module { name: module_name(self.tcx, def_id), decls: Vec::new() };
+        let mut decls: Vec<_> = all_generic_decls_for(self, def_id).collect();
+        let span = self.tcx.def_span(def_id);
+        let param_env = self.param_env(def_id);
+        let ty = self.tcx.type_of(def_id);
 
-        (modl, names.summary())
+        let retty = names.with_public_clones(|names| ty::translate_ty(self, names, span, ty));
+        let sig = Signature {
+            name: item_name(self.tcx, def_id),
+            attrs: Vec::new(),
+            args: Vec::new(),
+            retty: Some(retty),
+            contract: Contract::new(),
+        };
+
+        let value = match self.tcx.opt_associated_item(def_id).map(|item| item.container) {
+            Some(TraitContainer(_)) => None,
+            _ if !is_literal_ty(ty) => None,
+            _ if self.tcx.is_static(def_id) => self.eval_static_scalar(def_id, param_env, ty),
+            _ => self.tcx.const_eval_poly(def_id).ok(),
+        };
+
+        let decl = match value {
+            Some(value) => {
+                let value = ConstantKind::Val(value, ty);
+                let body = names.with_public_clones(|names| {
+                    from_mir_constant_kind(self, names, value, param_env, span)
+                });
+                Decl::LogicDecl(Logic { sig, body })
+            }
+            None => Decl::ValDecl(ValKind::Function { sig }),
+        };
+
+        decls.extend(names.to_clones(self));
+        decls.push(decl);
+
+        (Module { name: module_name(self.tcx, def_id), decls }, names.summary())
+    }
+
+    // Statics cannot go through `const_eval_poly`: their value is an allocation, from which we read
+    // the scalar. Statics of reference type hold a pointer and are left abstract.
+    fn eval_static_scalar(
+        &self,
+        def_id: DefId,
+        param_env: ParamEnv<'tcx>,
+        ty: Ty<'tcx>,
+    ) -> Option<ConstValue<'tcx>> {
+        if ty.is_ref() || ty.is_unit() {
+            return None;
+        }
+
+        let alloc = self.tcx.eval_static_initializer(def_id).ok()?;
+        let size = self.tcx.layout_of(param_env.and(ty)).ok()?.size;
+        let bytes = alloc.inner().get_bytes(&self.tcx, AllocRange { start: Size::ZERO, size }).ok()?;
+        let bits = read_target_uint(self.tcx.data_layout.endian, bytes).ok()?;
+        Some(ConstValue::Scalar(Scalar::from_uint(bits, size)))
     }
 }
 
@@ -35,6 +97,16 @@ pub fn from_mir_constant<'tcx>(
     names: &mut CloneMap<'tcx>,
     c: &rustc_middle::mir::Constant<'tcx>,
 ) -> Exp {
+    // A use of a static is a constant reference to its allocation. Shared references being
+    // transparent, this is the logical constant the static is translated to.
+    if let Some(def_id) = c.check_static_ptr(ctx.tcx) {
+        if ctx.tcx.is_mutable_static(def_id) {
+            ctx.crash_and_error(c.span, "mutable statics are not supported");
+        }
+        ctx.translate(def_id);
+        return Exp::pure_qvar(names.insert(def_id, InternalSubsts::empty()).qname(ctx.tcx, def_id));
+    }
+
     from_mir_constant_kind(ctx, names, c.literal, env, c.span)
 }
 
@@ -50,7 +122,7 @@ pub fn from_mir_constant_kind<'tcx>(
     }
 
     if ck.ty().is_unit() {
-        return Exp::Tuple(Vec::new());
+        return Exp::Const(Constant::Unit);
     }
 
     if ck.ty().peel_refs().is_str() {
@@ -86,13 +158,42 @@ pub fn from_ty_const<'tcx>(
         return Exp::impure_var(format!("promoted{:?}", p.as_usize()).into());
     }
 
-    if let ConstKind::Param(_) = c.val() {
-        ctx.crash_and_error(span, "const generic parameters are not yet supported");
+    // Const generic parameters are declared as logical constants of the module
+    if let ConstKind::Param(p) = c.val() {
+        return Exp::pure_var(ident_of(p.name));
+    }
+
+    // Constants depending on generic parameters cannot be evaluated, and those of compound types
+    // cannot be written as literals, so we refer to the logical constant they are translated to
+    if let ConstKind::Unevaluated(u) = c.val() &&
+       matches!(ctx.tcx.def_kind(u.def.did), DefKind::Const | DefKind::AssocConst) &&
+       (!is_literal_ty(c.ty()) || matches!(c.eval(ctx.tcx, env).val(), ConstKind::Unevaluated(_))) {
+        let (def_id, subst) =
+            traits::resolve_opt(ctx.tcx, env, u.def.did, u.substs).unwrap_or((u.def.did, u.substs));
+        ctx.translate(def_id);
+        return Exp::pure_qvar(names.insert(def_id, subst).qname(ctx.tcx, def_id));
+    }
+
+    // Other constants, like the anonymous constants of array lengths, are written as literals,
+    // which requires evaluating them
+    if let ConstKind::Unevaluated(_) = c.eval(ctx.tcx, env).val() {
+        ctx.crash_and_error(
+            span,
+            "constant expressions depending on generic parameters are not supported",
+        )
     }
 
     return try_to_bits(ctx, names, env, c.ty(), span, c);
 }
 
+// Whether constants of this type can be translated to Why3 literals
+fn is_literal_ty(ty: Ty) -> bool {
+    use rustc_middle::ty::TyKind::{Bool, Char, Float, Int, Uint};
+    matches!(ty.kind(), Bool | Char | Int(_) | Uint(_) | Float(_))
+        || ty.is_unit()
+        || ty.peel_refs().is_str()
+}
+
 fn try_to_bits<'tcx, C: ToBits<'tcx>>(
     ctx: &mut TranslationCtx<'_, 'tcx>,
     names: &mut CloneMap<'tcx>,
@@ -101,8 +202,8 @@ fn try_to_bits<'tcx, C: ToBits<'tcx>>(
     span: Span,
     c: C,
 ) -> Exp {
-    use rustc_middle::ty::TyKind::{Bool, Int, Uint};
-    use rustc_middle::ty::{IntTy::*, UintTy::*};
+    use rustc_middle::ty::TyKind::{Bool, Char, Float, Int, Uint};
+    use rustc_middle::ty::{FloatTy::*, IntTy::*, UintTy::*};
     let why3_ty = ty::translate_ty(ctx, names, span, ty);
 
     match ty.kind() {
@@ -116,7 +217,7 @@ fn try_to_bits<'tcx, C: ToBits<'tcx>>(
         }
         Int(Isize) => {
             let bits = c.get_bits(ctx.tcx, env, ty);
-            Exp::Const(Constant::Uint(bits.unwrap() as isize as u128, Some(why3_ty)))
+            Exp::Const(Constant::Int(bits.unwrap() as isize as i128, Some(why3_ty)))
         }
         Int(I32) => {
             let bits = c.get_bits(ctx.tcx, env, ty);
@@ -154,6 +255,17 @@ fn try_to_bits<'tcx, C: ToBits<'tcx>>(
             let bits = c.get_bits(ctx.tcx, env, ty);
             Exp::Const(Constant::Uint(bits.unwrap() as u8 as u128, Some(why3_ty)))
         }
+        Float(fty) => {
+            let bits = c.get_bits(ctx.tcx, env, ty).unwrap();
+            let value = match fty {
+                F32 => f32::from_bits(bits as u32) as f64,
+                F64 => f64::from_bits(bits as u64),
+            };
+            if !value.is_finite() {
+                ctx.crash_and_error(span, "infinite and NaN float constants are not supported");
+            }
+            Exp::Const(Constant::float(value, Some(why3_ty)))
+        }
         Bool => {
             if c.get_bits(ctx.tcx, env, ty) == Some(1) {
                 Exp::mk_true()
@@ -161,7 +273,13 @@ fn try_to_bits<'tcx, C: ToBits<'tcx>>(
                 Exp::mk_false()
             }
         }
-        _ if ty.is_unit() => Exp::Tuple(Vec::new()),
+        Char => {
+            let bits = c.get_bits(ctx.tcx, env, ty);
+            let c = char::from_u32(bits.unwrap() as u32).unwrap();
+            names.import_builtin_module(QName::from_string("prelude.Char").unwrap());
+            Exp::Const(Constant::Char(c))
+        }
+        _ if ty.is_unit() => Exp::Const(Constant::Unit),
         _ => {
             ctx.crash_and_error(
                 span,
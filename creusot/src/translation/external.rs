@@ -24,7 +24,7 @@ pub fn default_decl<'tcx>(
     let mut names = CloneMap::new(ctx.tcx, def_id, false);
 
     let mut decls: Vec<_> = Vec::new();
-    decls.extend(all_generic_decls_for(ctx.tcx, def_id));
+    decls.extend(all_generic_decls_for(ctx, def_id));
 
     let mut sig = crate::util::signature_of(ctx, &mut names, def_id);
     let name = module_name(ctx.tcx, def_id);
@@ -6,8 +6,9 @@ use crate::{
         ty::{closure_accessors, translate_closure_ty, translate_ty},
     },
     util::{self, ident_of, is_ghost_closure, signature_of},
+    validate::validate_no_raw_pointers,
 };
-use rustc_borrowck::borrow_set::BorrowSet;
+use rustc_borrowck::{borrow_set::BorrowSet, consumers::get_body_with_borrowck_facts};
 use rustc_hir::def_id::DefId;
 use rustc_index::bit_set::BitSet;
 use rustc_infer::infer::TyCtxtInferExt;
@@ -18,17 +19,21 @@ use rustc_middle::ty::{
 use rustc_middle::ty::{GenericParamDef, GenericParamDefKind};
 use rustc_middle::ty::{ParamEnv, Ty};
 use rustc_middle::{
-    mir::traversal::preorder,
-    mir::{BasicBlock, Body, Local, Location, MirPass, Operand, VarDebugInfo},
+    mir::traversal::{preorder, reachable_as_bitset},
+    mir::{BasicBlock, Body, Local, Location, MirPass, Operand, StatementKind, VarDebugInfo},
     ty::TyCtxt,
     ty::{TyKind, WithOptConstParam},
 };
-use rustc_middle::{mir::Place, ty::DefIdTree};
+use rustc_middle::{
+    mir::{Field, Place},
+    ty::DefIdTree,
+};
 use rustc_mir_dataflow::move_paths::MoveData;
 use rustc_mir_transform::{remove_false_edges::*, simplify::*};
 use rustc_span::{Symbol, DUMMY_SP};
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::rc::Rc;
+use std::time::Instant;
 use why3::{declaration::*, Ident};
 use why3::{
     exp::*,
@@ -38,12 +43,14 @@ use why3::{
 
 use indexmap::IndexMap;
 
+mod inline;
 mod place;
 mod promoted;
 mod statement;
 mod terminator;
 
 use crate::ctx::*;
+use crate::options::MirSource;
 use crate::translation::{traits, ty};
 
 pub fn translate_function<'tcx, 'sess>(
@@ -60,16 +67,43 @@ pub fn translate_function<'tcx, 'sess>(
         return translate_trusted(tcx, ctx, def_id);
     }
 
-    // We use `mir_promoted` as it is the MIR required by borrowck which we will have run by this point
-    let (body, promoted) = tcx.mir_promoted(WithOptConstParam::unknown(def_id.expect_local()));
-    let mut body = body.borrow().clone();
+    let (mut body, promoted) = match ctx.opts.mir_source {
+        // We use `mir_promoted` as it is the MIR required by borrowck which we will have run by this point
+        MirSource::Built if !ctx.opts.polonius => ctx.built_body(def_id.expect_local()),
+        MirSource::Built | MirSource::Promoted => ctx.body_with_promoted(def_id.expect_local()),
+        MirSource::Optimized => {
+            if ctx.opts.polonius {
+                ctx.crash_and_error(
+                    ctx.def_span(def_id),
+                    "borrowck facts are only available for promoted MIR",
+                );
+            }
+            (tcx.optimized_mir(def_id).clone(), tcx.promoted_mir(def_id).clone())
+        }
+    };
+    let start = Instant::now();
     // Basic clean up, replace FalseEdges with Gotos. Could potentially also replace other statement with Nops.
     // Investigate if existing MIR passes do this as part of 'post borrowck cleanup'.
     RemoveFalseEdges.run_pass(tcx, &mut body);
-    SimplifyCfg::new("verify").run_pass(tcx, &mut body);
+
+    let loans = if ctx.opts.polonius {
+        // Only remove unreachable blocks so that locations can be mapped back to the borrowck facts.
+        let blocks = reachable_as_bitset(&body).iter().collect();
+        remove_dead_blocks(tcx, &mut body);
+        let _span = tracing::info_span!("borrowck_facts").entered();
+        let local_id = def_id.expect_local();
+        let facts = get_body_with_borrowck_facts(tcx, WithOptConstParam::unknown(local_id));
+        Some(LoanLiveness::new(tcx, facts, blocks))
+    } else {
+        SimplifyCfg::new("verify").run_pass(tcx, &mut body);
+        None
+    };
+    tracing::info!(elapsed = ?start.elapsed(), "mir cleanup");
+
+    validate_no_raw_pointers(ctx, &body);
 
     let mut decls = Vec::new();
-    decls.extend(closure_generic_decls(ctx.tcx, def_id));
+    decls.extend(closure_generic_decls(ctx, def_id));
 
     if ctx.tcx.is_closure(def_id) {
         if let TyKind::Closure(_, subst) = ctx.tcx.type_of(def_id).kind() {
@@ -82,7 +116,7 @@ pub fn translate_function<'tcx, 'sess>(
     }
 
     let param_env = ctx.param_env(def_id);
-    for p in promoted.borrow().iter_enumerated() {
+    for p in promoted.iter_enumerated() {
         if is_ghost_closure(ctx.tcx, p.1.return_ty()).is_some() {
             continue;
         }
@@ -100,9 +134,14 @@ pub fn translate_function<'tcx, 'sess>(
         .and_then(|span| ctx.span_attr(span))
         .map(|attr| sig.attrs.push(attr));
 
-    let func_translator = BodyTranslator::build_context(tcx, ctx, &body, &mut names, sig, def_id);
+    let start = Instant::now();
+    let func_translator =
+        BodyTranslator::build_context(tcx, ctx, &body, &mut names, sig, def_id, loans);
+    tracing::info!(elapsed = ?start.elapsed(), "build_context");
 
+    let start = Instant::now();
     decls.extend(func_translator.translate());
+    tracing::info!(elapsed = ?start.elapsed(), "translate_body");
     let name = module_name(ctx.tcx, def_id);
     Module { name, decls }
 }
@@ -115,7 +154,7 @@ pub fn translate_trusted<'tcx>(
     let mut names = CloneMap::new(tcx, def_id, true);
     names.clone_self(def_id);
     let mut decls = Vec::new();
-    decls.extend(all_generic_decls_for(tcx, def_id));
+    decls.extend(all_generic_decls_for(ctx, def_id));
 
     let sig = signature_of(ctx, &mut names, def_id);
     let name = module_name(tcx, def_id);
@@ -126,7 +165,66 @@ pub fn translate_trusted<'tcx>(
     return Module { name, decls };
 }
 
-use crate::resolve::EagerResolver;
+fn variant_var(header: BasicBlock) -> Ident {
+    format!("variant_prev'{}", header.index()).into()
+}
+
+// The value of the variant `exp` is smaller than the one saved in `prev` at the loop header
+fn variant_decreases(tcx: TyCtxt, exp: Exp, ty: Ty, prev: Ident) -> Option<Exp> {
+    let prev = Exp::pure_var(prev);
+    let lower_bound = match ty.kind() {
+        TyKind::Uint(_) => None,
+        TyKind::Int(_) => Some(Exp::Const(Constant::Int(0, None))),
+        TyKind::Adt(def, _)
+            if Some(def.did()) == tcx.get_diagnostic_item(Symbol::intern("creusot_int")) =>
+        {
+            Some(Exp::Const(Constant::Int(0, None)))
+        }
+        _ => return None,
+    };
+    let decreases = Exp::BinaryOp(BinOp::Lt, box exp, box prev.clone());
+    Some(match lower_bound {
+        Some(zero) => Exp::BinaryOp(BinOp::Le, box zero, box prev).and(decreases),
+        None => decreases,
+    })
+}
+
+// Past these sizes, provers tend to struggle with the generated verification conditions
+const MAX_NODES: usize = 5000;
+const MAX_QUANTIFIER_DEPTH: usize = 4;
+const MAX_OBLIGATIONS: usize = 100;
+
+fn check_metrics(ctx: &mut TranslationCtx, def_id: DefId, func: &CfgFunction, calls: usize) {
+    let mut metrics = func.metrics();
+    metrics.obligations += calls;
+    tracing::info!(?metrics, "function size");
+    ctx.record_metrics(def_id, metrics);
+
+    let mut reasons = Vec::new();
+    if metrics.nodes > MAX_NODES {
+        reasons.push(format!("{} expression nodes", metrics.nodes));
+    }
+    if metrics.quantifier_depth > MAX_QUANTIFIER_DEPTH {
+        reasons.push(format!("quantifiers nested {} deep", metrics.quantifier_depth));
+    }
+    if metrics.obligations > MAX_OBLIGATIONS {
+        reasons.push(format!("{} proof obligations", metrics.obligations));
+    }
+
+    if !reasons.is_empty() {
+        ctx.warn(
+            ctx.def_span(def_id),
+            &format!(
+                "verification conditions of `{}` may be hard to prove ({}), consider splitting the function or moving parts of the specification into lemmas",
+                ctx.tcx.def_path_str(def_id),
+                reasons.join(", ")
+            ),
+        );
+    }
+}
+
+use crate::analysis::StorageOnly;
+use crate::resolve::{EagerResolver, LoanLiveness};
 
 // Split this into several sub-contexts: Core, Analysis, Results?
 pub struct BodyTranslator<'body, 'sess, 'tcx> {
@@ -149,6 +247,9 @@ pub struct BodyTranslator<'body, 'sess, 'tcx> {
 
     past_blocks: BTreeMap<mlcfg::BlockId, mlcfg::Block>,
 
+    // Edges `(pred, succ, drop)` to redirect through the drop block `drop` once `pred` is translated
+    drop_edges: Vec<(BlockId, BlockId, BlockId)>,
+
     // Type translation context
     ctx: &'body mut TranslationCtx<'sess, 'tcx>,
 
@@ -160,8 +261,18 @@ pub struct BodyTranslator<'body, 'sess, 'tcx> {
 
     invariants: IndexMap<BasicBlock, Vec<(Symbol, Exp)>>,
 
+    // Variants of the loops starting at each block, saved at every iteration in a ghost variable
+    loop_variants: IndexMap<BasicBlock, (Exp, Ty<'tcx>)>,
+    variant_vars: Vec<(bool, Ident, Type)>,
+
     assertions: IndexMap<DefId, Exp>,
 
+    // Fields written to deinitialized places, only selected by the following `SetDiscriminant`
+    variant_fields: HashMap<Place<'tcx>, Vec<(Field, Exp)>>,
+
+    // Calls to other program functions, each of which has to establish a precondition
+    calls: usize,
+
     borrows: Rc<BorrowSet<'tcx>>,
 }
 
@@ -173,8 +284,13 @@ impl<'body, 'sess, 'tcx> BodyTranslator<'body, 'sess, 'tcx> {
         names: &'body mut CloneMap<'tcx>,
         sig: Signature,
         def_id: DefId,
+        loans: Option<LoanLiveness>,
     ) -> Self {
-        let (invariants, assertions) =
+        if ctx.opts.check_statements {
+            statement::check_statements(ctx, body);
+        }
+
+        let (invariants, assertions, loop_variants) =
             corrected_invariant_names_and_locations(ctx, names, def_id, &body);
         let mut erased_locals = BitSet::new_empty(body.local_decls.len());
 
@@ -186,8 +302,19 @@ impl<'body, 'sess, 'tcx> BodyTranslator<'body, 'sess, 'tcx> {
             }
         });
 
+        erased_locals.union(&StorageOnly::for_body(body));
+
         let mut clean_body = body.clone();
 
+        // Move path gathering rejects the deaggregation statements found in optimized MIR, they
+        // don't create borrows so we can safely erase them.
+        for stmt in clean_body.basic_blocks_mut().iter_mut().flat_map(|bbd| &mut bbd.statements) {
+            if matches!(stmt.kind, StatementKind::Deinit(_) | StatementKind::SetDiscriminant { .. })
+            {
+                stmt.make_nop();
+            }
+        }
+
         tcx.infer_ctxt().enter(|infcx| {
             renumber::renumber_mir(&infcx, &mut clean_body, &mut Default::default());
         });
@@ -195,7 +322,7 @@ impl<'body, 'sess, 'tcx> BodyTranslator<'body, 'sess, 'tcx> {
             .unwrap_or_else(|_| ctx.crash_and_error(ctx.def_span(def_id), "illegal move"));
         let borrows = BorrowSet::build(tcx, &clean_body, true, &move_paths);
         let borrows = Rc::new(borrows);
-        let resolver = EagerResolver::new(tcx, body, borrows.clone());
+        let resolver = EagerResolver::new(tcx, body, borrows.clone(), loans);
 
         // TODO: Remove?
         let local_map = real_locals(tcx, body);
@@ -210,11 +337,16 @@ impl<'body, 'sess, 'tcx> BodyTranslator<'body, 'sess, 'tcx> {
             local_map,
             current_block: (Vec::new(), None),
             past_blocks: BTreeMap::new(),
+            drop_edges: Vec::new(),
             ctx,
             fresh_id: body.basic_blocks().len(),
             names,
             invariants,
+            loop_variants,
+            variant_vars: Vec::new(),
             assertions,
+            variant_fields: HashMap::new(),
+            calls: 0,
             borrows,
         }
     }
@@ -225,6 +357,8 @@ impl<'body, 'sess, 'tcx> BodyTranslator<'body, 'sess, 'tcx> {
         decls.extend(self.names.to_clones(self.ctx));
 
         self.translate_body();
+        self.insert_drop_blocks();
+        self.remove_orphan_blocks();
 
         let arg_count = self.body.arg_count;
         let vars = self.translate_vars();
@@ -246,14 +380,20 @@ impl<'body, 'sess, 'tcx> BodyTranslator<'body, 'sess, 'tcx> {
         };
         decls.extend(self.names.to_clones(self.ctx));
 
-        decls.push(Decl::FunDecl(CfgFunction {
+        let func = CfgFunction {
             sig: self.sig,
             rec: true,
             constant: false,
-            vars: vars.into_iter().map(|i| (i.0, i.1.ident(), i.2)).collect(),
+            vars: vars
+                .into_iter()
+                .map(|i| (i.0, i.1.ident(), i.2))
+                .chain(std::mem::take(&mut self.variant_vars))
+                .collect(),
             entry,
             blocks: self.past_blocks,
-        }));
+        };
+        check_metrics(self.ctx, self.def_id, &func, self.calls);
+        decls.push(Decl::FunDecl(func));
         decls
     }
 
@@ -268,6 +408,12 @@ impl<'body, 'sess, 'tcx> BodyTranslator<'body, 'sess, 'tcx> {
                 self.emit_statement(Invariant(name.to_string().into(), body));
             }
 
+            if let Some((variant, ty)) = self.loop_variants.get(&bb).cloned() {
+                let ty = ty::translate_ty(self.ctx, self.names, DUMMY_SP, ty);
+                self.variant_vars.push((true, variant_var(bb), ty));
+                self.emit_statement(Assign { lhs: variant_var(bb), rhs: Exp::Pure(box variant) });
+            }
+
             self.freeze_locals_between_blocks(bb);
 
             let mut loc = bb.start_location();
@@ -279,6 +425,7 @@ impl<'body, 'sess, 'tcx> BodyTranslator<'body, 'sess, 'tcx> {
             }
 
             self.translate_terminator(bbd.terminator(), loc);
+            self.check_loop_variant(bb);
 
             self.past_blocks.insert(
                 BlockId(bb.into()),
@@ -290,6 +437,64 @@ impl<'body, 'sess, 'tcx> BodyTranslator<'body, 'sess, 'tcx> {
         }
     }
 
+    // Jumping back to the header of a loop with a variant has to decrease it
+    fn check_loop_variant(&mut self, bb: BasicBlock) {
+        let back_edges: Vec<_> = self.body[bb]
+            .terminator()
+            .successors()
+            .filter(|succ| {
+                self.loop_variants.contains_key(*succ)
+                    && self.body.dominators().is_dominated_by(bb, **succ)
+            })
+            .copied()
+            .collect();
+
+        for header in back_edges {
+            match self.current_block.1 {
+                Some(Terminator::Goto(BlockId(target))) if target == header.index() => {}
+                _ => self.ctx.crash_and_error(
+                    self.body[bb].terminator().source_info.span,
+                    "loop variants are only supported when the loop is continued unconditionally",
+                ),
+            }
+
+            let (variant, ty) = &self.loop_variants[&header];
+            let decreases = variant_decreases(self.tcx, variant.clone(), *ty, variant_var(header))
+                .unwrap_or_else(|| {
+                    self.ctx.crash_and_error(
+                        self.body[bb].terminator().source_info.span,
+                        &format!("unsupported type for a loop variant: {}", ty),
+                    )
+                });
+            self.emit_statement(Assert(decreases));
+        }
+    }
+
+    // Blocks are translated in preorder, so a join point may be reached before some of its
+    // predecessors: their terminators can only be redirected once the whole body is translated.
+    fn insert_drop_blocks(&mut self) {
+        for (pred, succ, drop) in std::mem::take(&mut self.drop_edges) {
+            self.past_blocks.get_mut(&pred).unwrap().terminator.retarget(succ, drop);
+        }
+    }
+
+    // Exhaustive switches drop their `otherwise` edge, which leaves the `absurd` block it pointed
+    // to without predecessors. Remove such blocks so they don't give rise to spurious goals.
+    fn remove_orphan_blocks(&mut self) {
+        let mut reachable = HashSet::new();
+        let mut todo = vec![BlockId(0)];
+        while let Some(id) = todo.pop() {
+            // A target may be a block which was never translated
+            if !reachable.insert(id) {
+                continue;
+            }
+            if let Some(block) = self.past_blocks.get(&id) {
+                todo.extend(block.terminator.targets());
+            }
+        }
+        self.past_blocks.retain(|id, _| reachable.contains(id));
+    }
+
     fn translate_vars(&mut self) -> Vec<(bool, LocalIdent, Type)> {
         let mut vars = Vec::with_capacity(self.body.local_decls.len());
 
@@ -360,6 +565,8 @@ impl<'body, 'sess, 'tcx> BodyTranslator<'body, 'sess, 'tcx> {
             return;
         }
 
+        // Statements already emitted for `bb` (such as its invariants) must stay in it
+        let header = std::mem::take(&mut self.current_block.0);
         for pred in pred_blocks {
             let dying = self.resolver.locals_resolved_between_blocks(*pred, bb);
 
@@ -375,16 +582,13 @@ impl<'body, 'sess, 'tcx> BodyTranslator<'body, 'sess, 'tcx> {
             let pred_id = BlockId(pred.index());
 
             // Otherwise, we emit the deaths and move them to a stand-alone block.
-            self.past_blocks
-                .get_mut(&pred_id)
-                .unwrap()
-                .terminator
-                .retarget(BlockId(bb.index()), drop_block);
+            self.drop_edges.push((pred_id, BlockId(bb.index()), drop_block));
             self.past_blocks.insert(
                 drop_block,
                 Block { statements: deaths, terminator: Terminator::Goto(BlockId(bb.into())) },
             );
         }
+        self.current_block.0 = header;
     }
 
     fn fresh_block_id(&mut self) -> BlockId {
@@ -403,8 +607,21 @@ impl<'body, 'sess, 'tcx> BodyTranslator<'body, 'sess, 'tcx> {
         let param_env = self.param_env();
 
         for local in dying.iter() {
+            tracing::info!(?local, "resolving dead local");
             let local_ty = self.body.local_decls[local].ty;
             let ident = self.translate_local(local).ident();
+
+            // Resolving a mutable borrow fixes its final value to its current one, which must
+            // then satisfy the invariant of the borrowed type. Reborrows are checked when they
+            // are themselves resolved, or by the contract of the function they are passed to.
+            if let TyKind::Ref(_, inner, rustc_hir::Mutability::Mut) = local_ty.kind() {
+                let inv = type_invariant_pred(&mut self.ctx, &mut self.names, param_env, *inner);
+                if let Some(inv) = inv {
+                    let cur = Exp::Current(box Exp::impure_var(ident.clone()));
+                    self.emit_statement(Assert(inv.app_to(cur)));
+                }
+            }
+
             resolve_predicate_of(&mut self.ctx, &mut self.names, param_env, local_ty)
                 .emit(Exp::impure_var(ident), self);
         }
@@ -674,6 +891,21 @@ fn resolve_predicate_of<'tcx>(
     }
 }
 
+// The invariant predicate of `ty`, if it has one
+fn type_invariant_pred<'tcx>(
+    ctx: &mut TranslationCtx<'_, 'tcx>,
+    names: &mut CloneMap<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    ty: Ty<'tcx>,
+) -> Option<Exp> {
+    let inv_id = ctx.get_diagnostic_item(Symbol::intern("creusot_type_invariant_method"))?;
+    let subst = ctx.mk_substs([GenericArg::from(ty)].iter());
+    // Only the types with an invariant implement the trait
+    let method = traits::resolve_assoc_item_opt(ctx.tcx, param_env, inv_id, subst)?;
+    ctx.translate(method.0);
+    Some(Exp::impure_qvar(names.insert(method.0, method.1).qname(ctx.tcx, method.0)))
+}
+
 fn resolve_trait_loaded(tcx: TyCtxt) -> bool {
     tcx.get_diagnostic_item(Symbol::intern("creusot_resolve")).is_some()
 }
@@ -681,45 +913,75 @@ fn resolve_trait_loaded(tcx: TyCtxt) -> bool {
 // Closures inherit the generic parameters of the original function they were defined in, but
 // add 3 'ghost' generics tracking metadata about the closure. We choose to erase those parameters,
 // as they contain a function type along with other irrelevant details (for us).
-pub(crate) fn closure_generic_decls(
-    tcx: TyCtxt,
+pub(crate) fn closure_generic_decls<'tcx>(
+    ctx: &mut TranslationCtx<'_, 'tcx>,
     mut def_id: DefId,
-) -> impl Iterator<Item = Decl> + '_ {
+) -> impl Iterator<Item = Decl> {
     loop {
-        if tcx.is_closure(def_id) {
-            def_id = tcx.parent(def_id);
+        if ctx.tcx.is_closure(def_id) {
+            def_id = ctx.tcx.parent(def_id);
         } else {
             break;
         }
     }
 
-    all_generic_decls_for(tcx, def_id)
+    all_generic_decls_for(ctx, def_id)
 }
 
-pub fn all_generic_decls_for(tcx: TyCtxt, def_id: DefId) -> impl Iterator<Item = Decl> + '_ {
+pub fn all_generic_decls_for<'tcx>(
+    ctx: &mut TranslationCtx<'_, 'tcx>,
+    def_id: DefId,
+) -> impl Iterator<Item = Decl> {
+    let tcx = ctx.tcx;
     let generics = tcx.generics_of(def_id);
 
-    generic_decls((0..generics.count()).map(move |i| generics.param_at(i, tcx)))
+    generic_decls(ctx, def_id, (0..generics.count()).map(move |i| generics.param_at(i, tcx)))
 }
 
-pub fn own_generic_decls_for(tcx: TyCtxt, def_id: DefId) -> impl Iterator<Item = Decl> + '_ {
-    let generics = tcx.generics_of(def_id);
-    generic_decls(generics.params.iter())
+pub fn own_generic_decls_for<'tcx>(
+    ctx: &mut TranslationCtx<'_, 'tcx>,
+    def_id: DefId,
+) -> impl Iterator<Item = Decl> {
+    let generics = ctx.tcx.generics_of(def_id);
+    generic_decls(ctx, def_id, generics.params.iter())
 }
 
-fn generic_decls<'tcx, I: Iterator<Item = &'tcx GenericParamDef> + 'tcx>(
+fn generic_decls<'tcx, I: Iterator<Item = &'tcx GenericParamDef>>(
+    ctx: &mut TranslationCtx<'_, 'tcx>,
+    def_id: DefId,
     it: I,
-) -> impl Iterator<Item = Decl> + 'tcx {
-    it.filter_map(|param| {
-        if let GenericParamDefKind::Type { .. } = param.kind {
-            Some(Decl::TyDecl(TyDecl::Opaque {
+) -> impl Iterator<Item = Decl> {
+    let mut names = CloneMap::new(ctx.tcx, def_id, false);
+    let mut decls = Vec::new();
+
+    for param in it {
+        match param.kind {
+            GenericParamDefKind::Type { .. } => decls.push(Decl::TyDecl(TyDecl::Opaque {
                 ty_name: (&*param.name.as_str().to_lowercase()).into(),
                 ty_params: vec![],
-            }))
-        } else {
-            None
+            })),
+            // Const generic parameters become logical constants, which are substituted by the
+            // actual const arguments when the module is cloned
+            GenericParamDefKind::Const { .. } => {
+                let span = ctx.tcx.def_span(param.def_id);
+                let ty = ctx.tcx.type_of(param.def_id);
+                let ty = translate_ty(ctx, &mut names, span, ty);
+                decls.extend(names.to_clones(ctx));
+
+                let sig = Signature {
+                    name: ident_of(param.name),
+                    attrs: Vec::new(),
+                    args: Vec::new(),
+                    retty: Some(ty),
+                    contract: Contract::new(),
+                };
+                decls.push(Decl::ValDecl(ValKind::Function { sig }));
+            }
+            GenericParamDefKind::Lifetime => {}
         }
-    })
+    }
+
+    decls.into_iter()
 }
 
 pub fn real_locals<'tcx>(tcx: TyCtxt<'tcx>, body: &Body<'tcx>) -> HashMap<Local, Local> {
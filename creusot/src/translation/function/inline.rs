@@ -0,0 +1,84 @@
+use rustc_hir::def_id::DefId;
+use rustc_middle::{mir::MirPass, ty::subst::SubstsRef};
+use rustc_mir_transform::{remove_false_edges::RemoveFalseEdges, simplify::SimplifyCfg};
+use rustc_span::Span;
+use why3::exp::{Exp, Pattern};
+
+use super::{place::translate_local, promoted::translate_linear_body, BodyTranslator};
+use crate::{specification::contract_of, util};
+
+// Functions marked with `#[inline_body]` are not called through their contract: their body is
+// translated in place as a chain of `let` bindings. This is only possible when the body is
+// straight-line code without calls, which covers getters and constructor-like functions.
+impl<'tcx> BodyTranslator<'_, '_, 'tcx> {
+    pub(super) fn inline_call(
+        &mut self,
+        def_id: DefId,
+        subst: SubstsRef<'tcx>,
+        args: &[Exp],
+        span: Span,
+    ) -> Option<Exp> {
+        if !util::is_inline_body(self.tcx, def_id) {
+            return None;
+        }
+
+        let name = self.tcx.def_path_str(def_id);
+        match self.inlined_body(def_id, subst, args) {
+            Ok(exp) => Some(exp),
+            Err(reason) => {
+                self.ctx.warn(span, &format!("cannot inline `{}`: {}", name, reason));
+                None
+            }
+        }
+    }
+
+    fn inlined_body(
+        &mut self,
+        def_id: DefId,
+        subst: SubstsRef<'tcx>,
+        args: &[Exp],
+    ) -> Result<Exp, String> {
+        let Some(local_id) = def_id.as_local() else {
+            return Err("only functions of the current crate can be inlined".into());
+        };
+        if subst.non_erasable_generics().next().is_some() {
+            return Err("generic functions cannot be inlined".into());
+        }
+        if util::is_trusted(self.tcx, def_id) {
+            return Err("trusted functions cannot be inlined".into());
+        }
+        if contract_of(self.ctx, def_id).has_requires() {
+            return Err("functions with preconditions cannot be inlined".into());
+        }
+
+        let (mut body, promoted) = self.ctx.body_with_promoted(local_id);
+        if !promoted.is_empty() {
+            return Err("functions with promoted constants cannot be inlined".into());
+        }
+        RemoveFalseEdges.run_pass(self.tcx, &mut body);
+        SimplifyCfg::new("verify").run_pass(self.tcx, &mut body);
+
+        let param_env = self.tcx.param_env(def_id);
+        let exp = translate_linear_body(self.ctx, self.names, param_env, &body)
+            .map_err(|err| err.msg().to_owned())?;
+
+        // Bind all the arguments at once so that they can't capture the callee's locals
+        let params: Vec<_> = body
+            .args_iter()
+            .map(|arg| Pattern::VarP(translate_local(&body, &Default::default(), arg).ident()))
+            .collect();
+        Ok(match params.len() {
+            0 => exp,
+            1 => Exp::Let {
+                pattern: params.into_iter().next().unwrap(),
+                arg: box args[0].clone(),
+                body: box exp,
+            },
+            _ => Exp::Let {
+                pattern: Pattern::TupleP(params),
+                arg: box Exp::Tuple(args.to_vec()),
+                body: box exp,
+            },
+        })
+    }
+}
@@ -1,10 +1,14 @@
 use std::collections::HashMap;
 
 use rustc_middle::{
-    mir::{Body, Local, Place},
+    mir::{
+        visit::{PlaceContext, Visitor},
+        Body, Local, Location, Place, ProjectionElem, Statement, Terminator,
+    },
     ty::{TyKind, UintTy},
 };
 use why3::exp::{
+    BinOp, Constant,
     Exp::{self, *},
     Pattern::*,
 };
@@ -13,12 +17,38 @@ use why3::QName;
 
 use super::{BodyTranslator, LocalIdent};
 use crate::{
+    clone_map::PreludeModule,
     ctx::{CloneMap, TranslationCtx},
     translation::function::statement::uint_to_int,
-    translation::ty::{closure_accessor_name, variant_accessor_name},
+    translation::ty::{closure_accessor_name, is_transparent_newtype, variant_accessor_name},
     util::{constructor_qname, item_qname},
 };
 
+// Subslice patterns are not supported. Statements and terminators using them are rejected before
+// they are translated, so that the error points at them.
+struct SubsliceFinder(bool);
+
+impl<'tcx> Visitor<'tcx> for SubsliceFinder {
+    fn visit_place(&mut self, place: &Place<'tcx>, _: PlaceContext, _: Location) {
+        self.0 |= place
+            .projection
+            .iter()
+            .any(|elem| matches!(elem, ProjectionElem::Subslice { .. }));
+    }
+}
+
+pub(super) fn statement_uses_subslice(stmt: &Statement, loc: Location) -> bool {
+    let mut finder = SubsliceFinder(false);
+    finder.visit_statement(stmt, loc);
+    finder.0
+}
+
+pub(super) fn terminator_uses_subslice(term: &Terminator, loc: Location) -> bool {
+    let mut finder = SubsliceFinder(false);
+    finder.visit_terminator(term, loc);
+    finder.0
+}
+
 impl<'body, 'sess, 'tcx> BodyTranslator<'body, 'sess, 'tcx> {
     pub fn translate_rplace(&mut self, rhs: &Place<'tcx>) -> Exp {
         translate_rplace_inner(
@@ -81,6 +111,7 @@ impl<'body, 'sess, 'tcx> BodyTranslator<'body, 'sess, 'tcx> {
                     }
                 }
                 Field(ix, _) => match place_ty.ty.kind() {
+                    TyKind::Adt(def, _) if is_transparent_newtype(self.ctx, *def) => {}
                     TyKind::Adt(def, _) => {
                         let variant_id = place_ty.variant_index.unwrap_or_else(|| 0u32.into());
                         let variant = &def.variants()[variant_id];
@@ -186,8 +217,23 @@ impl<'body, 'sess, 'tcx> BodyTranslator<'body, 'sess, 'tcx> {
                         ],
                     )
                 }
-                ConstantIndex { .. } => unimplemented!("ConstantIndex"),
-                Subslice { .. } => unimplemented!("Subslice"),
+                ConstantIndex { offset, from_end, .. } => {
+                    let set = Exp::impure_qvar(QName::from_string("Seq.set").unwrap());
+                    let seq = translate_rplace_inner(
+                        &mut self.ctx,
+                        &mut self.names,
+                        &self.body,
+                        &self.local_map,
+                        lhs.local,
+                        stump,
+                    );
+                    let ix_exp = constant_index(&mut self.names, seq.clone(), offset, from_end);
+
+                    inner = Call(box set, vec![seq, ix_exp, inner])
+                }
+                Subslice { .. } => {
+                    unreachable!("subslice patterns are rejected before translation")
+                }
             }
         }
 
@@ -221,6 +267,7 @@ pub(super) fn translate_rplace_inner<'tcx>(
                 }
             }
             Field(ix, _) => match place_ty.ty.kind() {
+                TyKind::Adt(def, _) if is_transparent_newtype(ctx, *def) => {}
                 TyKind::Adt(def, _) => {
                     let variant_id = place_ty.variant_index.unwrap_or_else(|| 0u32.into());
                     let variant = &def.variants()[variant_id];
@@ -265,8 +312,14 @@ pub(super) fn translate_rplace_inner<'tcx>(
                     vec![inner, conv_func.app_to(ix_exp)],
                 )
             }
-            ConstantIndex { .. } => unimplemented!("constant index projection"),
-            Subslice { .. } => unimplemented!("subslice projection"),
+            ConstantIndex { offset, from_end, .. } => {
+                let ix_exp = constant_index(names, inner.clone(), *offset, *from_end);
+                inner = Call(
+                    box Exp::impure_qvar(QName::from_string("Seq.get").unwrap()),
+                    vec![inner, ix_exp],
+                )
+            }
+            Subslice { .. } => unreachable!("subslice patterns are rejected before translation"),
         }
         place_ty = place_ty.projection_ty(ctx.tcx, *elem);
     }
@@ -274,6 +327,19 @@ pub(super) fn translate_rplace_inner<'tcx>(
     inner
 }
 
+// Index of the element selected by a `ConstantIndex` projection of `seq`, as produced by slice
+// patterns like `[first, .., last]`
+fn constant_index(names: &mut CloneMap<'_>, seq: Exp, offset: u64, from_end: bool) -> Exp {
+    let offset = Exp::Const(Constant::Int(offset as i128, None));
+    if from_end {
+        names.import_prelude_module(PreludeModule::Int);
+        let len = Call(box Exp::impure_qvar(QName::from_string("Seq.length").unwrap()), vec![seq]);
+        BinaryOp(BinOp::Sub, box len, box offset)
+    } else {
+        offset
+    }
+}
+
 pub(super) fn translate_local(body: &Body, map: &HashMap<Local, Local>, loc: Local) -> LocalIdent {
     use rustc_middle::mir::VarDebugInfoContents::Place;
     let debug_info: Vec<_> = body
@@ -1,19 +1,23 @@
 use std::collections::HashMap;
 
 use crate::{
+    clone_map::PreludeModule,
     ctx::{CloneMap, TranslationCtx},
     error::Error,
     translation::{
         binop_to_binop,
         constant::from_mir_constant,
-        function::{statement::uint_from_int, LocalIdent},
-        ty::translate_ty,
+        function::{
+            statement::{array_literal, array_repeat, bookkeeping_statement, uint_from_int},
+            LocalIdent,
+        },
+        ty::{is_transparent_newtype, translate_ty},
         unop_to_unop,
     },
     util::{self, constructor_qname},
 };
 use rustc_middle::{
-    mir::{Body, BorrowKind, Operand, Promoted, StatementKind, TerminatorKind},
+    mir::{Body, BorrowKind, Location, Operand, Promoted, StatementKind, TerminatorKind},
     ty::ParamEnv,
 };
 use why3::{
@@ -24,7 +28,7 @@ use why3::{
 
 use crate::error::CreusotResult;
 
-use super::place::translate_rplace_inner;
+use super::place::{statement_uses_subslice, translate_local, translate_rplace_inner};
 
 pub fn promoted_signature<'tcx>(
     ctx: &mut TranslationCtx<'_, 'tcx>,
@@ -63,23 +67,34 @@ pub fn translate_promoted<'tcx>(
     param_env: ParamEnv<'tcx>,
     (promoted, body): (Promoted, &Body<'tcx>),
 ) -> CreusotResult<Decl> {
+    let exp = translate_linear_body(ctx, names, param_env, body)?;
+    let sig = promoted_signature(ctx, names, (promoted, body));
+    Ok(Decl::Let(LetDecl { sig, rec: false, constant: true, body: exp }))
+}
+
+/// Translate a body made of a straight line of blocks into a chain of `let` bindings which
+/// computes its return place. This is used for promoted constants and to inline small functions.
+pub(super) fn translate_linear_body<'tcx>(
+    ctx: &mut TranslationCtx<'_, 'tcx>,
+    names: &mut CloneMap<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    body: &Body<'tcx>,
+) -> CreusotResult<Exp> {
     let mut previous_block = None;
     let mut exp = Exp::impure_var("_0".into());
     for (id, bbd) in body.basic_blocks().iter_enumerated().rev() {
-        // Safety check
         match bbd.terminator().kind {
-            TerminatorKind::Return => {
-                assert!(previous_block == None);
-            }
-            TerminatorKind::Goto { target } => {
-                assert!(previous_block == Some(target))
-            }
-            _ => {}
+            TerminatorKind::Return if previous_block == None => {}
+            TerminatorKind::Goto { target } if previous_block == Some(target) => {}
+            _ => Err(Error::new(bbd.terminator().source_info.span, "control flow is not linear"))?,
         }
         previous_block = Some(id);
         use rustc_middle::mir::Rvalue::*;
         use rustc_middle::ty::UintTy;
-        for stmt in bbd.statements.iter().rev() {
+        for (ix, stmt) in bbd.statements.iter().enumerate().rev() {
+            if statement_uses_subslice(stmt, Location { block: id, statement_index: ix }) {
+                Err(Error::new(stmt.source_info.span, "subslice patterns are not supported"))?
+            }
             match &stmt.kind {
                 StatementKind::Assign(box (tgt, val)) => {
                     let rhs = match val {
@@ -119,12 +134,18 @@ pub fn translate_promoted<'tcx>(
 
                             match kind {
                                 Tuple => Exp::Tuple(fields),
+                                Array(_) => array_literal(fields),
                                 Adt(adt, varix, _, _, _) => {
                                     let adt = ctx.adt_def(*adt);
-                                    let variant_def = &adt.variants()[*varix];
-                                    let qname = constructor_qname(ctx.tcx, variant_def);
+                                    if is_transparent_newtype(ctx, adt) {
+                                        let mut fields = fields;
+                                        fields.remove(0)
+                                    } else {
+                                        let variant_def = &adt.variants()[*varix];
+                                        let qname = constructor_qname(ctx.tcx, variant_def);
 
-                                    Exp::Constructor { ctor: qname, args: fields }
+                                        Exp::Constructor { ctor: qname, args: fields }
+                                    }
                                 }
                                 Closure(def_id, _) if util::is_ghost(ctx.tcx, *def_id) => ctx
                                     .crash_and_error(
@@ -148,39 +169,47 @@ pub fn translate_promoted<'tcx>(
 
                         Ref(_, _, _) => Err(Error::new(
                             stmt.source_info.span,
-                            "cannot take mutable ref in promoted body",
+                            "cannot take a mutable reference",
                         ))?,
 
+                        Repeat(op, len) => {
+                            let len = len.try_eval_usize(ctx.tcx, param_env).ok_or_else(|| {
+                                Error::new(
+                                    stmt.source_info.span,
+                                    "array lengths must be known constants",
+                                )
+                            })?;
+                            names.import_prelude_module(PreludeModule::Prelude);
+                            array_repeat(translate_operand(ctx, names, body, param_env, op), len)
+                        }
                         ShallowInitBox(_, _)
                         | NullaryOp(_, _)
                         | Discriminant(_)
                         | ThreadLocalRef(_)
                         | AddressOf(_, _)
-                        | Repeat(_, _)
-                        | Cast(_, _, _) => Err(Error::new(
-                            stmt.source_info.span,
-                            "unsupported rvalue in promoted mir",
-                        ))?,
+                        | Cast(_, _, _) => {
+                            Err(Error::new(stmt.source_info.span, "unsupported rvalue"))?
+                        }
                     };
-                    let lhs =
-                        LocalIdent::anon(tgt.as_local().ok_or_else(|| {
-                            Error::new(stmt.source_info.span, "expected MIR local")
-                        })?);
+                    let lhs = tgt
+                        .as_local()
+                        .ok_or_else(|| Error::new(stmt.source_info.span, "expected MIR local"))?;
+                    let lhs = translate_local(body, &HashMap::new(), lhs);
                     exp = Exp::Let {
                         pattern: Pattern::VarP(lhs.ident()),
                         arg: box rhs,
                         body: box exp,
                     };
                 }
-                _ => Err(Error::new(
+                bookkeeping_statement!() => {}
+                kind => Err(Error::new(
                     stmt.source_info.span,
-                    "tell Xavier to stop trying to simplify promoted bodies",
+                    format!("unsupported statement: {:?}", kind),
                 ))?,
             }
         }
     }
-    let sig = promoted_signature(ctx, names, (promoted, body));
-    Ok(Decl::Let(LetDecl { sig, rec: false, constant: true, body: exp }))
+    Ok(exp)
 }
 
 fn translate_operand<'tcx>(
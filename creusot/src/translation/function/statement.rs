@@ -1,46 +1,82 @@
 use rustc_borrowck::borrow_set::TwoPhaseActivation;
 use rustc_middle::{
     mir::{
-        BinOp, BorrowKind::*, CastKind, Location, Operand::*, Place, Rvalue, SourceInfo, Statement,
-        StatementKind,
+        BinOp, Body, BorrowKind::*, CastKind, Location, Operand, Operand::*, Place,
+        ProjectionElem, Rvalue, SourceInfo, Statement, StatementKind, UnOp,
     },
-    ty::{IntTy, TyKind, UintTy},
+    ty::{adjustment::PointerCast, IntTy, Ty, TyKind, UintTy},
 };
+use rustc_target::abi::VariantIdx;
 
 use why3::{
-    exp::Exp::{self, *},
+    exp::{
+        Constant,
+        Exp::{self, *},
+    },
     mlcfg::Statement::*,
     QName,
 };
 
-use super::BodyTranslator;
+use super::{
+    place::statement_uses_subslice,
+    terminator::{discriminator_for_switch, variant_pattern},
+    BodyTranslator,
+};
 use crate::{
     clone_map::PreludeModule,
-    translation::{binop_to_binop, unop_to_unop},
+    constant::from_ty_const,
+    ctx::TranslationCtx,
+    options::IntModel,
+    translation::{binop_to_binop, ty, unop_to_unop},
     util::{self, constructor_qname, is_ghost_closure, item_name},
 };
 
+/// Statements which only matter to borrow checking, code generation or Miri, and never change the
+/// value of a place: storage markers, `Nop`, `FakeRead`, `AscribeUserType`, `Retag` and `Coverage`.
+///
+/// This is the only list of them: statement translators skip them with `bookkeeping_statement!()`
+/// as a pattern.
+macro_rules! bookkeeping_statement {
+    () => {
+        StatementKind::StorageDead(_)
+            | StatementKind::StorageLive(_)
+            | StatementKind::Nop
+            | StatementKind::FakeRead(_)
+            | StatementKind::AscribeUserType(_, _)
+            | StatementKind::Retag(_, _)
+            | StatementKind::Coverage(_)
+    };
+}
+pub(super) use bookkeeping_statement;
+
 impl<'tcx> BodyTranslator<'_, '_, 'tcx> {
     pub fn translate_statement(&mut self, statement: &'_ Statement<'tcx>, loc: Location) {
+        if statement_uses_subslice(statement, loc) {
+            let span = statement.source_info.span;
+            self.ctx.crash_and_error(span, "subslice patterns are not supported")
+        }
+
         use StatementKind::*;
         match statement.kind {
             Assign(box (ref pl, ref rv)) => {
                 self.translate_assign(statement.source_info, pl, rv, loc)
             }
-            SetDiscriminant { .. } => {
-                // TODO: implement support for set discriminant
-                self.ctx
-                    .crash_and_error(statement.source_info.span, "SetDiscriminant is not supported")
+            SetDiscriminant { box ref place, variant_index } => {
+                self.translate_set_discriminant(statement.source_info, place, variant_index)
+            }
+            // Only found in optimized MIR, always followed by the re-initialization of the place.
+            // Enums are rebuilt once their discriminant is set, so collect the fields until then.
+            // Other places are reinitialized field by field, which needs no special handling.
+            Deinit(box place) => {
+                if place.ty(self.body, self.tcx).ty.is_enum() {
+                    self.variant_fields.insert(place, Vec::new());
+                }
             }
-            // Erase Storage markers and Nops
-            StorageDead(_) | StorageLive(_) | Nop => {}
-            // Not real instructions
-            FakeRead(_) | AscribeUserType(_, _) | Retag(_, _) | Coverage(_) => {}
+            bookkeeping_statement!() => {}
             CopyNonOverlapping(_) => self.ctx.crash_and_error(
                 statement.source_info.span,
                 "copy non overlapping is not supported",
             ),
-            Deinit(_) => unreachable!("Deinit unsupported")
             // No assembly!
             // LlvmInlineAsm(_) => self
             //     .ctx
@@ -62,7 +98,14 @@ impl<'tcx> BodyTranslator<'_, '_, 'tcx> {
                     let ty = place.ty(self.body, self.tcx).ty;
                     let pl_exp = self.translate_rplace(place);
                     self.resolve_ty(ty).emit(pl_exp, self);
-                    self.translate_rplace(pl)
+                    let rhs = self.translate_rplace(pl);
+
+                    if let Move(pl) = rval && !pl.projection.is_empty() {
+                        self.emit_assignment(place, rhs);
+                        self.forget_moved_place(si, pl);
+                        return;
+                    }
+                    rhs
                 }
                 Constant(box c) => {
                     if let Some(c) = c.literal.const_for_ty() {
@@ -127,10 +170,35 @@ impl<'tcx> BodyTranslator<'_, '_, 'tcx> {
                     return;
                 }
             },
-            Rvalue::Discriminant(_) => return,
+            Rvalue::Discriminant(pl) => {
+                let bbd = &self.body.basic_blocks()[loc.block];
+                // Reads which only feed the following `SwitchInt` are fused into a match by the
+                // terminator translation.
+                if loc.statement_index + 1 == bbd.statements.len()
+                    && discriminator_for_switch(bbd).is_some()
+                {
+                    return;
+                }
+                self.translate_discriminant(si, pl, rvalue.ty(self.body, self.tcx))
+            }
             Rvalue::BinaryOp(BinOp::BitAnd, box (l, r)) if l.ty(self.body, self.tcx).is_bool() => {
                 self.translate_operand(l).and(self.translate_operand(r))
             }
+            Rvalue::BinaryOp(BinOp::BitOr, box (l, r)) if l.ty(self.body, self.tcx).is_bool() => {
+                BinaryOp(
+                    why3::exp::BinOp::Or,
+                    box self.translate_operand(l),
+                    box self.translate_operand(r),
+                )
+            }
+            Rvalue::BinaryOp(BinOp::BitXor, box (l, r)) if l.ty(self.body, self.tcx).is_bool() => {
+                self.names.import_prelude_module(PreludeModule::Prelude);
+                let eqb = Call(
+                    box Exp::impure_qvar(QName::from_string("Prelude.eqb").unwrap()),
+                    vec![self.translate_operand(l), self.translate_operand(r)],
+                );
+                UnaryOp(why3::exp::UnOp::Not, box eqb)
+            }
             Rvalue::BinaryOp(BinOp::Eq, box (l, r)) if l.ty(self.body, self.tcx).is_bool() => {
                 self.names.import_prelude_module(PreludeModule::Prelude);
                 Call(
@@ -138,7 +206,29 @@ impl<'tcx> BodyTranslator<'_, '_, 'tcx> {
                     vec![self.translate_operand(l), self.translate_operand(r)],
                 )
             }
-            Rvalue::BinaryOp(op, box (l, r)) | Rvalue::CheckedBinaryOp(op, box (l, r)) => {
+            Rvalue::BinaryOp(
+                op @ (BinOp::BitAnd | BinOp::BitOr | BinOp::BitXor | BinOp::Shl | BinOp::Shr),
+                box (l, r),
+            ) => {
+                let exp = self.translate_bitwise_binop(si, *op, l, r);
+                self.ctx.attach_span(si.span, exp)
+            }
+            Rvalue::BinaryOp(op, box (l, r)) if l.ty(self.body, self.tcx).is_floating_point() => {
+                let exp = self.translate_float_binop(si, *op, l, r);
+                self.ctx.attach_span(si.span, exp)
+            }
+            // Chars are compared through their code
+            Rvalue::BinaryOp(op, box (l, r)) if l.ty(self.body, self.tcx).is_char() => {
+                self.names.import_prelude_module(PreludeModule::Int);
+                let exp = BinaryOp(
+                    binop_to_binop(*op),
+                    box char_code().app_to(self.translate_operand(l)),
+                    box char_code().app_to(self.translate_operand(r)),
+                );
+
+                self.ctx.attach_span(si.span, exp)
+            }
+            Rvalue::BinaryOp(op, box (l, r)) => {
                 let exp = BinaryOp(
                     binop_to_binop(*op),
                     box self.translate_operand(l),
@@ -147,6 +237,25 @@ impl<'tcx> BodyTranslator<'_, '_, 'tcx> {
 
                 self.ctx.attach_span(si.span, exp)
             }
+            // Overflow is already an obligation of the operation on bounded integers, and cannot
+            // happen on unbounded ones, so the overflow flag is false whenever the result exists
+            // and the assertion which checks it holds trivially.
+            Rvalue::CheckedBinaryOp(op, box (l, r)) => {
+                let exp = BinaryOp(
+                    binop_to_binop(*op),
+                    box self.translate_operand(l),
+                    box self.translate_operand(r),
+                );
+
+                Exp::Tuple(vec![self.ctx.attach_span(si.span, exp), Exp::mk_false()])
+            }
+            Rvalue::UnaryOp(UnOp::Neg, v) => match v.ty(self.body, self.tcx).kind() {
+                TyKind::Float(fty) => Call(
+                    box Exp::impure_qvar(ty::float_qname(self.names, *fty, "negate")),
+                    vec![self.translate_operand(v)],
+                ),
+                _ => UnaryOp(why3::exp::UnOp::Neg, box self.translate_operand(v)),
+            },
             Rvalue::UnaryOp(op, v) => UnaryOp(unop_to_unop(*op), box self.translate_operand(v)),
             Rvalue::Aggregate(box kind, ops) => {
                 use rustc_middle::mir::AggregateKind::*;
@@ -154,12 +263,18 @@ impl<'tcx> BodyTranslator<'_, '_, 'tcx> {
 
                 match kind {
                     Tuple => Exp::Tuple(fields),
+                    Array(_) => array_literal(fields),
                     Adt(adt, varix, _, _, _) => {
                         let adt = self.tcx.adt_def(*adt);
-                        let variant_def = &adt.variants()[*varix];
-                        let qname = constructor_qname(self.tcx, variant_def);
+                        if ty::is_transparent_newtype(self.ctx, adt) {
+                            let mut fields = fields;
+                            fields.remove(0)
+                        } else {
+                            let variant_def = &adt.variants()[*varix];
+                            let qname = constructor_qname(self.tcx, variant_def);
 
-                        Constructor { ctor: qname, args: fields }
+                            Constructor { ctor: qname, args: fields }
+                        }
                     }
                     Closure(def_id, subst) => {
                         if util::is_invariant(self.tcx, *def_id) {
@@ -197,32 +312,79 @@ impl<'tcx> BodyTranslator<'_, '_, 'tcx> {
             }
             Rvalue::Cast(CastKind::Misc, op, ty) => {
                 let op_ty = op.ty(self.body, self.tcx);
-                if !op_ty.is_integral() {
-                    self.ctx
-                        .crash_and_error(si.span, "Non integral casts are currently unsupported")
+                if op_ty.is_enum() && let Some(pl) = op.place() {
+                    self.translate_discriminant(si, &pl, *ty)
                 } else {
-                    let op_to_int = match op_ty.kind() {
-                        TyKind::Int(ity) => int_to_int(ity),
-                        TyKind::Uint(uty) => uint_to_int(uty),
-                        _ => unreachable!(),
+                    // Make sure the modules of both integer types are imported
+                    ty::translate_ty(self.ctx, self.names, si.span, op_ty);
+                    ty::translate_ty(self.ctx, self.names, si.span, *ty);
+
+                    // Casts go through mathematical integers, which are wrapped to the range of
+                    // the target type when it may not contain all the values of the source type
+                    let op_int = match op_ty.kind() {
+                        TyKind::Int(ity) => int_to_int(ity).app_to(self.translate_operand(op)),
+                        TyKind::Uint(uty) => uint_to_int(uty).app_to(self.translate_operand(op)),
+                        TyKind::Bool => IfThenElse(
+                            box self.translate_operand(op),
+                            box Const(Constant::Int(1, None)),
+                            box Const(Constant::Int(0, None)),
+                        ),
+                        TyKind::Char => char_code().app_to(self.translate_operand(op)),
+                        _ => self.ctx.crash_and_error(
+                            si.span,
+                            "Non integral casts are currently unsupported",
+                        ),
+                    };
+                    let op_int = if cast_may_wrap(op_ty, *ty) {
+                        self.names.import_builtin_module(
+                            QName::from_string("int.EuclideanDivision").unwrap(),
+                        );
+                        wrap_int(*ty, op_int)
+                    } else {
+                        op_int
                     };
                     match ty.kind() {
-                        TyKind::Int(ity) => {
-                            int_from_int(ity).app_to(op_to_int.app_to(self.translate_operand(op)))
+                        TyKind::Int(ity) => int_from_int(ity).app_to(op_int),
+                        TyKind::Uint(uty) => uint_from_int(uty).app_to(op_int),
+                        TyKind::Char => {
+                            Exp::impure_qvar(QName::from_string("Char.chr").unwrap()).app_to(op_int)
                         }
-                        TyKind::Uint(uty) => {
-                            uint_from_int(uty).app_to(op_to_int.app_to(self.translate_operand(op)))
-                        }
-                        _ => unreachable!(),
+                        _ => self.ctx.crash_and_error(
+                            si.span,
+                            "Non integral casts are currently unsupported",
+                        ),
                     }
                 }
             }
+            // Arrays and slices are both sequences, so unsizing a reference leaves it unchanged
+            Rvalue::Cast(CastKind::Pointer(PointerCast::Unsize), op, ty)
+                if is_array_to_slice(op.ty(self.body, self.tcx), *ty) =>
+            {
+                self.translate_operand(op)
+            }
             Rvalue::Cast(CastKind::Pointer(_), _, _) => {
                 self.ctx.crash_and_error(si.span, "Pointer casts are currently unsupported")
             }
+            Rvalue::Repeat(op, len) => {
+                self.names.import_prelude_module(PreludeModule::Prelude);
+                let elem = self.translate_operand(op);
+                match len.try_eval_usize(self.tcx, self.param_env()) {
+                    Some(len) => array_repeat(elem, len),
+                    // The length depends on generic parameters, it is then a logical constant of
+                    // type usize
+                    None => {
+                        self.names.import_prelude_module(PreludeModule::UInt64);
+                        let param_env = self.param_env();
+                        let len = from_ty_const(self.ctx, self.names, *len, param_env, si.span);
+                        Call(
+                            box Exp::impure_qvar(QName::from_string("Prelude.repeat").unwrap()),
+                            vec![elem, uint_to_int(&UintTy::Usize).app_to(len)],
+                        )
+                    }
+                }
+            }
             Rvalue::ShallowInitBox(_, _)
             | Rvalue::NullaryOp(_, _)
-            | Rvalue::Repeat(_, _)
             | Rvalue::ThreadLocalRef(_)
             | Rvalue::AddressOf(_, _) => self.ctx.crash_and_error(
                 si.span,
@@ -230,50 +392,361 @@ impl<'tcx> BodyTranslator<'_, '_, 'tcx> {
             ),
         };
 
+        if let [ref base @ .., ProjectionElem::Downcast(..), ProjectionElem::Field(field, _)] =
+            place.projection[..]
+        {
+            let base = Place { local: place.local, projection: self.tcx.intern_place_elems(base) };
+            if let Some(fields) = self.variant_fields.get_mut(&base) {
+                fields.push((field, rval));
+                return;
+            }
+        }
+
         self.emit_assignment(place, rval);
     }
 }
 
+impl<'tcx> BodyTranslator<'_, '_, 'tcx> {
+    fn translate_set_discriminant(
+        &mut self,
+        si: SourceInfo,
+        place: &Place<'tcx>,
+        variant_index: VariantIdx,
+    ) {
+        let (def, subst) = match place.ty(self.body, self.tcx).ty.kind() {
+            TyKind::Adt(def, subst) if def.is_enum() => (*def, *subst),
+            _ => self.ctx.crash_and_error(si.span, "SetDiscriminant is only supported on enums"),
+        };
+        let written = self.variant_fields.remove(place).unwrap_or_else(|| {
+            self.ctx.crash_and_error(
+                si.span,
+                "SetDiscriminant is only supported on places which were deinitialized",
+            )
+        });
+
+        let variant = &def.variants()[variant_index];
+        let mut args: Vec<_> = variant
+            .fields
+            .iter()
+            .map(|f| {
+                let ty = f.ty(self.tcx, subst);
+                Exp::Any(ty::translate_ty(self.ctx, self.names, si.span, ty))
+            })
+            .collect();
+        for (field, exp) in written {
+            args[field.as_usize()] = exp;
+        }
+
+        let ctor = Constructor { ctor: constructor_qname(self.tcx, variant), args };
+        self.emit_assignment(place, ctor);
+    }
+
+    // Moving out of a field leaves the old value in the why3 model of its owner, where resolving
+    // the owner would also resolve the moved value while it is still in use. Overwrite the field
+    // with an arbitrary value so the owner no longer says anything about it.
+    pub(super) fn forget_moved_place(&mut self, si: SourceInfo, pl: &Place<'tcx>) {
+        let ty = pl.ty(self.body, self.tcx).ty;
+        if self.resolve_ty(ty).exp.is_none() {
+            return;
+        }
+        let any = Exp::Any(ty::translate_ty(self.ctx, self.names, si.span, ty));
+        self.emit_assignment(pl, any);
+    }
+
+    // Build a match on the constructor of `pl` which yields the discriminant of each variant.
+    fn translate_discriminant(
+        &mut self,
+        si: SourceInfo,
+        pl: &Place<'tcx>,
+        discr_ty: Ty<'tcx>,
+    ) -> Exp {
+        let def = match pl.ty(self.body, self.tcx).ty.kind() {
+            TyKind::Adt(def, _) if def.is_enum() => *def,
+            _ => self.ctx.crash_and_error(si.span, "only discriminants of enums are supported"),
+        };
+        let why3_ty = ty::translate_ty(self.ctx, self.names, si.span, discr_ty);
+
+        let branches = def
+            .discriminants(self.tcx)
+            .map(|(vid, discr)| {
+                let val = int_const(discr_ty, sign_extend(discr.ty, discr.val), why3_ty.clone());
+                (variant_pattern(self.tcx, &def, vid), Exp::Const(val))
+            })
+            .collect();
+
+        Exp::Match(box self.translate_rplace(pl), branches)
+    }
+
+    // Bitwise operations and shifts on integers go through the Why3 bitvectors of the same width,
+    // which are only imported with `--int-model=bv`. Shifting by the width of the type or more
+    // overflows in Rust, so the amount is checked by an assertion.
+    fn translate_bitwise_binop(
+        &mut self,
+        si: SourceInfo,
+        op: BinOp,
+        l: &Operand<'tcx>,
+        r: &Operand<'tcx>,
+    ) -> Exp {
+        if self.ctx.opts.int_model != IntModel::Bitvector {
+            self.ctx.crash_and_error(
+                si.span,
+                "bitwise operations on integers require `--int-model=bv`",
+            )
+        }
+
+        let ty = l.ty(self.body, self.tcx);
+        ty::translate_ty(self.ctx, self.names, si.span, ty);
+        let bv = format!("BV{}", bit_width(ty));
+        self.names.import_builtin_module(QName::from_string(&format!("bv.{}", bv)).unwrap());
+        let bv_fn =
+            |name: &str| Exp::impure_qvar(QName::from_string(&format!("{}.{}", bv, name)).unwrap());
+
+        let lhs = bv_fn("of_int").app_to(int_of(ty).app_to(self.translate_operand(l)));
+        let exp = match op {
+            BinOp::BitAnd | BinOp::BitOr | BinOp::BitXor => {
+                let name = match op {
+                    BinOp::BitAnd => "bw_and",
+                    BinOp::BitOr => "bw_or",
+                    _ => "bw_xor",
+                };
+                let rhs = bv_fn("of_int").app_to(int_of(ty).app_to(self.translate_operand(r)));
+                bv_fn(name).app_to(lhs).app_to(rhs)
+            }
+            _ => {
+                let amount_ty = r.ty(self.body, self.tcx);
+                ty::translate_ty(self.ctx, self.names, si.span, amount_ty);
+                let amount = int_of(amount_ty).app_to(self.translate_operand(r));
+                let width = Const(Constant::Uint(bit_width(ty) as u128, None));
+                let in_range = BinaryOp(
+                    why3::exp::BinOp::Le,
+                    box Const(Constant::Uint(0, None)),
+                    box amount.clone(),
+                )
+                .and(BinaryOp(why3::exp::BinOp::Lt, box amount.clone(), box width));
+                let in_range = self.ctx.attach_span(si.span, in_range);
+                self.emit_statement(Assert(in_range));
+
+                let name = match op {
+                    BinOp::Shl => "lsl",
+                    _ if ty.is_signed() => "asr",
+                    _ => "lsr",
+                };
+                bv_fn(name).app_to(lhs).app_to(amount)
+            }
+        };
+
+        let bv_to_int = if ty.is_signed() { "to_int" } else { "to_uint" };
+        match ty.kind() {
+            TyKind::Int(ity) => int_from_int(ity).app_to(bv_fn(bv_to_int).app_to(exp)),
+            TyKind::Uint(uty) => uint_from_int(uty).app_to(bv_fn(bv_to_int).app_to(exp)),
+            _ => unreachable!("bitwise operation on {:?}", ty),
+        }
+    }
+
+    // Floats go through the program functions of their prelude module, which round to nearest
+    // and compare following IEEE 754, like Rust does.
+    fn translate_float_binop(
+        &mut self,
+        si: SourceInfo,
+        op: BinOp,
+        l: &Operand<'tcx>,
+        r: &Operand<'tcx>,
+    ) -> Exp {
+        let fty = match l.ty(self.body, self.tcx).kind() {
+            TyKind::Float(fty) => *fty,
+            _ => unreachable!(),
+        };
+        let name = match op {
+            BinOp::Add => "add_rne",
+            BinOp::Sub => "sub_rne",
+            BinOp::Mul => "mul_rne",
+            BinOp::Div => "div_rne",
+            BinOp::Lt => "ltb",
+            BinOp::Le => "leb",
+            BinOp::Gt => "gtb",
+            BinOp::Ge => "geb",
+            BinOp::Eq | BinOp::Ne => "eqb",
+            _ => {
+                let msg = format!("unsupported operation on floats: {:?}", op);
+                self.ctx.crash_and_error(si.span, &msg)
+            }
+        };
+        let call = Call(
+            box Exp::impure_qvar(ty::float_qname(self.names, fty, name)),
+            vec![self.translate_operand(l), self.translate_operand(r)],
+        );
+
+        if op == BinOp::Ne {
+            UnaryOp(why3::exp::UnOp::Not, box call)
+        } else {
+            call
+        }
+    }
+}
+
+// Derived from the same list as the match arms of the statement translators
+fn is_bookkeeping(kind: &StatementKind) -> bool {
+    matches!(kind, bookkeeping_statement!())
+}
+
+// Statements which change the value of a place and are translated as such
+fn is_translated(kind: &StatementKind) -> bool {
+    matches!(
+        kind,
+        StatementKind::Assign(_) | StatementKind::SetDiscriminant { .. } | StatementKind::Deinit(_)
+    )
+}
+
+/// Reports every statement of `body` which is neither bookkeeping nor translated, instead of
+/// stopping at the first one. Only run when `CREUSOT_CHECK_STATEMENTS` is set.
+pub(super) fn check_statements(ctx: &TranslationCtx, body: &Body) {
+    for stmt in body.basic_blocks().iter().flat_map(|bbd| &bbd.statements) {
+        if !is_bookkeeping(&stmt.kind) && !is_translated(&stmt.kind) {
+            ctx.error(stmt.source_info.span, &format!("unknown statement: {:?}", stmt.kind));
+        }
+    }
+}
+
+// Array literals are built by appending each element to the empty sequence
+pub(super) fn array_literal(elems: Vec<Exp>) -> Exp {
+    elems.into_iter().fold(Exp::impure_qvar(QName::from_string("Seq.empty").unwrap()), |arr, e| {
+        Call(box Exp::impure_qvar(QName::from_string("Seq.snoc").unwrap()), vec![arr, e])
+    })
+}
+
+fn is_array_to_slice(from: Ty, to: Ty) -> bool {
+    match (from.builtin_deref(true), to.builtin_deref(true)) {
+        (Some(from), Some(to)) => from.ty.is_array() && to.ty.is_slice(),
+        _ => false,
+    }
+}
+
+pub(super) fn array_repeat(elem: Exp, len: u64) -> Exp {
+    Call(
+        box Exp::impure_qvar(QName::from_string("Prelude.repeat").unwrap()),
+        vec![elem, Exp::Const(Constant::Uint(len as u128, None))],
+    )
+}
+
+fn bit_width(ty: Ty) -> u64 {
+    match ty.kind() {
+        TyKind::Int(ity) => ity.bit_width().unwrap_or(64),
+        TyKind::Uint(uty) => uty.bit_width().unwrap_or(64),
+        _ => unreachable!(),
+    }
+}
+
+// Interpret the raw bits of a value of integer type `ty` as an integer
+fn sign_extend(ty: Ty, bits: u128) -> i128 {
+    let shift = 128 - bit_width(ty);
+    match ty.kind() {
+        TyKind::Int(_) => ((bits << shift) as i128) >> shift,
+        _ => bits as i128,
+    }
+}
+
+// Build the constant of integer type `ty` obtained by an `as` cast of `val`
+fn int_const(ty: Ty, val: i128, why3_ty: why3::ty::Type) -> Constant {
+    let shift = 128 - bit_width(ty);
+    let bits = ((val as u128) << shift) >> shift;
+    match ty.kind() {
+        TyKind::Int(_) => Constant::Int(sign_extend(ty, bits), Some(why3_ty)),
+        _ => Constant::Uint(bits, Some(why3_ty)),
+    }
+}
+
+// Whether some values of the integer, boolean or char type `src` are out of the range of `tgt`
+fn cast_may_wrap(src: Ty, tgt: Ty) -> bool {
+    match (src.kind(), tgt.kind()) {
+        (TyKind::Bool, _) | (_, TyKind::Char) => false,
+        // Char codes are below 0x110000
+        (TyKind::Char, _) => bit_width(tgt) < 32,
+        (TyKind::Int(_), TyKind::Uint(_)) => true,
+        (TyKind::Uint(_), TyKind::Int(_)) => bit_width(tgt) <= bit_width(src),
+        _ => bit_width(tgt) < bit_width(src),
+    }
+}
+
+// Wrap the mathematical integer `x` to the range of the integer type `ty`, like `as` casts do:
+// `x mod 2^n` for unsigned types and `(x + 2^(n-1)) mod 2^n - 2^(n-1)` for signed ones
+fn wrap_int(ty: Ty, x: Exp) -> Exp {
+    use why3::exp::BinOp::{Add, Sub};
+    let width = bit_width(ty);
+    let modulus = if width == 128 {
+        let max = Const(Constant::Uint(u128::MAX, None));
+        BinaryOp(Add, box max, box Const(Constant::Uint(1, None)))
+    } else {
+        Const(Constant::Uint(1 << width, None))
+    };
+    let euclid_mod = |x: Exp| {
+        Exp::impure_qvar(QName::from_string("EuclideanDivision.mod").unwrap())
+            .app_to(x)
+            .app_to(modulus.clone())
+    };
+
+    match ty.kind() {
+        TyKind::Int(_) => {
+            let half = Const(Constant::Uint(1 << (width - 1), None));
+            let shifted = euclid_mod(BinaryOp(Add, box x, box half.clone()));
+            BinaryOp(Sub, box shifted, box half)
+        }
+        _ => euclid_mod(x),
+    }
+}
+
+pub fn char_code() -> Exp {
+    Exp::impure_qvar(QName::from_string("Char.code").unwrap())
+}
+
 fn int_from_int(ity: &IntTy) -> Exp {
     match ity {
         IntTy::Isize => Exp::impure_qvar(QName::from_string("Int64.of_int").unwrap()),
-        IntTy::I8 => unimplemented!(),
-        IntTy::I16 => unimplemented!(),
+        IntTy::I8 => Exp::impure_qvar(QName::from_string("Int8.of_int").unwrap()),
+        IntTy::I16 => Exp::impure_qvar(QName::from_string("Int16.of_int").unwrap()),
         IntTy::I32 => Exp::impure_qvar(QName::from_string("Int32.of_int").unwrap()),
         IntTy::I64 => Exp::impure_qvar(QName::from_string("Int64.of_int").unwrap()),
-        IntTy::I128 => unimplemented!(),
+        IntTy::I128 => Exp::impure_qvar(QName::from_string("Int128.of_int").unwrap()),
     }
 }
 
 pub fn uint_from_int(uty: &UintTy) -> Exp {
     match uty {
         UintTy::Usize => Exp::impure_qvar(QName::from_string("UInt64.of_int").unwrap()),
-        UintTy::U8 => unimplemented!(),
-        UintTy::U16 => unimplemented!(),
+        UintTy::U8 => Exp::impure_qvar(QName::from_string("UInt8.of_int").unwrap()),
+        UintTy::U16 => Exp::impure_qvar(QName::from_string("UInt16.of_int").unwrap()),
         UintTy::U32 => Exp::impure_qvar(QName::from_string("UInt32.of_int").unwrap()),
         UintTy::U64 => Exp::impure_qvar(QName::from_string("UInt64.of_int").unwrap()),
-        UintTy::U128 => unimplemented!(),
+        UintTy::U128 => Exp::impure_qvar(QName::from_string("UInt128.of_int").unwrap()),
+    }
+}
+
+// The conversion of the integer type `ty` to mathematical integers
+fn int_of(ty: Ty) -> Exp {
+    match ty.kind() {
+        TyKind::Int(ity) => int_to_int(ity),
+        TyKind::Uint(uty) => uint_to_int(uty),
+        _ => unreachable!("{:?} is not an integer type", ty),
     }
 }
 
 fn int_to_int(ity: &IntTy) -> Exp {
     match ity {
         IntTy::Isize => Exp::impure_qvar(QName::from_string("Int64.to_int").unwrap()),
-        IntTy::I8 => unimplemented!(),
-        IntTy::I16 => unimplemented!(),
+        IntTy::I8 => Exp::impure_qvar(QName::from_string("Int8.to_int").unwrap()),
+        IntTy::I16 => Exp::impure_qvar(QName::from_string("Int16.to_int").unwrap()),
         IntTy::I32 => Exp::impure_qvar(QName::from_string("Int32.to_int").unwrap()),
         IntTy::I64 => Exp::impure_qvar(QName::from_string("Int64.to_int").unwrap()),
-        IntTy::I128 => unimplemented!(),
+        IntTy::I128 => Exp::impure_qvar(QName::from_string("Int128.to_int").unwrap()),
     }
 }
 
 pub fn uint_to_int(uty: &UintTy) -> Exp {
     match uty {
         UintTy::Usize => Exp::impure_qvar(QName::from_string("UInt64.to_int").unwrap()),
-        UintTy::U8 => unimplemented!(),
-        UintTy::U16 => unimplemented!(),
+        UintTy::U8 => Exp::impure_qvar(QName::from_string("UInt8.to_int").unwrap()),
+        UintTy::U16 => Exp::impure_qvar(QName::from_string("UInt16.to_int").unwrap()),
         UintTy::U32 => Exp::impure_qvar(QName::from_string("UInt32.to_int").unwrap()),
         UintTy::U64 => Exp::impure_qvar(QName::from_string("UInt64.to_int").unwrap()),
-        UintTy::U128 => unimplemented!(),
+        UintTy::U128 => Exp::impure_qvar(QName::from_string("UInt128.to_int").unwrap()),
     }
 }
@@ -5,7 +5,10 @@ use rustc_infer::{
     traits::{FulfillmentError, Obligation, ObligationCause, TraitEngine},
 };
 use rustc_middle::{
-    mir::{Location, Operand, SourceInfo, SwitchTargets, Terminator, TerminatorKind::*},
+    mir::{
+        AssertKind, AssertMessage, Location, Operand, SourceInfo, SwitchTargets, Terminator,
+        TerminatorKind::*,
+    },
     ty::{
         self,
         subst::{GenericArgKind, SubstsRef},
@@ -18,16 +21,18 @@ use rustc_target::abi::VariantIdx;
 use rustc_trait_selection::traits::FulfillmentContext;
 
 use std::collections::HashMap;
+use why3::declaration::Attribute;
 use why3::exp::{BinOp, Constant, Exp, Pattern};
 use why3::mlcfg::{BlockId, Statement, Terminator as MlT};
 use why3::QName;
 
 use crate::{
+    specification::contract_of,
     translation::traits,
-    util::{constructor_qname, is_ghost_closure},
+    util::{constructor_qname, is_ghost_closure, is_transparent_pointer},
 };
 
-use super::BodyTranslator;
+use super::{place::terminator_uses_subslice, statement::char_code, BodyTranslator};
 
 // Translate the terminator of a basic block.
 // There isn't much that's special about this. The only subtlety is in how
@@ -37,6 +42,11 @@ use super::BodyTranslator;
 
 impl<'tcx> BodyTranslator<'_, '_, 'tcx> {
     pub fn translate_terminator(&mut self, terminator: &Terminator<'tcx>, location: Location) {
+        if terminator_uses_subslice(terminator, location) {
+            let span = terminator.source_info.span;
+            self.ctx.crash_and_error(span, "subslice patterns are not supported")
+        }
+
         match &terminator.kind {
             Goto { target } => self.emit_terminator(mk_goto(*target)),
             SwitchInt { discr, targets, .. } => {
@@ -46,12 +56,19 @@ impl<'tcx> BodyTranslator<'_, '_, 'tcx> {
                         .unwrap_or_else(|| discr.clone());
 
                 let discriminant = self.translate_operand(&real_discr);
+                // Rustc's exhaustiveness checking sends the `otherwise` edge of a complete match
+                // to an unreachable block, so it is safe to leave it out.
+                let exhaustive = matches!(
+                    self.body.basic_blocks()[targets.otherwise()].terminator().kind,
+                    Unreachable
+                );
                 let switch = make_switch(
                     self.ctx.tcx.sess,
                     self.tcx,
                     terminator.source_info,
                     real_discr.ty(self.body, self.tcx),
                     targets,
+                    exhaustive,
                     discriminant,
                 );
 
@@ -63,6 +80,10 @@ impl<'tcx> BodyTranslator<'_, '_, 'tcx> {
             Call { func, args, destination, .. } => {
                 if destination.is_none() {
                     // If we have no target block after the call, then we cannot move past it.
+                    // Unless panics are allowed to diverge, the call must be proved unreachable.
+                    if self.ctx.opts.diverging_panics {
+                        self.emit_statement(Statement::Assume(Exp::mk_false()));
+                    }
                     self.emit_terminator(MlT::Absurd);
                     return;
                 }
@@ -80,11 +101,19 @@ impl<'tcx> BodyTranslator<'_, '_, 'tcx> {
                     return;
                 }
 
-                let predicates = self
-                    .ctx
-                    .extern_spec(fun_def_id)
-                    .map(|p| p.predicates_for(self.tcx, subst))
-                    .unwrap_or_else(Vec::new);
+                // Calls to trait methods, like overloaded operators, are specified by the
+                // implementation they resolve to, whose extern spec may require extra bounds
+                let (spec_id, spec_subst) =
+                    traits::resolve_assoc_item_opt(self.tcx, self.param_env(), fun_def_id, subst)
+                        .unwrap_or((fun_def_id, subst));
+                let predicates = match self.ctx.extern_spec(spec_id) {
+                    Some(spec) => spec.predicates_for(self.tcx, spec_subst),
+                    None => self
+                        .ctx
+                        .extern_spec(fun_def_id)
+                        .map(|p| p.predicates_for(self.tcx, subst))
+                        .unwrap_or_else(Vec::new),
+                };
 
                 use rustc_trait_selection::traits::error_reporting::InferCtxtExt;
                 self.tcx.infer_ctxt().enter(|infcx| {
@@ -105,15 +134,21 @@ impl<'tcx> BodyTranslator<'_, '_, 'tcx> {
                 let mut func_args: Vec<_> =
                     args.iter().map(|arg| self.translate_operand(arg)).collect();
 
+                let inlined =
+                    self.inline_call(fun_def_id, subst, &func_args, terminator.source_info.span);
+
                 if func_args.is_empty() {
                     // We use tuple as a dummy argument for 0-ary functions
                     func_args.push(Exp::Tuple(vec![]))
                 }
-                let call_exp = if self.is_box_new(fun_def_id) {
+                let call_exp = if let Some(exp) = inlined {
+                    exp
+                } else if self.is_pointer_identity(fun_def_id, subst) {
                     assert_eq!(func_args.len(), 1);
 
                     func_args.remove(0)
                 } else {
+                    self.calls += 1;
                     let fname = self.get_func_name(fun_def_id, subst, terminator.source_info.span);
                     let exp = Exp::Call(box Exp::impure_qvar(fname), func_args);
                     let span = terminator.source_info.span.source_callsite();
@@ -124,12 +159,20 @@ impl<'tcx> BodyTranslator<'_, '_, 'tcx> {
                 self.emit_assignment(&loc, call_exp);
                 self.emit_terminator(MlT::Goto(BlockId(bb.into())));
             }
-            Assert { cond, expected, msg: _, target, cleanup: _ } => {
+            Assert { cond, expected, msg, target, cleanup: _ } => {
                 let mut ass = self.translate_operand(cond);
                 if !expected {
                     ass = Exp::UnaryOp(why3::exp::UnOp::Not, box ass);
                 }
-                self.emit_statement(Statement::Assert(ass));
+                let expl = assert_explanation(self.tcx, msg, terminator.source_info.span);
+                ass = Exp::Attr(Attribute::Attr(format!("expl:{}", expl)), box ass);
+                // A failed assertion panics, so when panics are allowed execution only continues
+                // if it holds
+                if self.ctx.opts.diverging_panics {
+                    self.emit_statement(Statement::Assume(ass));
+                } else {
+                    self.emit_statement(Statement::Assert(ass));
+                }
                 self.emit_terminator(mk_goto(*target))
             }
 
@@ -158,6 +201,9 @@ impl<'tcx> BodyTranslator<'_, '_, 'tcx> {
                 };
 
                 self.emit_assignment(place, rhs);
+                if let Operand::Move(pl) = value && !pl.projection.is_empty() {
+                    self.forget_moved_place(terminator.source_info, pl);
+                }
 
                 self.emit_terminator(mk_goto(*target))
             }
@@ -167,8 +213,20 @@ impl<'tcx> BodyTranslator<'_, '_, 'tcx> {
         }
     }
 
-    fn is_box_new(&self, def_id: DefId) -> bool {
-        self.tcx.def_path_str(def_id) == "std::boxed::Box::<T>::new"
+    // Creating or dereferencing a transparent pointer leaves its value unchanged, and so does
+    // cloning a reference-counted one (cloning a box clones its contents)
+    fn is_pointer_identity(&self, def_id: DefId, subst: SubstsRef<'tcx>) -> bool {
+        match &*self.tcx.def_path_str(def_id) {
+            "std::boxed::Box::<T>::new" | "std::rc::Rc::<T>::new" | "std::sync::Arc::<T>::new" => {
+                true
+            }
+            "std::ops::Deref::deref" => is_transparent_pointer(self.tcx, subst.type_at(0)),
+            "std::clone::Clone::clone" => {
+                let ty = subst.type_at(0);
+                !ty.is_box() && is_transparent_pointer(self.tcx, ty)
+            }
+            _ => false,
+        }
     }
 
     fn get_func_name(
@@ -179,6 +237,18 @@ impl<'tcx> BodyTranslator<'_, '_, 'tcx> {
     ) -> QName {
         if let Some(it) = self.tcx.opt_associated_item(def_id) {
             if let ty::TraitContainer(id) = it.container {
+                // A call through a trait object is only specified by the contract of the trait
+                if let ty::Dynamic(..) = subst.type_at(0).kind() &&
+                    contract_of(self.ctx, def_id).is_empty() {
+                    self.ctx.error(
+                        sp,
+                        &format!(
+                            "cannot call `{}` on a trait object, as it has no contract in its trait",
+                            self.tcx.def_path_str(def_id)
+                        ),
+                    );
+                }
+
                 let params = self.param_env();
                 let method = traits::resolve_assoc_item_opt(self.tcx, params, def_id, subst)
                     .expect("could not find instance");
@@ -270,6 +340,7 @@ pub fn make_switch<'tcx>(
     si: SourceInfo,
     switch_ty: Ty<'tcx>,
     targets: &SwitchTargets,
+    exhaustive: bool,
     discr: Exp,
 ) -> MlT {
     use rustc_middle::ty::TyKind::*;
@@ -310,8 +381,7 @@ pub fn make_switch<'tcx>(
                 .map(|(val, tgt)| (Constant::Uint(val, None), mk_goto(tgt)))
                 .collect();
 
-            let default = mk_goto(targets.otherwise());
-            build_constant_switch(discr, annoying.into_iter(), default)
+            build_int_switch(discr, annoying, targets.otherwise(), exhaustive)
         }
         Int(_) => {
             let annoying: Vec<(Constant, MlT)> = targets
@@ -319,8 +389,16 @@ pub fn make_switch<'tcx>(
                 .map(|(val, tgt)| (Constant::Int(val as i128, None), mk_goto(tgt)))
                 .collect();
 
-            let default = mk_goto(targets.otherwise());
-            build_constant_switch(discr, annoying.into_iter(), default)
+            build_int_switch(discr, annoying, targets.otherwise(), exhaustive)
+        }
+        // Chars are matched on their code
+        Char => {
+            let annoying: Vec<(Constant, MlT)> = targets
+                .iter()
+                .map(|(val, tgt)| (Constant::Uint(val, None), mk_goto(tgt)))
+                .collect();
+
+            build_int_switch(char_code().app_to(discr), annoying, targets.otherwise(), exhaustive)
         }
         Float(_) => sess.span_fatal_with_code(
             si.span,
@@ -331,10 +409,63 @@ pub fn make_switch<'tcx>(
     }
 }
 
+// Describe the panic an `Assert` terminator guards against, along with the source it comes from.
+// Why3 attributes cannot contain newlines or closing brackets, so such snippets are left out.
+fn assert_explanation(tcx: TyCtxt, msg: &AssertMessage, span: Span) -> String {
+    let snippet = tcx.sess.source_map().span_to_snippet(span).ok();
+    let (descr, detail) = match msg {
+        AssertKind::BoundsCheck { .. } => (
+            "index out of bounds",
+            snippet
+                .as_deref()
+                .and_then(split_index)
+                .map(|(base, ix)| format!("{} < {}.len()", ix, base)),
+        ),
+        _ => (msg.description(), snippet),
+    };
+    match detail {
+        Some(detail) if !detail.contains(&['\n', ']'][..]) => format!("{}: {}", descr, detail),
+        _ => descr.to_string(),
+    }
+}
+
+// Split `base[index]` into its base and index
+fn split_index(snippet: &str) -> Option<(&str, &str)> {
+    let inner = snippet.strip_suffix(']')?;
+    let mut depth = 0;
+    for (i, c) in inner.char_indices().rev() {
+        match c {
+            ']' => depth += 1,
+            '[' if depth == 0 => return Some((&inner[..i], &inner[i + 1..])),
+            '[' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
 fn mk_goto(bb: rustc_middle::mir::BasicBlock) -> MlT {
     MlT::Goto(BlockId(bb.into()))
 }
 
+// When the switch is exhaustive, the last value is not tested and becomes the default branch.
+fn build_int_switch(
+    discr: Exp,
+    mut branches: Vec<(Constant, MlT)>,
+    otherwise: rustc_middle::mir::BasicBlock,
+    exhaustive: bool,
+) -> MlT {
+    let default = match branches.pop() {
+        Some((_, last)) if exhaustive => last,
+        Some(branch) => {
+            branches.push(branch);
+            mk_goto(otherwise)
+        }
+        None => mk_goto(otherwise),
+    };
+    build_constant_switch(discr, branches.into_iter(), default)
+}
+
 fn build_constant_switch<T>(discr: Exp, targets: T, default: MlT) -> MlT
 where
     T: Iterator<Item = (Constant, MlT)> + DoubleEndedIterator,
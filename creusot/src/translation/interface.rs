@@ -26,7 +26,7 @@ pub fn interface_for<'tcx>(
 
     sig.contract.variant = Vec::new();
 
-    let mut decls: Vec<_> = closure_generic_decls(ctx.tcx, def_id).collect();
+    let mut decls: Vec<_> = closure_generic_decls(ctx, def_id).collect();
 
     if ctx.tcx.is_closure(def_id) {
         if let TyKind::Closure(_, subst) = ctx.tcx.type_of(def_id).kind() {
@@ -40,7 +40,7 @@ pub fn translate_logic_or_predicate<'tcx>(
     sig.contract = Contract::new();
 
     let mut decls: Vec<_> = Vec::new();
-    decls.extend(all_generic_decls_for(ctx.tcx, def_id));
+    decls.extend(all_generic_decls_for(ctx, def_id));
     decls.extend(names.to_clones(ctx));
 
     let proof_modl = proof_module(ctx, def_id);
@@ -112,7 +112,7 @@ pub(crate) fn spec_axiom(sig: &Signature) -> Axiom {
     condition.subst(&[("result".into(), func_call)].into_iter().collect());
     let args: Vec<_> = sig.args.iter().cloned().filter(|arg| &*arg.0 != "_").collect();
 
-    let axiom = if args.is_empty() { condition } else { Exp::Forall(args, box condition) };
+    let axiom = if args.is_empty() { condition } else { Exp::Forall(args, vec![], box condition) };
 
     Axiom { name: format!("{}_spec", &*sig.name).into(), axiom }
 }
@@ -142,7 +142,7 @@ fn definition_axiom(sig: &Signature, body: Exp) -> Axiom {
 
     let args = sig.args.clone();
 
-    let axiom = if args.is_empty() { condition } else { Exp::Forall(args, box condition) };
+    let axiom = if args.is_empty() { condition } else { Exp::Forall(args, vec![], box condition) };
 
     Axiom { name: "def".into(), axiom }
 }
@@ -159,7 +159,7 @@ fn implementation_module<'tcx>(
     names.use_full_clones = true;
 
     let mut decls: Vec<_> = Vec::new();
-    decls.extend(all_generic_decls_for(ctx.tcx, def_id));
+    decls.extend(all_generic_decls_for(ctx, def_id));
     decls.extend(names.to_clones(ctx));
     decls.push(Decl::LetFun(LetFun { sig, rec: true, ghost: true, body }));
 
@@ -5,17 +5,19 @@ use crate::util::closure_owner;
 use crate::{ctx::*, util};
 use rustc_macros::{TyDecodable, TyEncodable, TypeFoldable};
 use rustc_middle::thir::{self, ExprKind, Thir};
-use rustc_middle::ty::subst::{InternalSubsts, Subst, SubstsRef};
+use rustc_middle::ty::subst::{GenericArg, InternalSubsts, Subst, SubstsRef};
 use why3::declaration::Contract;
 use why3::exp::Exp;
 use why3::Ident;
 
-use self::typing::{pearlite_stub, Term};
+use self::typing::{pearlite_stub, Term, TermKind};
 
+use super::traits::resolve_assoc_item_opt;
 use super::LocalIdent;
 use rustc_hir::def_id::DefId;
 use rustc_middle::mir::{Body, Location};
-use rustc_middle::ty::{self, TyCtxt};
+use rustc_middle::ty::{self, Mutability, ParamEnv, TyCtxt, TyKind};
+use rustc_span::Symbol;
 
 mod builtins;
 mod lower;
@@ -67,6 +69,10 @@ impl<'tcx> PreContract<'tcx> {
     pub fn is_empty(&self) -> bool {
         self.requires.is_empty() && self.ensures.is_empty() && self.variant.is_none()
     }
+
+    pub fn has_requires(&self) -> bool {
+        !self.requires.is_empty()
+    }
 }
 
 #[derive(Clone, Debug, TyEncodable, TyDecodable)]
@@ -235,8 +241,91 @@ pub fn contract_of<'tcx>(ctx: &mut TranslationCtx<'_, 'tcx>, def_id: DefId) -> P
     if let Some(extern_spec) = ctx.extern_spec(def_id).cloned() {
         extern_spec.contract.get_pre(ctx).subst(ctx.tcx, subst)
     } else {
-        contract_clauses_of(ctx, def_id).unwrap().get_pre(ctx).subst(ctx.tcx, subst)
+        let mut contract =
+            contract_clauses_of(ctx, def_id).unwrap().get_pre(ctx).subst(ctx.tcx, subst);
+        type_invariant_clauses(ctx, def_id, &mut contract);
+        contract
+    }
+}
+
+// Program functions assume the type invariants of their arguments and must establish those of
+// their result. Behind a mutable borrow, the invariant must hold both before and after the call.
+// Trait methods and their implementations are left alone, as the contracts of the latter would
+// no longer refine those of the former.
+fn type_invariant_clauses<'tcx>(
+    ctx: &TranslationCtx<'_, 'tcx>,
+    def_id: DefId,
+    contract: &mut PreContract<'tcx>,
+) {
+    if !def_id.is_local()
+        || util::item_type(ctx.tcx, def_id) != util::ItemType::Program
+        || util::is_law(ctx.tcx, def_id)
+        || util::is_spec(ctx.tcx, def_id)
+        || ctx.trait_of_item(def_id).is_some()
+        || ctx.impl_of_method(def_id).and_then(|id| ctx.trait_id_of_impl(id)).is_some()
+    {
+        return;
+    }
+    let inv_name = Symbol::intern("creusot_type_invariant_method");
+    let Some(inv_id) = ctx.get_diagnostic_item(inv_name) else { return };
+
+    let param_env = ctx.param_env(def_id);
+    let span = ctx.def_span(def_id);
+    let (inputs, output) = util::inputs_and_output(ctx.tcx, def_id);
+
+    for (id, ty) in inputs {
+        if id.name.is_empty() {
+            continue;
+        }
+        let arg = Term { ty, kind: TermKind::Var(id.name), span };
+
+        match ty.kind() {
+            TyKind::Ref(_, inner, Mutability::Mut) => {
+                let cur = Term { ty: *inner, kind: TermKind::Cur { term: box arg.clone() }, span };
+                let fin = Term { ty: *inner, kind: TermKind::Fin { term: box arg }, span };
+                contract.requires.extend(type_invariant_of(ctx, param_env, inv_id, cur));
+                contract.ensures.extend(type_invariant_of(ctx, param_env, inv_id, fin));
+            }
+            // Shared borrows are transparent in terms
+            TyKind::Ref(_, inner, Mutability::Not) => {
+                let arg = Term { ty: *inner, ..arg };
+                contract.requires.extend(type_invariant_of(ctx, param_env, inv_id, arg));
+            }
+            _ => contract.requires.extend(type_invariant_of(ctx, param_env, inv_id, arg)),
+        }
     }
+
+    // The final value of a returned mutable borrow is chosen by the caller, so only its current
+    // value is known to satisfy the invariant.
+    let result = Term { ty: output, kind: TermKind::Var(Symbol::intern("result")), span };
+    let result = match output.kind() {
+        TyKind::Ref(_, inner, Mutability::Mut) => {
+            Term { ty: *inner, kind: TermKind::Cur { term: box result }, span }
+        }
+        TyKind::Ref(_, inner, Mutability::Not) => Term { ty: *inner, ..result },
+        _ => result,
+    };
+    contract.ensures.extend(type_invariant_of(ctx, param_env, inv_id, result));
+}
+
+// The invariant of the type of `term` applied to it, if this type has one
+fn type_invariant_of<'tcx>(
+    ctx: &TranslationCtx<'_, 'tcx>,
+    param_env: ParamEnv<'tcx>,
+    inv_id: DefId,
+    term: Term<'tcx>,
+) -> Option<Term<'tcx>> {
+    let subst = ctx.mk_substs([GenericArg::from(term.ty)].iter());
+    // Only the types with an invariant implement the trait
+    resolve_assoc_item_opt(ctx.tcx, param_env, inv_id, subst)?;
+
+    let span = term.span;
+    let fun = Term { ty: ctx.mk_fn_def(inv_id, subst), kind: TermKind::Item(inv_id, subst), span };
+    Some(Term {
+        ty: ctx.tcx.types.bool,
+        kind: TermKind::Call { id: inv_id, subst, fun: box fun, args: vec![term] },
+        span,
+    })
 }
 
 struct PurityVisitor<'a, 'tcx> {
@@ -5,7 +5,7 @@ use crate::translation::traits::resolve_opt;
 use crate::translation::ty::translate_ty;
 use crate::util::get_builtin;
 use rustc_hir::def_id::DefId;
-use rustc_middle::ty::{subst::SubstsRef, TyCtxt};
+use rustc_middle::ty::{subst::SubstsRef, IntTy, Ty, TyCtxt, TyKind, UintTy};
 use rustc_span::{symbol::sym, Symbol};
 use why3::exp::{BinOp, Constant, Exp, Purity, UnOp};
 use why3::QName;
@@ -36,32 +36,27 @@ impl<'tcx> Lower<'_, '_, 'tcx> {
         let builtin_attr = get_builtin(self.ctx.tcx, def_id.unwrap());
 
         if builtin_attr == Some(Symbol::intern("add_int")) {
-            let l = args.remove(0);
-            let r = args.remove(0);
+            let (l, r) = self.int_operands(def_id.unwrap(), args);
             self.names.import_prelude_module(PreludeModule::Int);
 
             return Some(Exp::BinaryOp(BinOp::Add, box l, box r));
         } else if builtin_attr == Some(Symbol::intern("sub_int")) {
-            let l = args.remove(0);
-            let r = args.remove(0);
+            let (l, r) = self.int_operands(def_id.unwrap(), args);
             self.names.import_prelude_module(PreludeModule::Int);
 
             return Some(Exp::BinaryOp(BinOp::Sub, box l, box r));
         } else if builtin_attr == Some(Symbol::intern("mul_int")) {
-            let l = args.remove(0);
-            let r = args.remove(0);
+            let (l, r) = self.int_operands(def_id.unwrap(), args);
             self.names.import_prelude_module(PreludeModule::Int);
 
             return Some(Exp::BinaryOp(BinOp::Mul, box l, box r));
         } else if builtin_attr == Some(Symbol::intern("div_int")) {
-            let l = args.remove(0);
-            let r = args.remove(0);
+            let (l, r) = self.int_operands(def_id.unwrap(), args);
             self.names.import_prelude_module(PreludeModule::Int);
 
             return Some(Exp::Call(box Exp::pure_var("div".into()), vec![l, r]));
         } else if builtin_attr == Some(Symbol::intern("rem_int")) {
-            let l = args.remove(0);
-            let r = args.remove(0);
+            let (l, r) = self.int_operands(def_id.unwrap(), args);
             self.names.import_prelude_module(PreludeModule::Int);
 
             return Some(Exp::Call(box Exp::pure_var("Int.mod".into()), vec![l, r]));
@@ -71,7 +66,8 @@ impl<'tcx> Lower<'_, '_, 'tcx> {
 
             return Some(Exp::UnaryOp(UnOp::Neg, box a));
         } else if builtin_attr == Some(Symbol::intern("<=")) {
-            let ty = self.ctx.tcx.fn_sig(def_id.unwrap()).no_bound_vars().unwrap().inputs()[0];
+            let sig = self.ctx.tcx.erase_late_bound_regions(self.ctx.tcx.fn_sig(def_id.unwrap()));
+            let ty = sig.inputs()[0];
             translate_ty(self.ctx, self.names, rustc_span::DUMMY_SP, ty);
 
             let l = args.remove(0);
@@ -79,7 +75,8 @@ impl<'tcx> Lower<'_, '_, 'tcx> {
 
             return Some(Exp::BinaryOp(BinOp::Le, box l, box r));
         } else if builtin_attr == Some(Symbol::intern("<")) {
-            let ty = self.ctx.tcx.fn_sig(def_id.unwrap()).no_bound_vars().unwrap().inputs()[0];
+            let sig = self.ctx.tcx.erase_late_bound_regions(self.ctx.tcx.fn_sig(def_id.unwrap()));
+            let ty = sig.inputs()[0];
             translate_ty(self.ctx, self.names, rustc_span::DUMMY_SP, ty);
 
             let l = args.remove(0);
@@ -87,7 +84,8 @@ impl<'tcx> Lower<'_, '_, 'tcx> {
 
             return Some(Exp::BinaryOp(BinOp::Lt, box l, box r));
         } else if builtin_attr == Some(Symbol::intern(">=")) {
-            let ty = self.ctx.tcx.fn_sig(def_id.unwrap()).no_bound_vars().unwrap().inputs()[0];
+            let sig = self.ctx.tcx.erase_late_bound_regions(self.ctx.tcx.fn_sig(def_id.unwrap()));
+            let ty = sig.inputs()[0];
             translate_ty(self.ctx, self.names, rustc_span::DUMMY_SP, ty);
 
             let l = args.remove(0);
@@ -95,7 +93,8 @@ impl<'tcx> Lower<'_, '_, 'tcx> {
 
             return Some(Exp::BinaryOp(BinOp::Ge, box l, box r));
         } else if builtin_attr == Some(Symbol::intern(">")) {
-            let ty = self.ctx.tcx.fn_sig(def_id.unwrap()).no_bound_vars().unwrap().inputs()[0];
+            let sig = self.ctx.tcx.erase_late_bound_regions(self.ctx.tcx.fn_sig(def_id.unwrap()));
+            let ty = sig.inputs()[0];
             translate_ty(self.ctx, self.names, rustc_span::DUMMY_SP, ty);
 
             let l = args.remove(0);
@@ -103,7 +102,8 @@ impl<'tcx> Lower<'_, '_, 'tcx> {
 
             return Some(Exp::BinaryOp(BinOp::Gt, box l, box r));
         } else if builtin_attr == Some(Symbol::intern("==")) {
-            let ty = self.ctx.tcx.fn_sig(def_id.unwrap()).no_bound_vars().unwrap().inputs()[0];
+            let sig = self.ctx.tcx.erase_late_bound_regions(self.ctx.tcx.fn_sig(def_id.unwrap()));
+            let ty = sig.inputs()[0];
             translate_ty(self.ctx, self.names, rustc_span::DUMMY_SP, ty);
 
             let l = args.remove(0);
@@ -111,7 +111,8 @@ impl<'tcx> Lower<'_, '_, 'tcx> {
 
             return Some(Exp::BinaryOp(BinOp::Eq, box l, box r));
         } else if builtin_attr == Some(Symbol::intern("!=")) {
-            let ty = self.ctx.tcx.fn_sig(def_id.unwrap()).no_bound_vars().unwrap().inputs()[0];
+            let sig = self.ctx.tcx.erase_late_bound_regions(self.ctx.tcx.fn_sig(def_id.unwrap()));
+            let ty = sig.inputs()[0];
             translate_ty(self.ctx, self.names, rustc_span::DUMMY_SP, ty);
 
             let l = args.remove(0);
@@ -213,6 +214,44 @@ impl<'tcx> Lower<'_, '_, 'tcx> {
     }
 }
 
+impl<'tcx> Lower<'_, '_, 'tcx> {
+    // Arithmetic on `Int` accepts a machine integer on either side, which is then coerced to its
+    // model, as `@` would do
+    fn int_operands(&mut self, def_id: DefId, args: &mut Vec<Exp>) -> (Exp, Exp) {
+        let sig = self.ctx.tcx.erase_late_bound_regions(self.ctx.tcx.fn_sig(def_id));
+        let l = args.remove(0);
+        let r = args.remove(0);
+        (self.coerce_to_int(sig.inputs()[0], l), self.coerce_to_int(sig.inputs()[1], r))
+    }
+
+    fn coerce_to_int(&mut self, ty: Ty<'tcx>, arg: Exp) -> Exp {
+        let to_int = match ty.kind() {
+            TyKind::Uint(UintTy::U8) => "prelude.UInt8.to_int",
+            TyKind::Uint(UintTy::U16) => "prelude.UInt16.to_int",
+            TyKind::Uint(UintTy::U32) => "mach.int.UInt32.to_int",
+            TyKind::Uint(UintTy::U64 | UintTy::Usize) => "mach.int.UInt64.to_int",
+            TyKind::Uint(UintTy::U128) => "prelude.UInt128.to_int",
+            TyKind::Int(IntTy::I8) => "prelude.Int8.to_int",
+            TyKind::Int(IntTy::I16) => "prelude.Int16.to_int",
+            TyKind::Int(IntTy::I32) => "mach.int.Int32.to_int",
+            TyKind::Int(IntTy::I64 | IntTy::Isize) => "mach.int.Int64.to_int",
+            TyKind::Int(IntTy::I128) => "prelude.Int128.to_int",
+            _ => return arg,
+        };
+
+        match arg {
+            Exp::Const(Constant::Uint(v, _)) => Exp::Const(Constant::Uint(v, None)),
+            Exp::Const(Constant::Int(v, _)) => Exp::Const(Constant::Int(v, None)),
+            arg if !self.ctx.opts.bounds_check => arg,
+            arg => {
+                let to_int = QName::from_string(to_int).unwrap();
+                self.names.import_builtin_module(to_int.clone().module_qname());
+                Exp::Call(box Exp::pure_qvar(to_int.without_search_path()), vec![arg])
+            }
+        }
+    }
+}
+
 fn trait_id_of_method(tcx: TyCtxt, def_id: DefId) -> Option<DefId> {
     tcx.impl_of_method(def_id).and_then(|id| tcx.trait_id_of_impl(id))
 }
@@ -1,14 +1,14 @@
 use super::typing::{self, Literal, LogicalOp, Pattern, Term, TermKind};
 use crate::translation::traits::resolve_assoc_item_opt;
-use crate::translation::ty::translate_ty;
-use crate::translation::ty::variant_accessor_name;
-use crate::util::constructor_qname;
+use crate::translation::ty::{float_qname, translate_ty};
+use crate::translation::ty::{is_transparent_newtype, variant_accessor_name};
+use crate::util::{constructor_qname, ItemType};
 use crate::{ctx::*, util};
 use rustc_middle::ty;
 use rustc_middle::ty::ParamEnv;
 use rustc_middle::ty::TyKind;
-use why3::exp::{BinOp, Constant, Exp, Pattern as Pat, Purity};
-use why3::QName;
+use why3::exp::{BinOp, Constant, Exp, Pattern as Pat, Purity, Trigger};
+use why3::{Ident, QName};
 
 pub fn lower_pure<'tcx>(
     ctx: &mut TranslationCtx<'_, 'tcx>,
@@ -66,6 +66,10 @@ impl<'tcx> Lower<'_, '_, 'tcx> {
                             _ => unreachable!(),
                         }
                     }
+                    Literal::Float(f) => {
+                        let ty = translate_ty(self.ctx, self.names, rustc_span::DUMMY_SP, term.ty);
+                        Constant::float(f, Some(ty))
+                    }
                     Literal::Bool(b) => {
                         if b {
                             Constant::const_true()
@@ -73,6 +77,11 @@ impl<'tcx> Lower<'_, '_, 'tcx> {
                             Constant::const_false()
                         }
                     }
+                    Literal::Char(c) => {
+                        self.names
+                            .import_builtin_module(QName::from_string("prelude.Char").unwrap());
+                        Constant::Char(c)
+                    }
                 };
                 Exp::Const(c)
             }
@@ -98,6 +107,48 @@ impl<'tcx> Lower<'_, '_, 'tcx> {
                 })
             }
             TermKind::Var(v) => Exp::pure_var(util::ident_of(v)),
+            TermKind::Binary { op, operand_ty, box lhs, box rhs }
+                if operand_ty.is_floating_point() =>
+            {
+                let fty = match operand_ty.kind() {
+                    TyKind::Float(fty) => *fty,
+                    _ => unreachable!(),
+                };
+                let lhs = self.lower_term(lhs);
+                let rhs = self.lower_term(rhs);
+
+                use typing::BinOp::*;
+                let name = match op {
+                    Add => "add_rne",
+                    Sub => "sub_rne",
+                    Mul => "mul_rne",
+                    Div => "div_rne",
+                    Lt => "lt",
+                    Le => "le",
+                    Gt => "gt",
+                    Ge => "ge",
+                    Eq | Ne => return Exp::BinaryOp(binop_to_binop(op), box lhs, box rhs),
+                    Rem => unreachable!("remainder of floats is rejected by the typechecker"),
+                };
+                let fun = Exp::QVar(float_qname(self.names, fty, name), self.pure);
+                Exp::Call(box fun, vec![lhs, rhs])
+            }
+            // Chars are ordered by their code. Distinct chars have distinct codes, so they are
+            // compared for equality with the logical equality of chars.
+            TermKind::Binary { op, operand_ty, box lhs, box rhs } if operand_ty.is_char() => {
+                translate_ty(self.ctx, self.names, rustc_span::DUMMY_SP, operand_ty);
+                let lhs = self.lower_term(lhs);
+                let rhs = self.lower_term(rhs);
+                if let typing::BinOp::Eq | typing::BinOp::Ne = op {
+                    return Exp::BinaryOp(binop_to_binop(op), box lhs, box rhs);
+                }
+
+                self.names.import_prelude_module(PreludeModule::Int);
+                let code = Exp::QVar(QName::from_string("Char.code").unwrap(), self.pure);
+                let lhs = Exp::Call(box code.clone(), vec![lhs]);
+                let rhs = Exp::Call(box code, vec![rhs]);
+                Exp::BinaryOp(binop_to_binop(op), box lhs, box rhs)
+            }
             TermKind::Binary { op, operand_ty, box lhs, box rhs } => {
                 translate_ty(self.ctx, self.names, rustc_span::DUMMY_SP, operand_ty);
 
@@ -123,6 +174,14 @@ impl<'tcx> Lower<'_, '_, 'tcx> {
                 box self.lower_term(lhs),
                 box self.lower_term(rhs),
             ),
+            TermKind::Unary { op: typing::UnOp::Neg, box arg } if arg.ty.is_floating_point() => {
+                let fty = match arg.ty.kind() {
+                    TyKind::Float(fty) => *fty,
+                    _ => unreachable!(),
+                };
+                let fun = Exp::QVar(float_qname(self.names, fty, "neg"), self.pure);
+                Exp::Call(box fun, vec![self.lower_term(arg)])
+            }
             TermKind::Unary { op, box arg } => {
                 let op = match op {
                     typing::UnOp::Not => why3::exp::UnOp::Not,
@@ -150,6 +209,22 @@ impl<'tcx> Lower<'_, '_, 'tcx> {
                     return args.remove(0);
                 }
 
+                // Overloaded operators resolve to the function of their implementation, which
+                // must itself be logical to be used here
+                if self.pure == Purity::Logic
+                    && method.0 != id
+                    && util::item_type(self.ctx.tcx, method.0) == ItemType::Program
+                    && util::get_builtin(self.ctx.tcx, method.0).is_none()
+                {
+                    self.ctx.crash_and_error(
+                        term.span,
+                        &format!(
+                            "called program function {:?} in logical context",
+                            self.ctx.tcx.def_path_str(method.0)
+                        ),
+                    );
+                }
+
                 self.lookup_builtin(method, &mut args).unwrap_or_else(|| {
                     self.ctx.translate(method.0);
 
@@ -164,21 +239,46 @@ impl<'tcx> Lower<'_, '_, 'tcx> {
                     }
                 })
             }
-            TermKind::Forall { binder, box body } => {
+            TermKind::Forall { binder, trigger, box body } => {
                 let ty = translate_ty(self.ctx, self.names, rustc_span::DUMMY_SP, binder.1);
-                Exp::Forall(vec![(binder.0.into(), ty)], box self.lower_term(body))
+                let binder = (Ident::from(binder.0), ty);
+                let trigger = self.lower_trigger(trigger);
+                match self.lower_term(body) {
+                    // Merge directly nested quantifiers, as in `forall<x : Int, y : Int>`
+                    Exp::Forall(mut binders, inner_trigger, body)
+                        if trigger.is_empty() && binders.iter().all(|b| b.0 != binder.0) =>
+                    {
+                        binders.insert(0, binder);
+                        Exp::Forall(binders, inner_trigger, body)
+                    }
+                    body => Exp::Forall(vec![binder], trigger, box body),
+                }
             }
-            TermKind::Exists { binder, box body } => {
+            TermKind::Exists { binder, trigger, box body } => {
                 let ty = translate_ty(self.ctx, self.names, rustc_span::DUMMY_SP, binder.1);
-                Exp::Exists(vec![(binder.0.into(), ty)], box self.lower_term(body))
+                let binder = (Ident::from(binder.0), ty);
+                let trigger = self.lower_trigger(trigger);
+                match self.lower_term(body) {
+                    Exp::Exists(mut binders, inner_trigger, body)
+                        if trigger.is_empty() && binders.iter().all(|b| b.0 != binder.0) =>
+                    {
+                        binders.insert(0, binder);
+                        Exp::Exists(binders, inner_trigger, body)
+                    }
+                    body => Exp::Exists(vec![binder], trigger, box body),
+                }
             }
             TermKind::Constructor { adt, variant, fields } => {
                 self.names.import_prelude_module(PreludeModule::Type);
-                let args = fields.into_iter().map(|f| self.lower_term(f)).collect();
+                let mut args: Vec<_> = fields.into_iter().map(|f| self.lower_term(f)).collect();
 
                 let ctor = constructor_qname(self.ctx.tcx, &adt.variants()[variant]);
                 crate::ty::translate_tydecl(self.ctx, self.ctx.def_span(adt.did()), adt.did());
-                Exp::Constructor { ctor, args }
+                if is_transparent_newtype(self.ctx, adt) {
+                    args.remove(0)
+                } else {
+                    Exp::Constructor { ctor, args }
+                }
             }
             TermKind::Cur { box term } => Exp::Current(box self.lower_term(term)),
             TermKind::Fin { box term } => Exp::Final(box self.lower_term(term)),
@@ -259,6 +359,9 @@ impl<'tcx> Lower<'_, '_, 'tcx> {
             TermKind::Projection { box lhs, name, def: did } => {
                 let def = self.ctx.tcx.adt_def(did);
                 let lhs = self.lower_term(lhs);
+                if is_transparent_newtype(self.ctx, def) {
+                    return lhs;
+                }
                 self.ctx
                     .translate_accessor(def.variants()[0u32.into()].fields[name.as_usize()].did);
                 let accessor = variant_accessor_name(
@@ -279,12 +382,24 @@ impl<'tcx> Lower<'_, '_, 'tcx> {
         }
     }
 
+    fn lower_trigger(&mut self, trigger: Vec<Term<'tcx>>) -> Vec<Trigger> {
+        if trigger.is_empty() {
+            return Vec::new();
+        }
+        vec![Trigger(trigger.into_iter().map(|pat| self.lower_term(pat)).collect())]
+    }
+
     fn lower_pat(&mut self, pat: Pattern<'tcx>) -> Pat {
         match pat {
             Pattern::Constructor { adt, variant, fields } => {
                 let variant = &adt.variants()[variant];
-                let fields = fields.into_iter().map(|pat| self.lower_pat(pat)).collect();
-                Pat::ConsP(constructor_qname(self.ctx.tcx, variant), fields)
+                let mut fields: Vec<_> =
+                    fields.into_iter().map(|pat| self.lower_pat(pat)).collect();
+                if is_transparent_newtype(self.ctx, adt) {
+                    fields.remove(0)
+                } else {
+                    Pat::ConsP(constructor_qname(self.ctx.tcx, variant), fields)
+                }
             }
             Pattern::Wildcard => Pat::Wildcard,
             Pattern::Binder(name) => Pat::VarP(name.into()),
@@ -347,7 +462,7 @@ pub(super) fn mk_binders(func: Exp, args: Vec<Exp>) -> Exp {
 
 fn is_identity_from<'tcx>(tcx: TyCtxt<'tcx>, id: DefId, subst: SubstsRef<'tcx>) -> bool {
     if tcx.def_path_str(id) == "std::convert::From::from" && subst.len() == 1 {
-        let out_ty = tcx.fn_sig(id).no_bound_vars().unwrap().output();
+        let out_ty = tcx.erase_late_bound_regions(tcx.fn_sig(id)).output();
         return subst[0].expect_ty() == out_ty.subst(tcx, subst);
     }
     false
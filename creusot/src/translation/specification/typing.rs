@@ -11,7 +11,7 @@ pub use rustc_middle::thir;
 use rustc_middle::thir::{
     visit, Adt, ArmId, Block, ExprId, ExprKind, Pat, PatKind, StmtId, StmtKind, Thir,
 };
-use rustc_middle::ty::{AdtDef, Ty, TyKind, UpvarSubsts};
+use rustc_middle::ty::{AdtDef, FloatTy, Ty, TyKind, UpvarSubsts};
 use rustc_middle::{
     mir::{BorrowKind, Mutability::*},
     ty::{subst::SubstsRef, TyCtxt, WithOptConstParam},
@@ -64,8 +64,8 @@ pub enum TermKind<'tcx> {
     Binary { op: BinOp, operand_ty: Ty<'tcx>, lhs: Box<Term<'tcx>>, rhs: Box<Term<'tcx>> },
     Logical { op: LogicalOp, lhs: Box<Term<'tcx>>, rhs: Box<Term<'tcx>> },
     Unary { op: UnOp, arg: Box<Term<'tcx>> },
-    Forall { binder: (String, Ty<'tcx>), body: Box<Term<'tcx>> },
-    Exists { binder: (String, Ty<'tcx>), body: Box<Term<'tcx>> },
+    Forall { binder: (String, Ty<'tcx>), trigger: Vec<Term<'tcx>>, body: Box<Term<'tcx>> },
+    Exists { binder: (String, Ty<'tcx>), trigger: Vec<Term<'tcx>>, body: Box<Term<'tcx>> },
     Call { id: DefId, subst: SubstsRef<'tcx>, fun: Box<Term<'tcx>>, args: Vec<Term<'tcx>> },
     Constructor { adt: AdtDef<'tcx>, variant: VariantIdx, fields: Vec<Term<'tcx>> },
     Tuple { fields: Vec<Term<'tcx>> },
@@ -101,6 +101,8 @@ impl<'tcx> TypeFoldable<'tcx> for Literal {
 pub enum Literal {
     Bool(bool),
     Int(u128, LitIntType),
+    Float(f64),
+    Char(char),
 }
 
 #[derive(Clone, Debug, TyDecodable, TyEncodable, TypeFoldable)]
@@ -113,6 +115,14 @@ pub enum Pattern<'tcx> {
 }
 
 pub fn typecheck(tcx: TyCtxt, id: LocalDefId) -> CreusotResult<Term> {
+    with_thir_term(tcx, id, |lower, expr| lower.expr_term(expr))
+}
+
+fn with_thir_term<'tcx, R>(
+    tcx: TyCtxt<'tcx>,
+    id: LocalDefId,
+    f: impl for<'a> FnOnce(&ThirTerm<'a, 'tcx>, ExprId) -> CreusotResult<R>,
+) -> CreusotResult<R> {
     let (thir, expr) = tcx.thir_body(WithOptConstParam::unknown(id)).map_err(|_| CrErr)?;
     let thir = thir.borrow();
     if thir.exprs.is_empty() {
@@ -123,7 +133,7 @@ pub fn typecheck(tcx: TyCtxt, id: LocalDefId) -> CreusotResult<Term> {
 
     let lower = ThirTerm { tcx, item_id: id, thir: &thir };
 
-    lower.expr_term(expr)
+    f(&lower, expr)
 }
 
 struct ThirTerm<'a, 'tcx> {
@@ -155,26 +165,38 @@ impl<'a, 'tcx> ThirTerm<'a, 'tcx> {
                 let lhs = self.expr_term(lhs)?;
                 let rhs = self.expr_term(rhs)?;
 
+                if operand_ty.is_bool() {
+                    let op = match op {
+                        rustc_middle::mir::BinOp::BitAnd => Some(LogicalOp::And),
+                        rustc_middle::mir::BinOp::BitOr => Some(LogicalOp::Or),
+                        _ => None,
+                    };
+                    if let Some(op) = op {
+                        let kind = TermKind::Logical { op, lhs: box lhs, rhs: box rhs };
+                        return Ok(Term { ty, span, kind });
+                    }
+                }
+
                 let op = match op {
                     rustc_middle::mir::BinOp::Add => BinOp::Add,
                     rustc_middle::mir::BinOp::Sub => BinOp::Sub,
                     rustc_middle::mir::BinOp::Mul => BinOp::Mul,
                     rustc_middle::mir::BinOp::Div => BinOp::Div,
-                    rustc_middle::mir::BinOp::Rem => BinOp::Rem,
-                    rustc_middle::mir::BinOp::BitXor => {
-                        return Err(Error::new(self.thir[expr].span, "unsupported operation"))
+                    rustc_middle::mir::BinOp::Rem if operand_ty.is_floating_point() => {
+                        return Err(Error::new(span, "unsupported operation on floats"))
                     }
-                    rustc_middle::mir::BinOp::BitAnd => {
-                        return Err(Error::new(self.thir[expr].span, "unsupported operation"))
-                    }
-                    rustc_middle::mir::BinOp::BitOr => {
-                        return Err(Error::new(self.thir[expr].span, "unsupported operation"))
-                    }
-                    rustc_middle::mir::BinOp::Shl => {
-                        return Err(Error::new(self.thir[expr].span, "unsupported operation"))
-                    }
-                    rustc_middle::mir::BinOp::Shr => {
-                        return Err(Error::new(self.thir[expr].span, "unsupported operation"))
+                    rustc_middle::mir::BinOp::Rem => BinOp::Rem,
+                    rustc_middle::mir::BinOp::BitXor if operand_ty.is_bool() => BinOp::Ne,
+                    // Machine integers can't be built back from mathematical ones in the logic
+                    rustc_middle::mir::BinOp::BitXor
+                    | rustc_middle::mir::BinOp::BitAnd
+                    | rustc_middle::mir::BinOp::BitOr
+                    | rustc_middle::mir::BinOp::Shl
+                    | rustc_middle::mir::BinOp::Shr => {
+                        return Err(Error::new(
+                            self.thir[expr].span,
+                            "bitwise operations on integers are only supported in programs",
+                        ))
                     }
                     rustc_middle::mir::BinOp::Eq => BinOp::Eq,
                     rustc_middle::mir::BinOp::Lt => BinOp::Lt,
@@ -223,6 +245,16 @@ impl<'a, 'tcx> ThirTerm<'a, 'tcx> {
                 let lit = match lit.node {
                     LitKind::Bool(b) => Literal::Bool(b),
                     LitKind::Int(u, s) => Literal::Int(u, s),
+                    LitKind::Char(c) => Literal::Char(c),
+                    // Parse at the precision of the type so that `f32` literals are rounded once
+                    LitKind::Float(f, _) => {
+                        let f = f.as_str().replace('_', "");
+                        let value = match ty.kind() {
+                            TyKind::Float(FloatTy::F32) => f.parse::<f32>().map(|f| f as f64),
+                            _ => f.parse::<f64>(),
+                        };
+                        Literal::Float(value.unwrap())
+                    }
                     _ => unimplemented!("Unsupported literal"),
                 };
                 Ok(Term { ty, span, kind: TermKind::Lit(lit) })
@@ -231,13 +263,19 @@ impl<'a, 'tcx> ThirTerm<'a, 'tcx> {
                 use Stub::*;
                 match pearlite_stub(self.tcx, f_ty) {
                     Some(Forall) => {
-                        let (binder, body) = self.quant_term(args[0])?;
-                        Ok(Term { ty, span, kind: TermKind::Forall { binder, body: box body } })
+                        let (binder, trigger, body) = self.quant_term(args[0])?;
+                        let kind = TermKind::Forall { binder, trigger, body: box body };
+                        Ok(Term { ty, span, kind })
                     }
                     Some(Exists) => {
-                        let (binder, body) = self.quant_term(args[0])?;
-                        Ok(Term { ty, span, kind: TermKind::Exists { binder, body: box body } })
+                        let (binder, trigger, body) = self.quant_term(args[0])?;
+                        let kind = TermKind::Exists { binder, trigger, body: box body };
+                        Ok(Term { ty, span, kind })
                     }
+                    Some(Trigger) => Err(Error::new(
+                        span,
+                        "triggers may only appear directly after the binders of a quantifier",
+                    )),
                     Some(Fin) => {
                         let term = self.expr_term(args[0])?;
 
@@ -335,7 +373,11 @@ impl<'a, 'tcx> ThirTerm<'a, 'tcx> {
             // TODO: If we deref a shared borrow this should be erased?
             // Can it happen?
             ExprKind::Deref { arg } => {
-                if self.thir[arg].ty.is_box() || self.thir[arg].ty.ref_mutability() == Some(Not) {
+                if let Some(pointer) = self.transparent_deref(arg) {
+                    self.expr_term(pointer)
+                } else if self.thir[arg].ty.is_box()
+                    || self.thir[arg].ty.ref_mutability() == Some(Not)
+                {
                     self.expr_term(arg)
                 } else {
                     Ok(Term { ty, span, kind: TermKind::Cur { term: box self.expr_term(arg)? } })
@@ -416,6 +458,10 @@ impl<'a, 'tcx> ThirTerm<'a, 'tcx> {
             ExprKind::NamedConst { def_id, substs, .. } => {
                 Ok(Term { ty, span, kind: TermKind::Item(def_id, substs) })
             }
+            // Const generic parameters are declared as logical constants of the module
+            ExprKind::ConstParam { param, .. } => {
+                Ok(Term { ty, span, kind: TermKind::Var(param.name) })
+            }
             ref ek => todo!("lower_expr: {:?}", ek),
         }
     }
@@ -538,7 +584,43 @@ impl<'a, 'tcx> ThirTerm<'a, 'tcx> {
         }
     }
 
-    fn quant_term(&self, body: ExprId) -> Result<((String, Ty<'tcx>), Term<'tcx>), Error> {
+    // Dereferencing an `Rc` or `Arc` calls `Deref::deref`, which leaves their value unchanged
+    fn transparent_deref(&self, expr: ExprId) -> Option<ExprId> {
+        let ExprKind::Call { ty, ref args, from_hir_call: false, .. } = self.thir[expr].kind else {
+            return None;
+        };
+        let TyKind::FnDef(def_id, subst) = ty.kind() else { return None };
+        let is_deref = self.tcx.def_path_str(*def_id) == "std::ops::Deref::deref";
+        (is_deref && util::is_transparent_pointer(self.tcx, subst.type_at(0))).then(|| args[0])
+    }
+
+    // The body of a quantifier, and the patterns of the trigger it starts with, if any
+    fn trigger_term(&self, expr: ExprId) -> CreusotResult<(Vec<Term<'tcx>>, Term<'tcx>)> {
+        match self.thir[expr].kind {
+            ExprKind::Scope { value, .. } => self.trigger_term(value),
+            ExprKind::Block { body: Block { ref stmts, expr: Some(expr), .. } }
+                if stmts.is_empty() =>
+            {
+                self.trigger_term(expr)
+            }
+            ExprKind::Call { ty, ref args, .. }
+                if matches!(pearlite_stub(self.tcx, ty), Some(Stub::Trigger)) =>
+            {
+                let pats = match self.expr_term(args[0])?.kind {
+                    TermKind::Tuple { fields } => fields,
+                    _ => unreachable!("trigger patterns are not a tuple"),
+                };
+                Ok((pats, self.expr_term(args[1])?))
+            }
+            _ => Ok((Vec::new(), self.expr_term(expr)?)),
+        }
+    }
+
+    // The binder, trigger and body of the closure passed to a quantifier
+    fn quant_term(
+        &self,
+        body: ExprId,
+    ) -> Result<((String, Ty<'tcx>), Vec<Term<'tcx>>, Term<'tcx>), Error> {
         trace!("{:?}", self.thir[body].kind);
         match self.thir[body].kind {
             ExprKind::Scope { value, .. } => self.quant_term(value),
@@ -549,9 +631,14 @@ impl<'a, 'tcx> ThirTerm<'a, 'tcx> {
                 };
 
                 let name = self.tcx.fn_arg_names(closure_id)[0];
-                let ty = sig.input(0).skip_binder();
-
-                Ok(((name.to_string(), ty), typecheck(self.tcx, closure_id.expect_local())?))
+                // The binder may mention lifetimes of the closure, which are meaningless in logic
+                let ty = self.tcx.erase_late_bound_regions(sig.input(0));
+
+                let (trigger, body) =
+                    with_thir_term(self.tcx, closure_id.expect_local(), |lower, body| {
+                        lower.trigger_term(body)
+                    })?;
+                Ok(((name.to_string(), ty), trigger, body))
             }
             _ => Err(Error::new(self.thir[body].span, "unexpected error in quantifier")),
         }
@@ -562,6 +649,7 @@ impl<'a, 'tcx> ThirTerm<'a, 'tcx> {
 pub(crate) enum Stub {
     Forall,
     Exists,
+    Trigger,
     Fin,
     Cur,
     Impl,
@@ -581,6 +669,9 @@ pub(crate) fn pearlite_stub<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>) -> Option<Stu
         if Some(*id) == tcx.get_diagnostic_item(Symbol::intern("exists")) {
             return Some(Stub::Exists);
         }
+        if Some(*id) == tcx.get_diagnostic_item(Symbol::intern("trigger")) {
+            return Some(Stub::Trigger);
+        }
         if Some(*id) == tcx.get_diagnostic_item(Symbol::intern("fin")) {
             return Some(Stub::Fin);
         }
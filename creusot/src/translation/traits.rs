@@ -45,7 +45,7 @@ impl<'tcx> TranslationCtx<'_, 'tcx> {
         self.translate_trait(trait_ref.def_id);
 
         // Impl Refinement module
-        let mut decls: Vec<_> = own_generic_decls_for(self.tcx, impl_id).collect();
+        let mut decls: Vec<_> = own_generic_decls_for(self, impl_id).collect();
         let mut names = CloneMap::new(self.tcx, impl_id, true);
 
         // names.param_env(param_env);
@@ -73,7 +73,7 @@ impl<'tcx> TranslationCtx<'_, 'tcx> {
                 names.insert(impl_item_id, subst);
             }
 
-            decls.extend(own_generic_decls_for(self.tcx, impl_item_id));
+            decls.extend(own_generic_decls_for(self, impl_item_id));
 
             let refn_subst = subst.rebase_onto(self.tcx, impl_id, trait_ref.substs);
             let refinement = names.insert(trait_item_id, refn_subst);
@@ -121,7 +121,7 @@ impl<'tcx> TranslationCtx<'_, 'tcx> {
 
         self.translated_items.insert(def_id);
 
-        let mut decls: Vec<_> = all_generic_decls_for(self.tcx, def_id).collect();
+        let mut decls: Vec<_> = all_generic_decls_for(self, def_id).collect();
         let name = item_name(self.tcx, def_id);
 
         let ty_decl = match self.tcx.associated_item(def_id).container {
@@ -193,11 +193,14 @@ fn logic_refinement<'tcx>(
     let trait_postcond = trait_contract.ensures_conj();
 
     let retty = names.with_public_clones(|names| translate_ty(ctx, names, span, output));
-    let post_refn =
-        Exp::Forall(vec![("result".into(), retty)], box impl_postcond.implies(trait_postcond));
+    let post_refn = Exp::Forall(
+        vec![("result".into(), retty)],
+        vec![],
+        box impl_postcond.implies(trait_postcond),
+    );
 
     let mut refn = trait_precond.implies(impl_precond).and(post_refn);
-    refn = if args.is_empty() { refn } else { Exp::Forall(args, box refn) };
+    refn = if args.is_empty() { refn } else { Exp::Forall(args, vec![], box refn) };
 
     let name = item_name(ctx.tcx, impl_item_id);
 
@@ -336,6 +339,8 @@ pub fn resolve_assoc_item_opt<'tcx>(
             Some((leaf_def.item.def_id, leaf_substs))
         }
         ImplSource::Param(_, _) => Some((def_id, substs)),
+        // Calls on trait objects are dispatched at runtime, so we stick to the trait method
+        ImplSource::Object(_) => Some((def_id, substs)),
         ImplSource::Closure(impl_data) => Some((impl_data.closure_def_id, impl_data.substs)),
         _ => unimplemented!(),
     }
@@ -1,7 +1,7 @@
 use rustc_hir::def_id::DefId;
 use rustc_middle::ty::subst::SubstsRef;
 use rustc_middle::ty::{self, subst::InternalSubsts, ProjectionTy, Ty, TyCtxt, TyKind::*};
-use rustc_middle::ty::{ClosureSubsts, FieldDef, VariantDef};
+use rustc_middle::ty::{subst::GenericArgKind, AdtDef, ClosureSubsts, FieldDef, VariantDef};
 use rustc_span::Symbol;
 use rustc_span::{Span, DUMMY_SP};
 use std::collections::VecDeque;
@@ -9,6 +9,7 @@ use why3::declaration::{AdtDecl, ConstructorDecl, LetFun};
 use why3::declaration::{Contract, Decl, Signature};
 use why3::exp::{Exp, Pattern};
 use why3::Ident;
+use why3::Print;
 
 use why3::declaration::TyDecl;
 use why3::{ty::Type as MlT, QName};
@@ -58,7 +59,7 @@ fn translate_ty_inner<'tcx>(
         Uint(uity) => uintty_to_ty(ctx, names, uity),
         Float(flty) => floatty_to_ty(names, flty),
         Adt(def, s) => {
-            if def.is_box() {
+            if util::is_transparent_pointer(ctx.tcx, ty) {
                 return translate_ty_inner(trans, ctx, names, span, s[0].expect_ty());
             }
 
@@ -137,6 +138,26 @@ fn translate_ty_inner<'tcx>(
             names.import_prelude_module(PreludeModule::Prelude);
             MlT::TConstructor(QName::from_string("opaque_ptr").unwrap())
         }
+        // Trait objects can only be observed through the contracts of their trait, so each trait
+        // gets an opaque type for its objects
+        Dynamic(preds, _) => match preds.principal() {
+            Some(principal) => {
+                let principal = principal.skip_binder();
+                names.import_prelude_module(PreludeModule::Type);
+                let cons = MlT::TConstructor(translate_dyn_name(ctx, principal.def_id));
+                let args = principal
+                    .substs
+                    .types()
+                    .map(|t| translate_ty_inner(trans, ctx, names, span, t))
+                    .collect();
+                MlT::TApp(box cons, args)
+            }
+            // Objects of auto traits alone have no methods at all
+            None => {
+                names.import_prelude_module(PreludeModule::Prelude);
+                MlT::TConstructor(QName::from_string("opaque_ptr").unwrap())
+            }
+        },
         Closure(id, subst) => {
             ctx.translate(*id);
 
@@ -160,6 +181,7 @@ pub fn translate_projection_ty<'tcx>(
 ) -> MlT {
     // ctx.translate(pty.trait_def_id(ctx.tcx));
     let name = names.insert(pty.item_def_id, pty.substs).qname(ctx.tcx, pty.item_def_id);
+    tracing::info!(?pty, name = %name.display(), "lowered projection");
     MlT::TConstructor(name)
 }
 
@@ -216,6 +238,20 @@ fn translate_ty_name(ctx: &mut TranslationCtx<'_, '_>, did: DefId) -> QName {
     QName { module: vec![module_name(ctx.tcx, did)], name: name.into() }
 }
 
+// The opaque type of the objects of the trait `trait_id`, parameterized by the type parameters
+// of the trait other than `Self`. The name is built from the full path of the trait, since all
+// these types live in the same module.
+fn translate_dyn_name(ctx: &mut TranslationCtx<'_, '_>, trait_id: DefId) -> QName {
+    let name = format!("dyn_{}", util::ident_path(ctx.tcx, trait_id).to_string().to_lowercase());
+
+    if !ctx.types.contains_key(&trait_id) {
+        let ty_params = ty_param_names(ctx.tcx, trait_id).skip(1).collect();
+        ctx.add_type(&[trait_id], TyDecl::Opaque { ty_name: name.clone().into(), ty_params });
+    }
+
+    QName { module: vec!["Type".into()], name: name.into() }
+}
+
 fn translate_ty_param(p: Symbol) -> Ident {
     Ident::build(&p.to_string().to_lowercase())
 }
@@ -259,6 +295,18 @@ pub fn translate_tydecl(ctx: &mut TranslationCtx<'_, '_>, span: Span, did: DefId
         return;
     }
 
+    let adt = ctx.tcx.adt_def(did);
+    if is_transparent_newtype(ctx, adt) {
+        let ty_name = translate_ty_name(ctx, did).name;
+        let ty_params: Vec<_> = ty_param_names(ctx.tcx, did).collect();
+        let substs = InternalSubsts::identity_for_item(ctx.tcx, did);
+        let alias = field_ty(ctx, &mut names, &adt.non_enum_variant().fields[0], substs);
+        ctx.add_type(&bg, TyDecl::Alias { ty_name, ty_params, alias });
+        return;
+    }
+
+    check_well_founded(ctx, span, &bg);
+
     let mut decls = Vec::new();
     for did in &bg {
         decls.push(build_ty_decl(ctx, &mut names, *did));
@@ -266,6 +314,77 @@ pub fn translate_tydecl(ctx: &mut TranslationCtx<'_, '_>, span: Span, did: DefId
     ctx.add_type(&bg, TyDecl::Adt { tys: decls });
 }
 
+// Why3 rejects algebraic types which have no finite values, so we check that every member of a
+// binding group has a variant which can be built from previously inhabited members.
+// Types outside of the group are assumed to be inhabited.
+fn check_well_founded(ctx: &TranslationCtx, span: Span, bg: &[DefId]) {
+    let mut inhabited: Vec<DefId> = Vec::new();
+
+    loop {
+        let before = inhabited.len();
+        for did in bg {
+            if inhabited.contains(did) {
+                continue;
+            }
+            let adt = ctx.tcx.adt_def(*did);
+            let substs = InternalSubsts::identity_for_item(ctx.tcx, *did);
+            let constructible = adt.variants().iter().any(|var| {
+                var.fields
+                    .iter()
+                    .all(|f| is_buildable(ctx.tcx, f.ty(ctx.tcx, substs), bg, &inhabited))
+            });
+            if constructible {
+                inhabited.push(*did);
+            }
+        }
+        if inhabited.len() == before {
+            break;
+        }
+    }
+
+    if let Some(did) = bg.iter().find(|did| !inhabited.contains(did)) {
+        ctx.crash_and_error(
+            span,
+            &format!("recursive type `{}` has no finite values", ctx.def_path_str(*did)),
+        );
+    }
+}
+
+// Whether a value of `ty` can be built without first building an uninhabited member of `bg`,
+// looking through tuples and transparent pointers
+fn is_buildable<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>, bg: &[DefId], inhabited: &[DefId]) -> bool {
+    match ty.kind() {
+        Adt(_, s) if util::is_transparent_pointer(tcx, ty) => {
+            is_buildable(tcx, s.type_at(0), bg, inhabited)
+        }
+        Adt(def, _) => !bg.contains(&def.did()) || inhabited.contains(&def.did()),
+        Tuple(tys) => tys.iter().all(|ty| is_buildable(tcx, ty, bg, inhabited)),
+        _ => true,
+    }
+}
+
+/// Whether `adt` is a single-field struct translated as an alias of its field's type, so that
+/// building it and projecting its field are the identity.
+pub fn is_transparent_newtype(ctx: &TranslationCtx, adt: AdtDef) -> bool {
+    if !ctx.opts.transparent_newtypes
+        || !adt.is_struct()
+        || adt.non_enum_variant().fields.len() != 1
+        || util::is_trusted(ctx.tcx, adt.did())
+    {
+        return false;
+    }
+
+    // Aliases cannot be recursive
+    if ty_binding_group(ctx.tcx, adt.did()).len() > 1 {
+        return false;
+    }
+    let substs = InternalSubsts::identity_for_item(ctx.tcx, adt.did());
+    !adt.non_enum_variant().fields[0].ty(ctx.tcx, substs).walk().any(|arg| match arg.unpack() {
+        GenericArgKind::Type(ty) => matches!(ty.kind(), Adt(def, _) if def.did() == adt.did()),
+        _ => false,
+    })
+}
+
 fn build_ty_decl<'tcx>(
     ctx: &mut TranslationCtx<'_, 'tcx>,
     names: &mut CloneMap<'tcx>,
@@ -550,21 +669,26 @@ fn uintty_to_ty(
     }
 }
 
-fn floatty_to_ty(_: &mut CloneMap<'_>, fty: &rustc_middle::ty::FloatTy) -> MlT {
-    use rustc_middle::ty::FloatTy::*;
-
-    match fty {
-        F32 => single_ty(),
-        F64 => double_ty(),
-    }
+fn floatty_to_ty(names: &mut CloneMap<'_>, fty: &rustc_middle::ty::FloatTy) -> MlT {
+    MlT::TConstructor(float_qname(names, *fty, "t"))
 }
 
-pub fn double_ty() -> MlT {
-    MlT::TConstructor(QName::from_string("double").unwrap())
-}
+/// The item `name` of the prelude module for floats of type `fty`, which wraps the IEEE 754
+/// theory of Why3
+pub fn float_qname(names: &mut CloneMap<'_>, fty: ty::FloatTy, name: &str) -> QName {
+    use ty::FloatTy::*;
 
-pub fn single_ty() -> MlT {
-    MlT::TConstructor(QName::from_string("single").unwrap())
+    let module = match fty {
+        F32 => {
+            names.import_prelude_module(PreludeModule::Float32);
+            "Float32"
+        }
+        F64 => {
+            names.import_prelude_module(PreludeModule::Float64);
+            "Float64"
+        }
+    };
+    QName { module: vec![module.into()], name: name.into() }
 }
 
 pub fn u8_ty() -> MlT {
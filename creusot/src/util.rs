@@ -1,7 +1,7 @@
 use crate::translation::ty::closure_accessor_name;
 use crate::{ctx::*, translation};
 use rustc_ast::ast::{MacArgs, MacArgsEq};
-use rustc_ast::{AttrItem, AttrKind, Attribute};
+use rustc_ast::{AttrItem, AttrKind, Attribute, Mutability};
 use rustc_hir::{def::DefKind, def_id::DefId};
 use rustc_middle::ty::subst::{InternalSubsts, SubstsRef};
 use rustc_middle::ty::{self, Ty, TyKind, VariantDef};
@@ -57,6 +57,10 @@ pub(crate) fn is_invariant(tcx: TyCtxt, def_id: DefId) -> bool {
     invariant_name(tcx, def_id).is_some()
 }
 
+pub(crate) fn is_loop_variant(tcx: TyCtxt, def_id: DefId) -> bool {
+    get_attr(tcx.get_attrs_unchecked(def_id), &["creusot", "spec", "loop_variant"]).is_some()
+}
+
 pub(crate) fn is_assertion(tcx: TyCtxt, def_id: DefId) -> bool {
     get_attr(tcx.get_attrs_unchecked(def_id), &["creusot", "spec", "assert"]).is_some()
 }
@@ -83,10 +87,26 @@ pub(crate) fn is_trusted(tcx: TyCtxt, def_id: DefId) -> bool {
     get_attr(tcx.get_attrs_unchecked(def_id), &["creusot", "decl", "trusted"]).is_some()
 }
 
+pub(crate) fn is_inline_body(tcx: TyCtxt, def_id: DefId) -> bool {
+    get_attr(tcx.get_attrs_unchecked(def_id), &["creusot", "decl", "inline_body"]).is_some()
+}
+
 pub(crate) fn is_law(tcx: TyCtxt, def_id: DefId) -> bool {
     get_attr(tcx.get_attrs_unchecked(def_id), &["creusot", "decl", "law"]).is_some()
 }
 
+// Boxes and reference-counted pointers are translated as the value they point to
+pub(crate) fn is_transparent_pointer(tcx: TyCtxt, ty: Ty) -> bool {
+    match ty.kind() {
+        TyKind::Adt(def, _) => {
+            def.is_box()
+                || tcx.is_diagnostic_item(Symbol::intern("Rc"), def.did())
+                || tcx.is_diagnostic_item(Symbol::intern("Arc"), def.did())
+        }
+        _ => false,
+    }
+}
+
 pub(crate) fn is_extern_spec(tcx: TyCtxt, def_id: DefId) -> bool {
     get_attr(tcx.get_attrs_unchecked(def_id), &["creusot", "extern_spec"]).is_some()
 }
@@ -200,7 +220,7 @@ pub fn module_name(tcx: TyCtxt, def_id: DefId) -> Ident {
     }
 }
 
-fn ident_path(tcx: TyCtxt, def_id: DefId) -> Ident {
+pub(crate) fn ident_path(tcx: TyCtxt, def_id: DefId) -> Ident {
     use heck::CamelCase;
 
     let def_path = tcx.def_path(def_id);
@@ -277,7 +297,9 @@ pub fn item_type(tcx: TyCtxt<'_>, def_id: DefId) -> ItemType {
                 ItemType::Program
             }
         }
-        DefKind::AssocConst => ItemType::Constant,
+        DefKind::Const | DefKind::AssocConst | DefKind::Static(Mutability::Not) => {
+            ItemType::Constant
+        }
         DefKind::Closure => ItemType::Closure,
         DefKind::Struct | DefKind::Enum => ItemType::Type,
         DefKind::AssocTy => ItemType::AssocTy,
@@ -321,6 +343,9 @@ pub fn inputs_and_output<'tcx>(
     (inputs, output)
 }
 
+// Subterms smaller than this are cheap enough to repeat
+const SHARED_TERM_MIN_SIZE: usize = 4;
+
 pub fn signature_of<'tcx>(
     ctx: &mut TranslationCtx<'_, 'tcx>,
     names: &mut CloneMap<'tcx>,
@@ -349,6 +374,12 @@ pub fn signature_of<'tcx>(
         contract.ensures.push(Exp::Const(Constant::const_false()));
     }
 
+    if ctx.opts.share_terms {
+        for clause in contract.requires.iter_mut().chain(contract.ensures.iter_mut()) {
+            clause.share_common_subterms(SHARED_TERM_MIN_SIZE);
+        }
+    }
+
     if let TyKind::Closure(_, subst) = ctx.tcx.type_of(def_id).kind() {
         let post_subst = names.with_public_clones(|names| {
             closure_capture_subst(ctx.tcx, names, def_id, subst, subst.as_closure().kind(), false)
@@ -557,24 +588,14 @@ impl<'a> ExpMutVisitor for ClosureSubst {
                     std::mem::swap(&mut self.0, &mut s);
                 }
             }
-            Exp::Forall(binders, exp) => {
-                let mut subst = self.0.clone();
-                binders.iter().for_each(|k| {
-                    subst.remove(&k.0);
-                });
-
-                std::mem::swap(&mut self.0, &mut subst);
-                self.visit_mut(exp);
-                std::mem::swap(&mut self.0, &mut subst);
-            }
-            Exp::Exists(binders, exp) => {
+            Exp::Forall(binders, _, _) | Exp::Exists(binders, _, _) => {
                 let mut subst = self.0.clone();
                 binders.iter().for_each(|k| {
                     subst.remove(&k.0);
                 });
 
                 std::mem::swap(&mut self.0, &mut subst);
-                self.visit_mut(exp);
+                super_visit_mut(self, exp);
                 std::mem::swap(&mut self.0, &mut subst);
             }
             _ => super_visit_mut(self, exp),
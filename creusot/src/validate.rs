@@ -3,6 +3,8 @@ use crate::util::is_law;
 use rustc_hir::def_id::LocalDefId;
 use rustc_hir::itemlikevisit::ItemLikeVisitor;
 use rustc_hir::{ForeignItem, ImplItem, Item, TraitItem};
+use rustc_middle::mir::visit::{PlaceContext, Visitor};
+use rustc_middle::mir::{Body, Location, Place, ProjectionElem, Rvalue};
 use rustc_middle::ty::TyCtxt;
 use rustc_span::Span;
 
@@ -32,3 +34,45 @@ pub fn validate_traits(ctx: &mut TranslationCtx) {
         ctx.error(sp, "Laws cannot have additional generic parameters");
     }
 }
+
+struct RawPointers<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    body: &'a Body<'tcx>,
+    uses: Vec<(Span, &'static str)>,
+}
+
+impl<'tcx> Visitor<'tcx> for RawPointers<'_, 'tcx> {
+    fn visit_place(&mut self, place: &Place<'tcx>, _: PlaceContext, location: Location) {
+        let deref_raw = place.iter_projections().any(|(base, elem)| {
+            elem == ProjectionElem::Deref && base.ty(self.body, self.tcx).ty.is_unsafe_ptr()
+        });
+        if deref_raw {
+            let span = self.body.source_info(location).span;
+            self.uses.push((span, "dereferencing raw pointers is not supported"));
+        }
+    }
+
+    fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
+        if let Rvalue::AddressOf(..) = rvalue {
+            let span = self.body.source_info(location).span;
+            self.uses.push((span, "taking raw pointers is not supported"));
+        }
+        self.super_rvalue(rvalue, location);
+    }
+}
+
+/// Raw pointers have no memory model, so a body which dereferences or creates one cannot be
+/// translated. Report every such use at once, pointing users to `#[trusted]`, whose bodies are
+/// not translated.
+pub fn validate_no_raw_pointers<'tcx>(ctx: &TranslationCtx<'_, 'tcx>, body: &Body<'tcx>) {
+    let mut visitor = RawPointers { tcx: ctx.tcx, body, uses: Vec::new() };
+    visitor.visit_body(body);
+
+    if visitor.uses.is_empty() {
+        return;
+    }
+    for (span, msg) in visitor.uses {
+        ctx.error(span, &format!("{}, consider marking this function as `#[trusted]`", msg));
+    }
+    ctx.tcx.sess.abort_if_errors();
+}
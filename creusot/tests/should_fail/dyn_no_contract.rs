@@ -0,0 +1,11 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+trait Tr {
+    fn value(&self) -> u32;
+}
+
+// The call goes through the vtable of `dyn Tr`, but `Tr::value` has no contract to rely on
+fn call(x: &dyn Tr) -> u32 {
+    x.value()
+}
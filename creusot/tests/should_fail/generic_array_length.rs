@@ -0,0 +1,8 @@
+#![feature(generic_const_exprs)]
+#![allow(incomplete_features)]
+extern crate creusot_contracts;
+
+// The length depends on `N` and cannot be evaluated
+pub fn repeat_twice<const N: usize>(x: u8) -> [u8; N * 2] {
+    [x; N * 2]
+}
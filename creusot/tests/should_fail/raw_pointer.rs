@@ -0,0 +1,7 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+#[ensures(true)]
+fn read(p: *const u32) -> u32 {
+    unsafe { *p }
+}
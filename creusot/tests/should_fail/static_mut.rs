@@ -0,0 +1,9 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+// Only immutable statics can be translated to logical constants
+static mut COUNTER: u32 = 0;
+
+fn read() -> u32 {
+    unsafe { COUNTER }
+}
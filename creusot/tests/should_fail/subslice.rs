@@ -0,0 +1,8 @@
+extern crate creusot_contracts;
+
+pub fn tail(s: &[u32]) -> &[u32] {
+    match s {
+        [_, rest @ ..] => rest,
+        [] => s,
+    }
+}
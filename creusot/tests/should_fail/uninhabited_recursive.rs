@@ -0,0 +1,11 @@
+extern crate creusot_contracts;
+
+use std::rc::Rc;
+
+pub enum Forest {
+    Trees(Box<Tree>, Box<Forest>),
+}
+
+pub struct Tree(u32, Rc<Forest>);
+
+pub fn size(_: Tree) {}
@@ -0,0 +1,8 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+// Indexing a vector is a program function, specifications must index its model instead
+#[requires(v[0] == 0u32)]
+fn first(v: &Vec<u32>) -> u32 {
+    v[0]
+}
@@ -0,0 +1,16 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+const SIZE: usize = 4;
+
+// The length is evaluated to a literal
+#[ensures((@result).len() == 8)]
+pub fn repeat_twice(x: u8) -> [u8; SIZE * 2] {
+    [x; SIZE * 2]
+}
+
+// The length is a const parameter
+#[ensures((@result).len() == @N)]
+pub fn repeat_n<const N: usize>(x: u8) -> [u8; N] {
+    [x; N]
+}
@@ -0,0 +1,13 @@
+extern crate creusot_contracts;
+
+pub fn literal(x: u32) -> [u32; 3] {
+    [x, 1, 2]
+}
+
+pub fn repeat(x: bool) -> [bool; 32] {
+    [x; 32]
+}
+
+pub fn promoted() -> &'static [u32; 2] {
+    &[4, 5]
+}
@@ -0,0 +1,25 @@
+extern crate creusot_contracts;
+
+use creusot_contracts::*;
+
+#[ensures(@result == (@s).len())]
+pub fn slice_len(s: &[u32]) -> usize {
+    s.len()
+}
+
+#[ensures(@result == (@a).len())]
+pub fn array_len(a: [u32; 4]) -> usize {
+    a.len()
+}
+
+#[requires((@s).len() > 0)]
+#[ensures((@^s)[0] == 0u32)]
+pub fn zero_first(s: &mut [u32]) {
+    s[0] = 0;
+}
+
+#[ensures(result == 0u32)]
+pub fn through_slice(mut a: [u32; 3]) -> u32 {
+    zero_first(&mut a);
+    a[0]
+}
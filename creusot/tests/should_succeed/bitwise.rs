@@ -0,0 +1,16 @@
+// INT_MODEL_BV
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+pub fn flags(x: u8) -> bool {
+    x & 0b101 == (x | 0b100) ^ 0b100
+}
+
+#[requires(@n < 32)]
+pub fn shifts(x: u32, n: u32) -> u32 {
+    (x << n) >> n
+}
+
+pub fn signed_shift(x: i64) -> i64 {
+    x >> 3
+}
@@ -0,0 +1,29 @@
+// BUILT
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+// Constants are not promoted in built MIR, the array is a local borrowed for the whole function
+#[ensures((@*result).len() == 2)]
+pub fn not_promoted() -> &'static [u32; 2] {
+    &[4, 5]
+}
+
+// Built MIR keeps the fake reads and user type ascriptions of the match
+#[ensures(result == (x == None))]
+pub fn match_option(x: Option<u32>) -> bool {
+    let y: Option<u32> = x;
+    match y {
+        Some(_) => false,
+        None => true,
+    }
+}
+
+#[ensures(@result == 10)]
+pub fn count() -> u32 {
+    let mut i = 0;
+    #[invariant(bounded, @i <= 10)]
+    while i < 10 {
+        i += 1;
+    }
+    i
+}
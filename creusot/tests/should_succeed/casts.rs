@@ -0,0 +1,56 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+#[ensures(b ==> @result == 1)]
+#[ensures(!b ==> @result == 0)]
+pub fn from_bool(b: bool) -> u32 {
+    b as u32
+}
+
+#[ensures(@result == @x)]
+pub fn widen_unsigned(x: u8) -> u64 {
+    x as u64
+}
+
+#[ensures(@result == @x)]
+pub fn widen_signed(x: i16) -> i128 {
+    x as i128
+}
+
+#[ensures(@result == @x)]
+pub fn widen_to_signed(x: u32) -> i64 {
+    x as i64
+}
+
+pub fn truncate() -> u8 {
+    let x: u32 = 300;
+    let y = x as u8;
+    proof_assert!(@y == 44);
+    y
+}
+
+pub fn truncate_signed() -> i8 {
+    let x: i32 = 200;
+    let y = x as i8;
+    proof_assert!(@y == -56);
+    y
+}
+
+pub fn negative_to_unsigned() -> u16 {
+    let x: i32 = -1;
+    let y = x as u16;
+    proof_assert!(@y == 65535);
+    y
+}
+
+pub fn unsigned_to_signed() -> i32 {
+    let x: u32 = 4_000_000_000;
+    let y = x as i32;
+    proof_assert!(@y == -294967296);
+    y
+}
+
+#[ensures(@x < 128 ==> @result == @x)]
+pub fn same_width(x: u8) -> i8 {
+    x as i8
+}
@@ -0,0 +1,35 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+#[ensures(@result == @c)]
+pub fn code(c: char) -> u32 {
+    c as u32
+}
+
+#[ensures(@result == @b)]
+pub fn from_byte(b: u8) -> char {
+    b as char
+}
+
+#[ensures(result == (@c < @d))]
+pub fn lt(c: char, d: char) -> bool {
+    c < d
+}
+
+#[ensures(result == (c == d))]
+pub fn eq(c: char, d: char) -> bool {
+    c == d
+}
+
+#[ensures(result == ('a' <= c && c <= 'z'))]
+pub fn is_lowercase(c: char) -> bool {
+    match c {
+        'a'..='z' => true,
+        _ => false,
+    }
+}
+
+#[ensures(result ==> @c >= 0x30 && @c <= 0x39)]
+pub fn is_digit(c: char) -> bool {
+    c >= '0' && c <= '9'
+}
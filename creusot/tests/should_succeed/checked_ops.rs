@@ -0,0 +1,26 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+// `rustc_inherit_overflow_checks` makes arithmetic checked even though Creusot turns overflow
+// checks off: each operation yields a `(value, overflowed)` pair followed by an assertion.
+
+#[rustc_inherit_overflow_checks]
+#[requires(@x < 100)]
+#[ensures(@result == @x + 1)]
+pub fn add(x: u32) -> u32 {
+    x + 1
+}
+
+#[rustc_inherit_overflow_checks]
+#[requires(0 < @x)]
+#[ensures(@result == @x - 1)]
+pub fn sub(x: u8) -> u8 {
+    x - 1
+}
+
+#[rustc_inherit_overflow_checks]
+#[requires(-100 <= @x && @x <= 100)]
+#[ensures(@result == @x * 2)]
+pub fn mul(x: i32) -> i32 {
+    x * 2
+}
@@ -0,0 +1,36 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+// The bound of `F` quantifies over the lifetime of its argument
+pub fn apply<F: for<'a> Fn(&'a u32) -> &'a u32>(f: F, x: &u32) -> &u32 {
+    f(x)
+}
+
+pub fn apply_id() -> u32 {
+    let x = 5;
+    *apply(|y: &u32| y, &x)
+}
+
+#[ensures(result == x)]
+pub fn pick<'a, 'b>(x: &'a u32, _y: &'b u32) -> &'a u32 {
+    x
+}
+
+#[ensures(*result == **x)]
+#[ensures(^result == ^*x)]
+pub fn reborrow_inner<'a, 'b>(x: &'a mut &'b mut u32) -> &'a mut u32 {
+    &mut **x
+}
+
+#[requires(@**x < 100)]
+#[ensures(@^*x == @**x + 1)]
+pub fn increment_through<'a, 'b>(x: &'a mut &'b mut u32) {
+    let y = reborrow_inner(x);
+    *y += 1;
+}
+
+#[logic]
+#[ensures(result == (*x <= *y))]
+pub fn le_refs<'a, 'b>(x: &'a Int, y: &'b Int) -> bool {
+    *x <= *y
+}
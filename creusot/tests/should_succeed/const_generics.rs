@@ -0,0 +1,29 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+// `N` is declared as a logical constant of the module of `first`
+#[requires(0usize < N)]
+#[ensures(result == a[0])]
+pub fn first<const N: usize>(a: [u32; N]) -> u32 {
+    a[0]
+}
+
+// Cloning `first` binds `N` to a definition equal to the argument
+pub fn call_first() -> u32 {
+    first([1, 2, 3])
+}
+
+#[ensures(result == N)]
+pub fn length<const N: usize>(_: [u32; N]) -> usize {
+    N
+}
+
+// Const arguments which are themselves parameters are passed directly
+#[ensures(result == N)]
+pub fn forward<const N: usize>(a: [u32; N]) -> usize {
+    length::<N>(a)
+}
+
+pub fn call_length() -> usize {
+    length([0; 4])
+}
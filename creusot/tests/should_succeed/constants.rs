@@ -0,0 +1,13 @@
+extern crate creusot_contracts;
+
+pub fn isize_literal() -> isize {
+    -1
+}
+
+pub fn char_literal() -> char {
+    'a'
+}
+
+pub fn unit_literal() -> () {
+    ()
+}
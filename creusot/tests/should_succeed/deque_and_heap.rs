@@ -0,0 +1,34 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+use std::collections::{BinaryHeap, VecDeque};
+
+#[ensures(result == (Some(1u32), Some(2u32), None))]
+pub fn deque() -> (Option<u32>, Option<u32>, Option<u32>) {
+    let mut d = VecDeque::new();
+    d.push_back(2);
+    d.push_front(1);
+    proof_assert! { (@d).len() == 2 }
+    let a = d.pop_front();
+    let b = d.pop_back();
+    (a, b, d.pop_front())
+}
+
+#[ensures(match result { Some(t) => @t == 5, None => false })]
+pub fn heap_max() -> Option<u32> {
+    let mut h = BinaryHeap::new();
+    h.push(3u32);
+    h.push(5);
+    h.push(1);
+    proof_assert! { (@h).occ(5u32) == 1 && (@h).len() == 3 }
+    match h.peek() {
+        Some(t) => {
+            proof_assert! { @*t == 5 }
+        }
+        None => {}
+    }
+    h.pop()
+}
+
+#[ensures(FBag::new().insert(x).insert(x).occ(x) == 2)]
+#[ensures(FBag::singleton(x).union(FBag::singleton(y)).contains(y))]
+pub fn bags(x: u32, y: u32) {}
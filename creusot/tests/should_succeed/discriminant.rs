@@ -0,0 +1,21 @@
+extern crate creusot_contracts;
+
+pub enum Signed {
+    Neg = -1,
+    Zero = 0,
+    Pos = 1,
+}
+
+pub fn signed_to_int(s: Signed) -> isize {
+    s as isize
+}
+
+pub enum Colour {
+    Red,
+    Green,
+    Blue = 10,
+}
+
+pub fn colour_to_int(c: Colour) -> u8 {
+    c as u8
+}
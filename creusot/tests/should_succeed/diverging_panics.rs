@@ -0,0 +1,8 @@
+// DIVERGING_PANICS
+extern crate creusot_contracts;
+
+use creusot_contracts::*;
+
+pub fn divide(y: u32, x: u32) -> u32 {
+    y / x
+}
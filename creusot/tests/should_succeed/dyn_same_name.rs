@@ -0,0 +1,30 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+// Both traits must get their own type for their objects
+pub mod a {
+    use creusot_contracts::*;
+
+    pub trait Tr {
+        #[ensures(@result <= 10)]
+        fn get(&self) -> u32;
+    }
+}
+
+pub mod b {
+    use creusot_contracts::*;
+
+    pub trait Tr {
+        #[ensures(result)]
+        fn get(&self) -> bool;
+    }
+}
+
+#[ensures(@result <= 10)]
+pub fn both(x: &dyn a::Tr, y: &dyn b::Tr) -> u32 {
+    if y.get() {
+        x.get()
+    } else {
+        0
+    }
+}
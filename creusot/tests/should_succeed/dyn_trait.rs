@@ -0,0 +1,23 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+pub trait Counter {
+    #[ensures(@result <= 10)]
+    fn count(&self) -> u32;
+}
+
+pub trait Get<T> {
+    #[ensures(result == *x)]
+    fn get(&self, x: &T) -> T;
+}
+
+// The calls go through the vtable and are only known by the contracts of the trait
+#[ensures(@result <= 20)]
+pub fn twice(c: &dyn Counter) -> u32 {
+    c.count() + c.count()
+}
+
+#[ensures(result == 1u64)]
+pub fn get_one(g: &dyn Get<u64>) -> u64 {
+    g.get(&1)
+}
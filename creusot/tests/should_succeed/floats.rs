@@ -0,0 +1,13 @@
+extern crate creusot_contracts;
+
+use creusot_contracts::*;
+
+#[ensures(result == x * 0.5)]
+pub fn half(x: f64) -> f64 {
+    x * 0.5
+}
+
+#[ensures(result == (x < y))]
+pub fn less(x: f32, y: f32) -> bool {
+    x < y
+}
@@ -0,0 +1,27 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+pub struct Point {
+    x: u32,
+    y: u32,
+}
+
+#[inline_body]
+fn get_x(p: &Point) -> u32 {
+    p.x
+}
+
+#[inline_body]
+fn swap(p: Point) -> Point {
+    Point { x: p.y, y: p.x }
+}
+
+#[ensures(result == p.x)]
+pub fn uses_get(p: &Point) -> u32 {
+    get_x(p)
+}
+
+#[ensures(result.x == p.y)]
+pub fn uses_swap(p: Point) -> Point {
+    swap(p)
+}
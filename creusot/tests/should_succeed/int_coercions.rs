@@ -0,0 +1,19 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+// A machine integer combined with an `Int` is coerced to its model, so these sums can't overflow
+#[requires(x + @y < 256)]
+#[ensures(@result == @x + y)]
+pub fn add(x: u8, y: u8) -> u8 {
+    x + y
+}
+
+#[ensures(@result == 2 * x)]
+pub fn double(x: u32) -> u64 {
+    x as u64 * 2
+}
+
+#[ensures(@result == x - 1)]
+pub fn pred(x: i32) -> i64 {
+    x as i64 - 1
+}
@@ -0,0 +1,26 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+#[ensures(@result == @n)]
+pub fn count(n: usize) -> usize {
+    let mut s = 0;
+    #[invariant(count, @s == produced.len())]
+    for _ in 0..n {
+        s += 1;
+    }
+    s
+}
+
+// The invariants of the loop see the `produced` of the loop, not the local of the same name
+#[requires(@n <= 1000)]
+#[ensures(@result == @n * (@n - 1) / 2)]
+pub fn sum_range(n: u32) -> u32 {
+    let produced = 0u32;
+    let mut sum = produced;
+    #[invariant(sum, @sum == produced.len() * (produced.len() - 1) / 2)]
+    #[invariant(bounded, produced.len() <= @n)]
+    for i in 0..n {
+        sum += i;
+    }
+    sum
+}
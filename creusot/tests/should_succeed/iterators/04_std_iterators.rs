@@ -0,0 +1,35 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+#[ensures(@result == (@v).len())]
+pub fn count_iter(v: &[u32]) -> usize {
+    let mut n = 0;
+    #[invariant(count, @n == produced.len())]
+    #[invariant(bounded, produced.len() <= (@v).len())]
+    for _ in v.iter() {
+        n += 1;
+    }
+    n
+}
+
+#[ensures(result ==> forall<i : Int> 0 <= i && i < (@v).len() ==> @((@v)[i]) == i)]
+pub fn is_identity(v: &[usize]) -> bool {
+    #[invariant(prefix, forall<j : Int> 0 <= j && j < produced.len() ==> @((@v)[j]) == j)]
+    for (i, x) in v.iter().enumerate() {
+        if *x != i {
+            return false;
+        }
+    }
+    true
+}
+
+#[ensures(@result == (@v).len())]
+pub fn count_map(v: &[u32]) -> usize {
+    let mut n = 0;
+    #[invariant(count, @n == produced.len())]
+    #[invariant(bounded, produced.len() <= (@v).len())]
+    for _ in v.iter().map(|x| *x == 0) {
+        n += 1;
+    }
+    n
+}
@@ -0,0 +1,12 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+#[ensures((@^v).len() == (@*v).len())]
+#[ensures(forall<i : Int> 0 <= i && i < (@^v).len() ==> @((@^v)[i]) == 0)]
+pub fn zero_all(v: &mut [u32]) {
+    v.iter_mut().for_each(|x| *x = 0)
+}
+
+pub fn increment_all(v: &mut [u32]) {
+    v.iter_mut().for_each(|x| *x += 1)
+}
@@ -0,0 +1,23 @@
+extern crate creusot_contracts;
+
+use creusot_contracts::*;
+
+#[ensures(result == 0u32)]
+pub fn count_down(mut i: u32) -> u32 {
+    #[invariant(dummy, true)]
+    #[variant(i)]
+    while i > 0 {
+        i -= 1;
+    }
+    i
+}
+
+#[requires(0 <= @n)]
+pub fn count_up(n: i32) {
+    let mut i: i32 = 0;
+    #[invariant(bounded, 0 <= @i && @i <= @n)]
+    #[variant(@n - @i)]
+    while i < n {
+        i += 1;
+    }
+}
@@ -0,0 +1,37 @@
+extern crate creusot_contracts;
+
+use creusot_contracts::*;
+
+pub enum Shape {
+    Point(Option<u32>),
+    Line(u32),
+    Empty,
+}
+
+pub fn nested(shape: Shape) -> u32 {
+    match shape {
+        Shape::Point(Some(x @ 1..=5)) | Shape::Line(x) if x > 2 => x,
+        Shape::Point(Some(y)) => y,
+        _ => 0,
+    }
+}
+
+#[ensures(result == (x == 0u32 || b))]
+pub fn guard_on_tuple(x: u32, b: bool) -> bool {
+    match (x, b) {
+        (0, _) | (_, true) => true,
+        (n, false) if n > 10 => false,
+        _ => false,
+    }
+}
+
+pub fn resolve_in_arm(v: Option<&mut u32>, b: bool) -> u32 {
+    match v {
+        Some(x) if b => {
+            *x = 0;
+            1
+        }
+        Some(_) => 2,
+        None => 0,
+    }
+}
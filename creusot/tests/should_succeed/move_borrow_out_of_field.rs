@@ -0,0 +1,14 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+// Resolving `p` after `p.0` was moved out must not resolve the borrow held by `a`.
+#[ensures(^p.0 == 1u32)]
+pub fn move_out_of_tuple(p: (&mut u32, &mut u32)) {
+    let a = p.0;
+    *a = 1;
+}
+
+// Same as above, but the target is already initialized which produces a `DropAndReplace`.
+pub fn replace_from_tuple<'a>(p: (Vec<&'a mut u32>, Vec<&'a mut u32>), a: &mut Vec<&'a mut u32>) {
+    *a = p.1;
+}
@@ -0,0 +1,7 @@
+pub fn ping() {
+    pong()
+}
+
+pub fn pong() {
+    ping()
+}
@@ -0,0 +1,18 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+#[logic]
+fn length<T>(s: Seq<T>) -> Nat {
+    Nat::from_int(s.len())
+}
+
+#[ensures(@length(@v) == @result)]
+pub fn len(v: &Vec<u32>) -> usize {
+    v.len()
+}
+
+#[ensures(@result == @x)]
+#[ensures(@Nat::from_int(@result) == @x)]
+pub fn id(x: u64) -> u64 {
+    x
+}
@@ -0,0 +1,20 @@
+// OPTIMIZED
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+pub struct Point {
+    x: u32,
+    y: u32,
+}
+
+// Translated from optimized MIR before `uses_get` inlines it, which must not read its stolen
+// promoted MIR
+#[inline_body]
+fn get_x(p: &Point) -> u32 {
+    p.x
+}
+
+#[ensures(result == p.x)]
+pub fn uses_get(p: &Point) -> u32 {
+    get_x(p)
+}
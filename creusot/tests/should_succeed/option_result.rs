@@ -0,0 +1,27 @@
+extern crate creusot_contracts;
+
+use creusot_contracts::*;
+
+#[ensures(result == 0u32 || o == Some(result))]
+pub fn option_methods(o: Option<u32>) -> u32 {
+    if o.is_some() {
+        o.expect("checked")
+    } else {
+        o.unwrap_or(0)
+    }
+}
+
+#[requires(exists<t: u32> r == Ok(t))]
+#[ensures(Ok(result) == r)]
+pub fn result_unwrap(r: Result<u32, bool>) -> u32 {
+    r.unwrap()
+}
+
+#[ensures(result == (exists<e: bool> r == Err(e)))]
+pub fn result_is_err(r: Result<u32, bool>) -> bool {
+    !r.is_ok()
+}
+
+pub fn option_map(o: Option<u32>) -> Option<u32> {
+    o.map(|x: u32| x / 2)
+}
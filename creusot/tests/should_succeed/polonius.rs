@@ -0,0 +1,25 @@
+// POLONIUS
+extern crate creusot_contracts;
+
+use creusot_contracts::*;
+
+#[ensures(@(@^v)[(@v).len()] == (@v).len())]
+pub fn two_phase(v: &mut Vec<usize>) {
+    v.push(v.len());
+}
+
+#[ensures(^x == 1u32)]
+pub fn reborrow_ends(x: &mut u32) {
+    let y = &mut *x;
+    *y = 0;
+    *x = 1;
+}
+
+#[ensures(b ==> ^x == 1u32)]
+#[ensures(!b ==> ^x == *x)]
+pub fn conditional_borrow(x: &mut u32, b: bool) {
+    let y = &mut *x;
+    if b {
+        *y = 1;
+    }
+}
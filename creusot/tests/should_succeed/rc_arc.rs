@@ -0,0 +1,30 @@
+extern crate creusot_contracts;
+
+use creusot_contracts::*;
+use std::rc::Rc;
+use std::sync::Arc;
+
+pub enum Tree {
+    Leaf,
+    Node(Rc<Tree>, u32, Rc<Tree>),
+}
+
+#[ensures(result == 0u32 || *t != Tree::Leaf)]
+pub fn root(t: &Tree) -> u32 {
+    match t {
+        Tree::Leaf => 0,
+        Tree::Node(_, v, _) => *v,
+    }
+}
+
+#[ensures(result == *x)]
+pub fn shared(x: Rc<u32>) -> u32 {
+    let y = Rc::clone(&x);
+    *y
+}
+
+#[ensures(result == x)]
+pub fn arc(x: u32) -> u32 {
+    let a = Arc::new(x);
+    *a.clone()
+}
@@ -0,0 +1,16 @@
+// OPTIMIZED
+extern crate creusot_contracts;
+
+// Optimized MIR builds enums field by field, then selects the variant with `SetDiscriminant`
+pub enum E {
+    A(u32, bool),
+    B,
+}
+
+pub fn build_a(x: u32) -> E {
+    E::A(x, true)
+}
+
+pub fn build_b() -> E {
+    E::B
+}
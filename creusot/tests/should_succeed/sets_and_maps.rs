@@ -0,0 +1,44 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+// The distinct values of a sequence
+#[logic]
+#[variant(s.len())]
+fn elements(s: Seq<u32>) -> FSet<u32> {
+    pearlite! {
+        if s.len() == 0 { FSet::new() } else { elements(s.tail()).insert(s[0]) }
+    }
+}
+
+#[predicate]
+fn all_in(s: Seq<u32>, set: Set<u32>) -> bool {
+    pearlite! { forall<i : Int> 0 <= i && i < s.len() ==> set.contains(s[i]) }
+}
+
+#[requires(all_in(@v, Set::new().insert(0u32).insert(1u32)))]
+#[ensures(elements(@v).is_subset(FSet::new().insert(0u32).insert(1u32)))]
+pub fn binary(v: &Vec<u32>) {}
+
+// Counts the occurrences of each key
+#[logic]
+#[variant(s.len())]
+fn histogram(s: Seq<u32>) -> FMap<u32, Int> {
+    pearlite! {
+        if s.len() == 0 {
+            FMap::new()
+        } else {
+            match histogram(s.tail()).get(s[0]) {
+                Some(n) => histogram(s.tail()).insert(s[0], n + 1),
+                None => histogram(s.tail()).insert(s[0], 1),
+            }
+        }
+    }
+}
+
+#[requires((@v).len() > 0)]
+#[ensures(histogram(@v).contains((@v)[0]))]
+#[ensures(!histogram(@v).is_empty())]
+pub fn first_counted(v: &Vec<u32>) {}
+
+#[ensures(forall<k : u32> FMap::new().insert(k, 1u32).remove(k).get(k) == None)]
+pub fn remove_inserted() {}
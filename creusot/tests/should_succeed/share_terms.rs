@@ -0,0 +1,18 @@
+// SHARE_TERMS
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+#[logic]
+fn square(x: Int) -> Int {
+    x * x
+}
+
+#[requires(square(@x + 1) < 1000)]
+#[ensures(square(@x + 1) >= 0 && @result == square(@x + 1))]
+pub fn repeated(x: u32) -> u32 {
+    (x + 1) * (x + 1)
+}
+
+// `i` is bound by the quantifier, so its subterms stay in place
+#[ensures(forall<i : Int> square(i + 1) >= 0 && square(i + 1) == square(i + 1))]
+pub fn under_binder() {}
@@ -0,0 +1,29 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+#[ensures(result == (@a)[0])]
+pub fn first(a: [u32; 3]) -> u32 {
+    let [x, _, _] = a;
+    x
+}
+
+#[ensures(match result {
+    Some(x) => (@s).len() > 0 && x == (@s)[(@s).len() - 1],
+    None => (@s).len() == 0,
+})]
+pub fn last(s: &[u32]) -> Option<u32> {
+    match s {
+        [.., x] => Some(*x),
+        [] => None,
+    }
+}
+
+#[requires((@*s).len() >= 2)]
+#[ensures((@^s)[0] == 1u32 && (@^s)[(@*s).len() - 1] == 2u32)]
+#[ensures((@^s).len() == (@*s).len())]
+pub fn set_ends(s: &mut [u32]) {
+    if let [first, .., last] = s {
+        *first = 1;
+        *last = 2;
+    }
+}
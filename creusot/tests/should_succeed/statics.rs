@@ -0,0 +1,35 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+pub static LIMIT: u32 = 100;
+
+pub const OFFSET: i64 = -3;
+
+pub struct Point {
+    pub x: u32,
+    pub y: u32,
+}
+
+// Compound constants are abstract, nothing is known about their value
+pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+pub const PAIR: (u32, bool) = (1, true);
+
+#[ensures(result == 100u32)]
+pub fn limit() -> u32 {
+    LIMIT
+}
+
+#[requires(@x >= 0)]
+#[ensures(@result == @x - 3)]
+pub fn offset(x: i64) -> i64 {
+    x + OFFSET
+}
+
+pub fn origin_x() -> u32 {
+    ORIGIN.x
+}
+
+pub fn pair() -> bool {
+    PAIR.1
+}
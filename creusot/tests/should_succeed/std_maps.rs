@@ -0,0 +1,33 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+use std::collections::{BTreeMap, HashMap};
+
+#[ensures(@result == 2)]
+pub fn hashmap_insert_get() -> u32 {
+    let mut m: HashMap<u32, u32> = HashMap::new();
+    m.insert(1, 1);
+    m.insert(1, 2);
+    match m.get(&1) {
+        Some(v) => *v,
+        None => 0,
+    }
+}
+
+#[ensures(result)]
+pub fn hashmap_remove() -> bool {
+    let mut m: HashMap<u32, u32> = HashMap::new();
+    m.insert(1, 1);
+    let old = m.remove(&1);
+    proof_assert! { old == Some(1u32) }
+    m.get(&1).is_none()
+}
+
+#[ensures((@^m).get(@k) == Some(v))]
+pub fn btreemap_insert(m: &mut BTreeMap<u64, bool>, k: u64, v: bool) {
+    m.insert(k, v);
+}
+
+#[ensures(result == !(@*m).contains(@k))]
+pub fn btreemap_remove(m: &mut BTreeMap<u64, bool>, k: u64) -> bool {
+    m.remove(&k).is_none()
+}
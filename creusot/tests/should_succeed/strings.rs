@@ -0,0 +1,27 @@
+extern crate creusot_contracts;
+
+use creusot_contracts::*;
+
+#[ensures(@result == (@s).push('!'))]
+pub fn exclaim(mut s: String) -> String {
+    s.push('!');
+    s
+}
+
+#[ensures(@result == (@a).concat(@b))]
+pub fn join(a: String, b: &str) -> String {
+    a + b
+}
+
+#[ensures(result ==> (@s).len() == 0)]
+pub fn empty(s: &str) -> bool {
+    s.len() == 0
+}
+
+#[ensures((@result).len() == 2)]
+pub fn build() -> String {
+    let mut s = String::new();
+    s.push('a');
+    s.push('b');
+    s
+}
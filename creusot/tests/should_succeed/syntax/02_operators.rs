@@ -98,3 +98,13 @@ fn bool_eq(a: bool, b: bool) -> bool {
 
 #[ensures(old(x) == x)]
 fn old_test(x: bool) {}
+
+#[ensures(result == (a | b))]
+fn bool_or(a: bool, b: bool) -> bool {
+    a | b
+}
+
+#[ensures(result == (a ^ b))]
+fn bool_xor(a: bool, b: bool) -> bool {
+    a ^ b
+}
@@ -0,0 +1,40 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+pub struct Buffer;
+
+impl Buffer {
+    pub const CAPACITY: usize = 16;
+}
+
+#[ensures(@result == 16)]
+pub fn capacity() -> usize {
+    Buffer::CAPACITY
+}
+
+pub trait HasSize {
+    const SIZE: usize;
+
+    type Item;
+}
+
+// The impl refines the abstract constant of the trait by its value
+impl HasSize for u32 {
+    const SIZE: usize = 4;
+
+    type Item = u32;
+}
+
+#[ensures(@result == 4)]
+pub fn size_u32() -> usize {
+    <u32 as HasSize>::SIZE
+}
+
+// Generically, nothing is known about the constant
+pub fn size<T: HasSize>() -> usize {
+    T::SIZE
+}
+
+pub fn item<T: HasSize>(x: T::Item) -> T::Item {
+    x
+}
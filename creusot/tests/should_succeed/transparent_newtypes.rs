@@ -0,0 +1,37 @@
+// TRANSPARENT_NEWTYPES
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+#[repr(transparent)]
+pub struct Meters(u64);
+
+pub struct Wrapper<T> {
+    inner: T,
+}
+
+#[ensures(result.0 == x)]
+pub fn meters(x: u64) -> Meters {
+    Meters(x)
+}
+
+#[requires(@m.0 < 1000)]
+#[ensures(@result.0 == @m.0 + 1)]
+pub fn incr(mut m: Meters) -> Meters {
+    m.0 += 1;
+    m
+}
+
+#[ensures(result == w.inner)]
+pub fn unwrap<T>(w: Wrapper<T>) -> T {
+    w.inner
+}
+
+#[logic]
+fn value(m: Meters) -> Int {
+    pearlite! { match m { Meters(x) => @x } }
+}
+
+#[ensures(value(*m) == @result)]
+pub fn get(m: &Meters) -> u64 {
+    m.0
+}
@@ -0,0 +1,49 @@
+extern crate creusot_contracts;
+use creusot_contracts::*;
+
+#[invariant(ordered, @self.low <= @self.high)]
+pub struct Interval {
+    low: u32,
+    high: u32,
+}
+
+#[invariant(positive, match self { Shape::Square(s) => @s > 0, Shape::Empty => true })]
+pub enum Shape {
+    Square(u32),
+    Empty,
+}
+
+// The invariant of the argument is assumed, that of the result has to be proved
+#[ensures(@result.low == @i.low)]
+pub fn clamp_high(i: Interval, high: u32) -> Interval {
+    if high >= i.low {
+        Interval { low: i.low, high }
+    } else {
+        Interval { low: i.low, high: i.low }
+    }
+}
+
+#[ensures(@result <= @i.high)]
+pub fn low(i: &Interval) -> u32 {
+    i.low
+}
+
+// The invariant holds before and must be restored after the call
+pub fn widen(i: &mut Interval) {
+    if i.high < u32::MAX {
+        i.high += 1;
+    }
+}
+
+// Only the current value of the returned borrow is known to satisfy the invariant
+pub fn first(i: &mut Interval, j: &mut Interval) -> &mut Interval {
+    let _ = j;
+    i
+}
+
+pub fn area(s: Shape) -> u32 {
+    match s {
+        Shape::Square(s) => s,
+        Shape::Empty => 0,
+    }
+}
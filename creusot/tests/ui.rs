@@ -69,6 +69,34 @@ fn run_creusot(file: &Path, contracts: &str) -> Option<std::process::Command> {
         cmd.env("CREUSOT_UNBOUNDED", "1");
     }
 
+    if header_line.contains("POLONIUS") {
+        cmd.env("CREUSOT_POLONIUS", "1");
+    }
+
+    if header_line.contains("SHARE_TERMS") {
+        cmd.env("CREUSOT_SHARE_TERMS", "1");
+    }
+
+    if header_line.contains("BUILT") {
+        cmd.env("CREUSOT_MIR", "built");
+    }
+
+    if header_line.contains("OPTIMIZED") {
+        cmd.env("CREUSOT_MIR", "optimized");
+    }
+
+    if header_line.contains("TRANSPARENT_NEWTYPES") {
+        cmd.env("CREUSOT_TRANSPARENT_NEWTYPES", "1");
+    }
+
+    if header_line.contains("DIVERGING_PANICS") {
+        cmd.env("CREUSOT_DIVERGING_PANICS", "1");
+    }
+
+    if header_line.contains("INT_MODEL_BV") {
+        cmd.env("CREUSOT_INT_MODEL", "bv");
+    }
+
     if header_line.contains("UISKIP") {
         return None;
     }
@@ -106,10 +134,9 @@ where
     let mut test_count = 0;
     let mut test_failures = 0;
     let bless = std::env::args().any(|arg| arg == "--bless");
-    let filter = std::env::args().nth(1);
+    let filter = std::env::args().skip(1).find(|arg| !arg.starts_with("--"));
 
     for entry in glob::glob(s).expect("Failed to read glob pattern") {
-        test_count += 1;
         let entry = entry.unwrap();
 
         if let Some(ref filter) = filter {
@@ -121,6 +148,7 @@ where
             None => continue,
             Some(mut c) => c.output().unwrap(),
         };
+        test_count += 1;
 
         let stderr = entry.with_extension("stderr");
         let stdout = entry.with_extension("mlcfg");
@@ -413,6 +413,7 @@ ast_struct! {
         pub lt_token: Token![<],
         pub args: Punctuated<QuantArg, Token![,]>,
         pub gt_token: Token![>],
+        pub trigger: Option<QuantTrigger>,
 
         pub term: Box<Term>
     }
@@ -424,19 +425,29 @@ ast_struct! {
         pub lt_token: Token![<],
         pub args: Punctuated<QuantArg, Token![,]>,
         pub gt_token: Token![>],
+        pub trigger: Option<QuantTrigger>,
 
         pub term: Box<Term>
     }
 }
 
 ast_struct! {
+    /// A binder of a quantifier: `x : Int` or `(a, b) : (Int, Int)`.
     pub struct QuantArg {
-        pub ident: Ident,
+        pub pat: Pat,
         pub colon_token: Token![:],
         pub ty: Box<Type>,
     }
 }
 
+ast_struct! {
+    /// The patterns instantiating a quantifier: `forall<x : Int> [f(x)] f(x) > 0`.
+    pub struct QuantTrigger {
+        pub bracket_token: token::Bracket,
+        pub terms: Punctuated<Term, Token![,]>,
+    }
+}
+
 ast_struct! {
     pub struct TermAbsurd {
         pub absurd_token: kw::absurd
@@ -1320,10 +1331,11 @@ pub(crate) mod parsing {
             }
 
             let gt_token: Token![>] = input.parse()?;
+            let trigger = if input.peek(token::Bracket) { Some(input.parse()?) } else { None };
 
             let term = input.parse()?;
 
-            Ok(TermForall { forall_token, lt_token, args, gt_token, term })
+            Ok(TermForall { forall_token, lt_token, args, gt_token, trigger, term })
         }
     }
 
@@ -1345,19 +1357,32 @@ pub(crate) mod parsing {
             }
 
             let gt_token: Token![>] = input.parse()?;
+            let trigger = if input.peek(token::Bracket) { Some(input.parse()?) } else { None };
 
             let term = input.parse()?;
 
-            Ok(TermExists { exists_token, lt_token, args, gt_token, term })
+            Ok(TermExists { exists_token, lt_token, args, gt_token, trigger, term })
+        }
+    }
+
+    impl Parse for QuantTrigger {
+        fn parse(input: ParseStream) -> Result<Self> {
+            let content;
+            let bracket_token = bracketed!(content in input);
+            let terms = content.parse_terminated(Term::parse)?;
+            if terms.is_empty() {
+                return Err(content.error("expected at least one trigger pattern"));
+            }
+            Ok(QuantTrigger { bracket_token, terms })
         }
     }
 
     impl Parse for QuantArg {
         fn parse(input: ParseStream) -> Result<Self> {
-            let ident = input.parse()?;
+            let pat = input.parse()?;
             let colon_token = input.parse()?;
             let ty = input.parse()?;
-            Ok(QuantArg { ident, colon_token, ty })
+            Ok(QuantArg { pat, colon_token, ty })
         }
     }
 
@@ -1771,6 +1796,7 @@ pub(crate) mod printing {
                 input.to_tokens(tokens);
             }
             self.gt_token.to_tokens(tokens);
+            self.trigger.to_tokens(tokens);
             self.term.to_tokens(tokens);
         }
     }
@@ -1783,18 +1809,27 @@ pub(crate) mod printing {
                 input.to_tokens(tokens);
             }
             self.gt_token.to_tokens(tokens);
+            self.trigger.to_tokens(tokens);
             self.term.to_tokens(tokens);
         }
     }
 
     impl ToTokens for QuantArg {
         fn to_tokens(&self, tokens: &mut TokenStream) {
-            self.ident.to_tokens(tokens);
+            self.pat.to_tokens(tokens);
             self.colon_token.to_tokens(tokens);
             self.ty.to_tokens(tokens);
         }
     }
 
+    impl ToTokens for QuantTrigger {
+        fn to_tokens(&self, tokens: &mut TokenStream) {
+            self.bracket_token.surround(tokens, |tokens| {
+                self.terms.to_tokens(tokens);
+            });
+        }
+    }
+
     impl ToTokens for TermAbsurd {
         fn to_tokens(&self, tokens: &mut TokenStream) {
             self.absurd_token.to_tokens(tokens);
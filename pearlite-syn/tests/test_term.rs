@@ -87,8 +87,16 @@ fn test_forall() {
         lt_token: Lt,
         args: [
             QuantArg {
-                ident: Ident(
-                    x,
+                pat: Ident(
+                    PatIdent {
+                        attrs: [],
+                        by_ref: None,
+                        mutability: None,
+                        ident: Ident(
+                            x,
+                        ),
+                        subpat: None,
+                    },
                 ),
                 colon_token: Colon,
                 ty: Path(
@@ -110,6 +118,7 @@ fn test_forall() {
             },
         ],
         gt_token: Gt,
+        trigger: None,
         term: TermLit {
             lit: Bool(
                 LitBool {
@@ -129,8 +138,16 @@ fn test_exists() {
         lt_token: Lt,
         args: [
             QuantArg {
-                ident: Ident(
-                    x,
+                pat: Ident(
+                    PatIdent {
+                        attrs: [],
+                        by_ref: None,
+                        mutability: None,
+                        ident: Ident(
+                            x,
+                        ),
+                        subpat: None,
+                    },
                 ),
                 colon_token: Colon,
                 ty: Path(
@@ -152,6 +169,198 @@ fn test_exists() {
             },
         ],
         gt_token: Gt,
+        trigger: None,
+        term: TermLit {
+            lit: Bool(
+                LitBool {
+                    value: true,
+                },
+            ),
+        },
+    }
+    "###);
+}
+
+#[test]
+fn test_forall_trigger() {
+    snapshot!(quote!(forall<x : u32> [f(x)] true) as Term, @r###"
+    TermForall {
+        forall_token: Keyword [forall],
+        lt_token: Lt,
+        args: [
+            QuantArg {
+                pat: Ident(
+                    PatIdent {
+                        attrs: [],
+                        by_ref: None,
+                        mutability: None,
+                        ident: Ident(
+                            x,
+                        ),
+                        subpat: None,
+                    },
+                ),
+                colon_token: Colon,
+                ty: Path(
+                    TypePath {
+                        qself: None,
+                        path: Path {
+                            leading_colon: None,
+                            segments: [
+                                PathSegment {
+                                    ident: Ident(
+                                        u32,
+                                    ),
+                                    arguments: None,
+                                },
+                            ],
+                        },
+                    },
+                ),
+            },
+        ],
+        gt_token: Gt,
+        trigger: Some(
+            QuantTrigger {
+                bracket_token: Bracket,
+                terms: [
+                    TermCall {
+                        func: TermPath {
+                            inner: ExprPath {
+                                attrs: [],
+                                qself: None,
+                                path: Path {
+                                    leading_colon: None,
+                                    segments: [
+                                        PathSegment {
+                                            ident: Ident(
+                                                f,
+                                            ),
+                                            arguments: None,
+                                        },
+                                    ],
+                                },
+                            },
+                        },
+                        paren_token: Paren,
+                        args: [
+                            TermPath {
+                                inner: ExprPath {
+                                    attrs: [],
+                                    qself: None,
+                                    path: Path {
+                                        leading_colon: None,
+                                        segments: [
+                                            PathSegment {
+                                                ident: Ident(
+                                                    x,
+                                                ),
+                                                arguments: None,
+                                            },
+                                        ],
+                                    },
+                                },
+                            },
+                        ],
+                    },
+                ],
+            },
+        ),
+        term: TermLit {
+            lit: Bool(
+                LitBool {
+                    value: true,
+                },
+            ),
+        },
+    }
+    "###);
+}
+
+#[test]
+fn test_exists_tuple() {
+    snapshot!(quote!(exists<(a, b) : (u32, u32)> true) as Term, @r###"
+    TermExists {
+        exists_token: Keyword [exists],
+        lt_token: Lt,
+        args: [
+            QuantArg {
+                pat: Tuple(
+                    PatTuple {
+                        attrs: [],
+                        paren_token: Paren,
+                        elems: [
+                            Ident(
+                                PatIdent {
+                                    attrs: [],
+                                    by_ref: None,
+                                    mutability: None,
+                                    ident: Ident(
+                                        a,
+                                    ),
+                                    subpat: None,
+                                },
+                            ),
+                            Comma,
+                            Ident(
+                                PatIdent {
+                                    attrs: [],
+                                    by_ref: None,
+                                    mutability: None,
+                                    ident: Ident(
+                                        b,
+                                    ),
+                                    subpat: None,
+                                },
+                            ),
+                        ],
+                    },
+                ),
+                colon_token: Colon,
+                ty: Tuple(
+                    TypeTuple {
+                        paren_token: Paren,
+                        elems: [
+                            Path(
+                                TypePath {
+                                    qself: None,
+                                    path: Path {
+                                        leading_colon: None,
+                                        segments: [
+                                            PathSegment {
+                                                ident: Ident(
+                                                    u32,
+                                                ),
+                                                arguments: None,
+                                            },
+                                        ],
+                                    },
+                                },
+                            ),
+                            Comma,
+                            Path(
+                                TypePath {
+                                    qself: None,
+                                    path: Path {
+                                        leading_colon: None,
+                                        segments: [
+                                            PathSegment {
+                                                ident: Ident(
+                                                    u32,
+                                                ),
+                                                arguments: None,
+                                            },
+                                        ],
+                                    },
+                                },
+                            ),
+                        ],
+                    },
+                ),
+            },
+        ],
+        gt_token: Gt,
+        trigger: None,
         term: TermLit {
             lit: Bool(
                 LitBool {
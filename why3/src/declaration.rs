@@ -131,7 +131,7 @@ impl Contract {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum Attribute {
     Attr(String),
@@ -166,6 +166,55 @@ pub struct CfgFunction {
     pub blocks: BTreeMap<BlockId, Block>,
 }
 
+/// Rough indicators of how hard the verification conditions of a function will be
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Metrics {
+    /// Expression nodes in the contract and the body
+    pub nodes: usize,
+    /// Deepest nesting of quantifiers
+    pub quantifier_depth: usize,
+    /// Postconditions, variants, assertions and loop invariants
+    pub obligations: usize,
+}
+
+impl CfgFunction {
+    pub fn metrics(&self) -> Metrics {
+        use crate::mlcfg::{Statement, Terminator};
+        let mut metrics = Metrics::default();
+        let mut add = |e: &Exp| {
+            metrics.nodes += e.size();
+            metrics.quantifier_depth = metrics.quantifier_depth.max(e.quantifier_depth());
+        };
+
+        let contract = &self.sig.contract;
+        contract
+            .requires
+            .iter()
+            .chain(&contract.ensures)
+            .chain(&contract.variant)
+            .for_each(&mut add);
+
+        let mut obligations = contract.ensures.len() + contract.variant.len();
+        for block in std::iter::once(&self.entry).chain(self.blocks.values()) {
+            for stmt in &block.statements {
+                match stmt {
+                    Statement::Assign { rhs: e, .. } | Statement::Assume(e) => add(e),
+                    Statement::Assert(e) | Statement::Invariant(_, e) => {
+                        obligations += 1;
+                        add(e)
+                    }
+                }
+            }
+            if let Terminator::Switch(discr, _) = &block.terminator {
+                add(discr)
+            }
+        }
+
+        metrics.obligations = obligations;
+        metrics
+    }
+}
+
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub struct Predicate {
@@ -6,7 +6,7 @@ use indexmap::IndexSet;
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum BinOp {
     And,
@@ -45,14 +45,14 @@ impl BinOp {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum UnOp {
     Not,
     Neg,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum Purity {
     Logic,
@@ -60,7 +60,7 @@ pub enum Purity {
 }
 
 // TODO: Should we introduce an 'ExprKind' struct which wraps `Exp` with attributes?
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum Exp {
     Any(Type),
@@ -90,10 +90,16 @@ pub enum Exp {
     Old(Box<Exp>),
     Absurd,
     Impl(Box<Exp>, Box<Exp>),
-    Forall(Vec<(Ident, Type)>, Box<Exp>),
-    Exists(Vec<(Ident, Type)>, Box<Exp>),
+    Forall(Vec<(Ident, Type)>, Vec<Trigger>, Box<Exp>),
+    Exists(Vec<(Ident, Type)>, Vec<Trigger>, Box<Exp>),
 }
 
+/// A trigger of a quantifier: the quantifier is instantiated by provers once terms matching all
+/// of its patterns are known.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct Trigger(pub Vec<Exp>);
+
 pub trait ExpMutVisitor: Sized {
     fn visit_mut(&mut self, exp: &mut Exp) {
         super_visit_mut(self, exp)
@@ -148,8 +154,10 @@ pub fn super_visit_mut<T: ExpMutVisitor>(f: &mut T, exp: &mut Exp) {
             f.visit_mut(l);
             f.visit_mut(r)
         }
-        Exp::Forall(_, e) => f.visit_mut(e),
-        Exp::Exists(_, e) => f.visit_mut(e),
+        Exp::Forall(_, trs, e) | Exp::Exists(_, trs, e) => {
+            trs.iter_mut().flat_map(|tr| &mut tr.0).for_each(|p| f.visit_mut(p));
+            f.visit_mut(e)
+        }
         Exp::Attr(_, e) => f.visit_mut(e),
         Exp::Ghost(e) => f.visit_mut(e),
     }
@@ -209,8 +217,10 @@ pub fn super_visit<T: ExpVisitor>(f: &mut T, exp: &Exp) {
             f.visit(l);
             f.visit(r)
         }
-        Exp::Forall(_, e) => f.visit(e),
-        Exp::Exists(_, e) => f.visit(e),
+        Exp::Forall(_, trs, e) | Exp::Exists(_, trs, e) => {
+            trs.iter().flat_map(|tr| &tr.0).for_each(|p| f.visit(p));
+            f.visit(e)
+        }
         Exp::Attr(_, e) => f.visit(e),
         Exp::Ghost(e) => f.visit(e),
     }
@@ -427,6 +437,7 @@ impl Precedence {
         match self {
             Infix1 => None,
             Infix2 | Infix3 => Some(AssocDir::Left),
+            Impl => Some(AssocDir::Right),
             Conj => Some(AssocDir::Right),
             Disj => Some(AssocDir::Right),
             App => Some(AssocDir::Left),
@@ -459,6 +470,9 @@ impl Exp {
             Exp::Match(_, _) => Atom,
             Exp::IfThenElse(_, _, _) => IfLet,
             Exp::BorrowMut(_) => App,
+            // Why3 lexes `-1` as a prefix negation
+            Exp::Const(Constant::Int(i, None)) if *i < 0 => Prefix,
+            Exp::Const(Constant::Float(f, None)) if f.starts_with('-') => Prefix,
             Exp::Const(_) => Atom,
             Exp::UnaryOp(UnOp::Neg, _) => Prefix,
             Exp::UnaryOp(UnOp::Not, _) => Not,
@@ -466,8 +480,8 @@ impl Exp {
             Exp::Call(_, _) => App,
             // Exp::Verbatim(_) => Any,
             Exp::Impl(_, _) => Impl,
-            Exp::Forall(_, _) => IfLet,
-            Exp::Exists(_, _) => IfLet,
+            Exp::Forall(_, _, _) => IfLet,
+            Exp::Exists(_, _, _) => IfLet,
             Exp::Ascribe(_, _) => Cast,
             Exp::Absurd => Atom,
             Exp::Pure(_) => Atom,
@@ -480,6 +494,48 @@ impl Exp {
         }
     }
 
+    /// Number of nodes in the expression
+    pub fn size(&self) -> usize {
+        struct Size(usize);
+
+        impl ExpVisitor for Size {
+            fn visit(&mut self, exp: &Exp) {
+                self.0 += 1;
+                super_visit(self, exp)
+            }
+        }
+
+        let mut size = Size(0);
+        size.visit(self);
+        size.0
+    }
+
+    /// Maximum number of nested `forall` and `exists`
+    pub fn quantifier_depth(&self) -> usize {
+        struct Depth {
+            current: usize,
+            max: usize,
+        }
+
+        impl ExpVisitor for Depth {
+            fn visit(&mut self, exp: &Exp) {
+                match exp {
+                    Exp::Forall(_, _, _) | Exp::Exists(_, _, _) => {
+                        self.current += 1;
+                        self.max = self.max.max(self.current);
+                        super_visit(self, exp);
+                        self.current -= 1;
+                    }
+                    _ => super_visit(self, exp),
+                }
+            }
+        }
+
+        let mut depth = Depth { current: 0, max: 0 };
+        depth.visit(self);
+        depth.max
+    }
+
     pub fn fvs(&self) -> IndexSet<Ident> {
         struct Fvs {
             fvs: IndexSet<Ident>,
@@ -498,18 +554,9 @@ impl Exp {
                         self.visit(arg);
                         self.fvs.extend(fvs);
                     }
-                    Exp::Forall(bnds, exp) => {
-                        let fvs = std::mem::take(&mut self.fvs);
-                        self.visit(exp);
-
-                        bnds.iter().for_each(|(l, _)| {
-                            self.fvs.remove(l);
-                        });
-                        self.fvs.extend(fvs);
-                    }
-                    Exp::Exists(bnds, exp) => {
+                    Exp::Forall(bnds, _, _) | Exp::Exists(bnds, _, _) => {
                         let fvs = std::mem::take(&mut self.fvs);
-                        self.visit(exp);
+                        super_visit(self, exp);
 
                         bnds.iter().for_each(|(l, _)| {
                             self.fvs.remove(l);
@@ -589,21 +636,13 @@ impl Exp {
                             s.visit_mut(br);
                         }
                     }
-                    Exp::Forall(binders, exp) => {
-                        let mut subst = self.clone();
-                        binders.iter().for_each(|k| {
-                            subst.remove(&k.0);
-                        });
-                        let mut s = &subst;
-                        s.visit_mut(exp);
-                    }
-                    Exp::Exists(binders, exp) => {
+                    Exp::Forall(binders, _, _) | Exp::Exists(binders, _, _) => {
                         let mut subst = self.clone();
                         binders.iter().for_each(|k| {
                             subst.remove(&k.0);
                         });
                         let mut s = &subst;
-                        s.visit_mut(exp);
+                        super_visit_mut(&mut s, exp);
                     }
                     _ => super_visit_mut(self, exp),
                 }
@@ -613,15 +652,155 @@ impl Exp {
     }
 }
 
-#[derive(Debug, Clone)]
+impl Exp {
+    /// Bind the subterms of `self` which occur more than once to variables, so that provers only see
+    /// them once: `f (g x) = f (g x)` becomes `let shared'0 = f (g x) in shared'0 = shared'0`.
+    ///
+    /// Only subterms with at least `min_size` nodes which are not below a binder are shared, which
+    /// guarantees their free variables mean the same thing at the top of the expression.
+    /// This must only be applied to logical terms.
+    pub fn share_common_subterms(&mut self, min_size: usize) {
+        // Keep attributes such as spans on the outside
+        if let Exp::Attr(_, inner) = self {
+            return inner.share_common_subterms(min_size);
+        }
+
+        // Computes the size of `e` and whether it mentions a shared variable bottom-up, counting
+        // the occurrences of the candidate subterms along the way. Subterms below a binder or an
+        // `old` are still measured but never counted: moving them out would change their meaning.
+        fn count<'a>(
+            e: &'a Exp,
+            shareable: bool,
+            min_size: usize,
+            counts: &mut HashMap<&'a Exp, (usize, usize)>,
+        ) -> (usize, bool) {
+            let mut size = 1;
+            let mut mentions_shared = matches!(e, Exp::Var(v, _) if v.0.starts_with("shared'"));
+
+            for (i, c) in e.children().enumerate() {
+                let shareable = shareable
+                    && match e {
+                        // Only the bound term and the scrutinee are outside of the binders
+                        Exp::Let { .. } | Exp::Match(_, _) => i == 0,
+                        Exp::Abs(_, _)
+                        | Exp::Forall(_, _, _)
+                        | Exp::Exists(_, _, _)
+                        | Exp::Old(_) => false,
+                        _ => true,
+                    };
+                let (s, m) = count(c, shareable, min_size, counts);
+                size += s;
+                mentions_shared |= m;
+            }
+
+            if shareable && size >= min_size && !mentions_shared {
+                counts.entry(e).or_insert((0, size)).0 += 1;
+            }
+            (size, mentions_shared)
+        }
+
+        struct Replace<'a>(&'a Exp, &'a Exp);
+
+        impl<'a> ExpMutVisitor for Replace<'a> {
+            fn visit_mut(&mut self, exp: &mut Exp) {
+                if exp == self.0 {
+                    *exp = self.1.clone();
+                    return;
+                }
+                match exp {
+                    Exp::Let { arg, .. } => self.visit_mut(arg),
+                    Exp::Match(scrut, _) => self.visit_mut(scrut),
+                    Exp::Abs(_, _)
+                    | Exp::Forall(_, _, _)
+                    | Exp::Exists(_, _, _)
+                    | Exp::Old(_) => {}
+                    _ => super_visit_mut(self, exp),
+                }
+            }
+        }
+
+        let mut bindings = Vec::new();
+        loop {
+            let mut counts = HashMap::new();
+            count(self, true, min_size, &mut counts);
+            let shared = counts
+                .into_iter()
+                .filter(|(_, (n, _))| *n > 1)
+                .max_by_key(|(_, (_, size))| *size)
+                .map(|(e, _)| e.clone());
+
+            let shared = match shared {
+                Some(shared) => shared,
+                None => break,
+            };
+            let id = Ident(format!("shared'{}", bindings.len()));
+            Replace(&shared, &Exp::pure_var(id.clone())).visit_mut(self);
+            bindings.push((id, shared));
+        }
+
+        // Terms mentioning a shared variable are never shared themselves and the bound terms are
+        // left untouched, so the bindings are independent of each other and their order is
+        // irrelevant
+        for (id, shared) in bindings {
+            let body = std::mem::replace(self, Exp::Absurd);
+            *self = Exp::Let { pattern: Pattern::VarP(id), arg: box shared, body: box body };
+        }
+    }
+
+    fn children(&self) -> impl Iterator<Item = &Exp> {
+        let mut children = Vec::new();
+        match self {
+            Exp::Current(e)
+            | Exp::Final(e)
+            | Exp::RecField { record: e, .. }
+            | Exp::BorrowMut(e)
+            | Exp::UnaryOp(_, e)
+            | Exp::Attr(_, e)
+            | Exp::Ghost(e)
+            | Exp::Abs(_, e)
+            | Exp::Ascribe(e, _)
+            | Exp::Pure(e)
+            | Exp::Old(e) => children.push(&**e),
+            Exp::Forall(_, trs, e) | Exp::Exists(_, trs, e) => {
+                children.extend(trs.iter().flat_map(|tr| &tr.0));
+                children.push(&**e)
+            }
+            Exp::Let { arg: l, body: r, .. }
+            | Exp::RecUp { record: l, val: r, .. }
+            | Exp::BinaryOp(_, l, r)
+            | Exp::Impl(l, r) => children.extend([&**l, &**r]),
+            Exp::Tuple(es) | Exp::Constructor { args: es, .. } => children.extend(es),
+            Exp::Call(f, args) => {
+                children.push(&**f);
+                children.extend(args)
+            }
+            Exp::Match(scrut, arms) => {
+                children.push(&**scrut);
+                children.extend(arms.iter().map(|(_, e)| e))
+            }
+            Exp::IfThenElse(c, t, e) => children.extend([&**c, &**t, &**e]),
+            Exp::Any(_)
+            | Exp::Var(_, _)
+            | Exp::QVar(_, _)
+            | Exp::Const(_)
+            | Exp::Verbatim(_)
+            | Exp::Absurd => {}
+        }
+        children.into_iter()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum Constant {
     Int(i128, Option<Type>),
     Uint(u128, Option<Type>),
-    // Float(f64),
+    // Kept as the text of an exact literal, see `Constant::float`
+    Float(String, Option<Type>),
     String(String),
-    Other(String),
+    Char(char),
     Bool(bool),
+    Unit,
 }
 impl Constant {
     pub fn const_true() -> Self {
@@ -630,9 +809,31 @@ impl Constant {
     pub fn const_false() -> Self {
         Constant::Bool(false)
     }
+
+    /// A finite floating point constant, written as a hexadecimal literal so that Why3 reads back
+    /// exactly the same value
+    pub fn float(value: f64, ty: Option<Type>) -> Self {
+        assert!(value.is_finite(), "non-finite float constant {}", value);
+        let bits = value.to_bits();
+        let sign = if value.is_sign_negative() { "-" } else { "" };
+        let exp = ((bits >> 52) & 0x7ff) as i64;
+        let mantissa = bits & ((1 << 52) - 1);
+
+        let lit = if exp == 0 && mantissa == 0 {
+            format!("{}0.0", sign)
+        } else {
+            // Subnormals have no implicit leading one
+            let (lead, exp) = if exp == 0 { (0, -1022) } else { (1, exp - 1023) };
+            let digits = format!("{:013x}", mantissa);
+            let digits = digits.trim_end_matches('0');
+            let digits = if digits.is_empty() { "0" } else { digits };
+            format!("{}0x{}.{}p{}", sign, lead, digits, exp)
+        };
+        Constant::Float(lit, ty)
+    }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum Pattern {
     Wildcard,
@@ -674,3 +875,40 @@ impl Pattern {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Print;
+
+    #[test]
+    fn size_and_quantifier_depth() {
+        let x = || Exp::pure_var("x".into());
+        let inner = Exp::Exists(vec![("x".into(), Type::Integer)], vec![], box x().eq(x()));
+        let e =
+            Exp::Forall(vec![("y".into(), Type::Integer)], vec![], box inner.and(Exp::mk_true()));
+
+        assert_eq!(e.size(), 5);
+        assert_eq!(e.quantifier_depth(), 2);
+    }
+
+    #[test]
+    fn share_common_subterms() {
+        let app = |f: &str, arg| Exp::pure_var(f.into()).app_to(arg);
+        let g_x = || app("g", Exp::pure_var("x".into()));
+
+        let mut e = app("f", g_x()).eq(app("f", g_x()));
+        e.share_common_subterms(2);
+        assert_eq!(e.display().to_string(), "let shared'0 = f (g x) in shared'0 = shared'0");
+
+        // `x` is bound by the quantifier, so `g x` cannot be moved out of it
+        let mut e = Exp::Forall(vec![("x".into(), Type::Integer)], vec![], box g_x().eq(g_x()));
+        e.share_common_subterms(2);
+        assert_eq!(e.display().to_string(), "forall x : int . g x = g x");
+
+        // `old (g x)` and `g x` denote different values, so nothing is hoisted out of `old`
+        let mut e = Exp::Old(box g_x()).eq(Exp::Old(box g_x()));
+        e.share_common_subterms(2);
+        assert_eq!(e.display().to_string(), "let shared'0 = old(g x) in shared'0 = shared'0");
+    }
+}
@@ -1,4 +1,4 @@
-#![feature(box_syntax, box_patterns)]
+#![feature(box_syntax, box_patterns, once_cell)]
 pub mod declaration;
 pub mod exp;
 pub mod mlcfg;
@@ -6,6 +6,7 @@ use crate::{
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
 
+pub mod parser;
 pub mod printer;
 
 #[derive(Debug, Clone)]
@@ -37,6 +38,14 @@ impl Terminator {
         }
     }
 
+    pub fn targets(&self) -> Vec<BlockId> {
+        match self {
+            Self::Goto(id) => vec![*id],
+            Self::Switch(_, brs) => brs.iter().flat_map(|(_, t)| t.targets()).collect(),
+            _ => Vec::new(),
+        }
+    }
+
     pub fn is_goto(&self) -> bool {
         matches!(self, Self::Goto(..))
     }
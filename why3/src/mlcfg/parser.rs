@@ -0,0 +1,1158 @@
+// Reads the output of the printer back into the `mlcfg` data structures. The grammar is the
+// subset of Why3 produced by `printer.rs` and not the whole of Why3: in particular expressions are
+// expected to be parenthesized the way the printer parenthesizes them, and `Exp::Verbatim` cannot
+// be recovered.
+
+use std::{collections::BTreeMap, fmt};
+
+use super::*;
+use crate::{
+    declaration::*,
+    exp::{BinOp, Constant, Precedence, Purity, Trigger, UnOp},
+    name::RESERVED,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+type Result<T> = std::result::Result<T, ParseError>;
+
+/// Parse a sequence of modules, such as the contents of a `.mlcfg` file
+pub fn parse_modules(input: &str) -> Result<Vec<Module>> {
+    let mut parser = Parser::new(input)?;
+    let mut modules = Vec::new();
+    while parser.peek() != &Tok::Eof {
+        parser.expect_kw("module")?;
+        modules.push(parser.module()?);
+    }
+    Ok(modules)
+}
+
+/// Parse a single expression
+pub fn parse_exp(input: &str) -> Result<Exp> {
+    let mut parser = Parser::new(input)?;
+    let exp = parser.exp()?;
+    parser.expect(&Tok::Eof)?;
+    Ok(exp)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    // Dotted names like `Type.Option`, which the printer emits without spaces
+    Name(Vec<String>),
+    Int(String),
+    // Decimal or hexadecimal float literals like `1.5` or `0x1.8p0`
+    Real(String),
+    Str(String),
+    TyVar(String),
+    Attr(Attribute),
+    Sym(&'static str),
+    Eof,
+}
+
+impl fmt::Display for Tok {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tok::Name(segs) => write!(f, "`{}`", segs.join(".")),
+            Tok::Int(i) | Tok::Real(i) => write!(f, "`{}`", i),
+            Tok::Str(s) => write!(f, "\"{}\"", s),
+            Tok::TyVar(v) => write!(f, "`'{}`", v),
+            Tok::Attr(_) => write!(f, "attribute"),
+            Tok::Sym(s) => write!(f, "`{}`", s),
+            Tok::Eof => write!(f, "end of input"),
+        }
+    }
+}
+
+struct Token {
+    tok: Tok,
+    line: usize,
+    spaced: bool,
+}
+
+const SYMBOLS: &[&str] = &[
+    "->", "<-", "<>", "<=", ">=", "&&", "||", "=", "<", ">", "+", "-", "*", "/", "%", "^", "|", ":",
+    ";", ",", ".", "(", ")", "{", "}", "[", "]",
+];
+
+fn is_ident_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_'
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '\''
+}
+
+fn starts_digit(chars: &[char]) -> bool {
+    chars.first().map_or(false, char::is_ascii_digit)
+}
+
+// Length of the number at the start of `chars`, which is either an integer or a float literal
+fn number_len(chars: &[char]) -> usize {
+    let digits = |from: usize, hex: bool| {
+        chars[from..]
+            .iter()
+            .take_while(|c| if hex { c.is_ascii_hexdigit() } else { c.is_ascii_digit() })
+            .count()
+    };
+    let mut len = if chars[0] == '-' { 1 } else { 0 };
+
+    if chars[len..].starts_with(&['0', 'x']) {
+        len += 2;
+        len += digits(len, true);
+        if chars.get(len) == Some(&'.') {
+            len += 1 + digits(len + 1, true);
+        }
+        if chars.get(len) == Some(&'p') {
+            len += 1;
+            if chars.get(len) == Some(&'-') {
+                len += 1;
+            }
+            len += digits(len, false);
+        }
+    } else {
+        len += digits(len, false);
+        if chars.get(len) == Some(&'.') && starts_digit(&chars[len + 1..]) {
+            len += 1 + digits(len + 1, false);
+        }
+    }
+    len
+}
+
+fn lex(input: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut toks = Vec::new();
+    let mut line = 1;
+    let mut i = 0;
+
+    let error = |line, message: &str| Err(ParseError { line, message: message.into() });
+
+    while i < chars.len() {
+        let c = chars[i];
+        let rest = &chars[i..];
+        let spaced = i > 0 && chars[i - 1].is_whitespace();
+
+        if c == '\n' {
+            line += 1;
+            i += 1;
+        } else if c.is_whitespace() {
+            i += 1;
+        } else if rest.starts_with(&['(', '*']) {
+            let mut depth = 0;
+            loop {
+                match &chars[i..] {
+                    ['(', '*', ..] => {
+                        depth += 1;
+                        i += 2
+                    }
+                    ['*', ')', ..] => {
+                        depth -= 1;
+                        i += 2;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                    ['\n', ..] => {
+                        line += 1;
+                        i += 1
+                    }
+                    [_, ..] => i += 1,
+                    [] => return error(line, "unterminated comment"),
+                }
+            }
+        } else if rest.starts_with(&['[', '@']) || rest.starts_with(&['[', '#']) {
+            let end = match rest.iter().position(|c| *c == ']') {
+                Some(end) => end,
+                None => return error(line, "unterminated attribute"),
+            };
+            let text: String = rest[2..end].iter().collect();
+            let attr = if c == '[' && rest[1] == '@' {
+                Attribute::Attr(text)
+            } else {
+                match parse_span(&text) {
+                    Some(span) => span,
+                    None => return error(line, "invalid span attribute"),
+                }
+            };
+            toks.push(Token { tok: Tok::Attr(attr), line, spaced });
+            i += end + 1;
+        } else if c == '"' {
+            let end = match rest[1..].iter().position(|c| *c == '"') {
+                Some(end) => end + 1,
+                None => return error(line, "unterminated string"),
+            };
+            toks.push(Token { tok: Tok::Str(rest[1..end].iter().collect()), line, spaced });
+            i += end + 1;
+        } else if c.is_ascii_digit() || (c == '-' && starts_digit(&rest[1..])) {
+            let len = number_len(rest);
+            let text: String = rest[..len].iter().collect();
+            let tok = if text.contains(&['.', 'p'][..]) { Tok::Real(text) } else { Tok::Int(text) };
+            toks.push(Token { tok, line, spaced });
+            i += len;
+        } else if c == '\'' && rest.get(1).map_or(false, |c| is_ident_start(*c)) {
+            let len = 1 + rest[1..].iter().take_while(|c| is_ident_char(**c)).count();
+            toks.push(Token { tok: Tok::TyVar(rest[1..len].iter().collect()), line, spaced });
+            i += len;
+        } else if is_ident_start(c) {
+            let mut segs = Vec::new();
+            loop {
+                let len = chars[i..].iter().take_while(|c| is_ident_char(**c)).count();
+                segs.push(chars[i..i + len].iter().collect());
+                i += len;
+                match chars.get(i..i + 2) {
+                    Some(['.', c]) if is_ident_char(*c) => i += 1,
+                    // Operators used as names, like `Seq.(++)`
+                    Some(['.', '(']) => {
+                        let len = match chars[i..].iter().position(|c| *c == ')') {
+                            Some(len) => len,
+                            None => return error(line, "unterminated operator name"),
+                        };
+                        segs.push(chars[i + 1..i + len + 1].iter().collect());
+                        i += len + 1;
+                        break;
+                    }
+                    _ => break,
+                }
+            }
+            toks.push(Token { tok: Tok::Name(segs), line, spaced });
+        } else if let Some(sym) = SYMBOLS.iter().copied().find(|sym| {
+            let sym: Vec<char> = sym.chars().collect();
+            rest.starts_with(&sym)
+        }) {
+            toks.push(Token { tok: Tok::Sym(sym), line, spaced });
+            i += sym.len();
+        } else {
+            return error(line, &format!("unexpected character `{}`", c));
+        }
+    }
+
+    toks.push(Token { tok: Tok::Eof, line, spaced: true });
+    Ok(toks)
+}
+
+// The contents of `[#"file" line start end]`
+fn parse_span(text: &str) -> Option<Attribute> {
+    let text = text.strip_prefix('"')?;
+    let (file, rest) = text.split_once('"')?;
+    let mut nums = rest.split_whitespace().map(str::parse);
+    let mut num = || nums.next()?.ok();
+    let span = Attribute::Span(file.into(), num()?, num()?, num()?);
+    if nums.next().is_some() {
+        return None;
+    }
+    Some(span)
+}
+
+fn qname(segs: &[String]) -> QName {
+    let (name, module) = segs.split_last().unwrap();
+    QName { module: module.iter().cloned().map(Ident).collect(), name: Ident(name.clone()) }
+}
+
+fn unparenthesize(ty: Type) -> Type {
+    match ty {
+        Type::Tuple(mut tys) if tys.len() == 1 && tys[0].complex() => tys.pop().unwrap(),
+        ty => ty,
+    }
+}
+
+fn is_capitalized(segs: &[String]) -> bool {
+    segs.last().map_or(false, |s| s.starts_with(char::is_uppercase))
+}
+
+struct Parser {
+    toks: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(input: &str) -> Result<Self> {
+        Ok(Parser { toks: lex(input)?, pos: 0 })
+    }
+
+    fn peek(&self) -> &Tok {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, n: usize) -> &Tok {
+        let last = self.toks.len() - 1;
+        &self.toks[std::cmp::min(self.pos + n, last)].tok
+    }
+
+    // Whether the next token is preceded by whitespace
+    fn spaced(&self) -> bool {
+        self.toks.get(self.pos).map_or(true, |tok| tok.spaced)
+    }
+
+    fn bump(&mut self) -> Tok {
+        let tok = self.peek().clone();
+        self.pos += 1;
+        tok
+    }
+
+    fn error<T>(&self, message: String) -> Result<T> {
+        let last = self.toks.len() - 1;
+        Err(ParseError { line: self.toks[std::cmp::min(self.pos, last)].line, message })
+    }
+
+    fn unexpected<T>(&self, expected: &str) -> Result<T> {
+        self.error(format!("expected {}, found {}", expected, self.peek()))
+    }
+
+    fn is_sym(&self, sym: &str) -> bool {
+        matches!(self.peek(), Tok::Sym(s) if *s == sym)
+    }
+
+    fn is_kw(&self, kw: &str) -> bool {
+        self.is_kw_at(0, kw)
+    }
+
+    fn is_kw_at(&self, n: usize, kw: &str) -> bool {
+        matches!(self.peek_at(n), Tok::Name(segs) if segs.len() == 1 && segs[0] == kw)
+    }
+
+    fn eat_sym(&mut self, sym: &str) -> bool {
+        let is_sym = self.is_sym(sym);
+        if is_sym {
+            self.bump();
+        }
+        is_sym
+    }
+
+    fn eat_kw(&mut self, kw: &str) -> bool {
+        let is_kw = self.is_kw(kw);
+        if is_kw {
+            self.bump();
+        }
+        is_kw
+    }
+
+    fn expect(&mut self, tok: &Tok) -> Result<()> {
+        if self.peek() == tok {
+            self.bump();
+            Ok(())
+        } else {
+            self.unexpected(&tok.to_string())
+        }
+    }
+
+    fn expect_sym(&mut self, sym: &'static str) -> Result<()> {
+        self.expect(&Tok::Sym(sym))
+    }
+
+    fn expect_kw(&mut self, kw: &str) -> Result<()> {
+        if self.eat_kw(kw) { Ok(()) } else { self.unexpected(&format!("`{}`", kw)) }
+    }
+
+    // A name which is not a keyword, the printer escapes identifiers which collide with one
+    fn is_name(&self) -> bool {
+        matches!(self.peek(), Tok::Name(segs) if segs.len() > 1 || !RESERVED.contains(&&*segs[0]))
+    }
+
+    fn ident(&mut self) -> Result<Ident> {
+        match self.peek() {
+            Tok::Name(segs) if segs.len() == 1 && self.is_name() => {
+                let id = Ident(segs[0].clone());
+                self.bump();
+                Ok(id)
+            }
+            _ => self.unexpected("an identifier"),
+        }
+    }
+
+    fn qname(&mut self) -> Result<QName> {
+        match self.bump() {
+            Tok::Name(segs) => Ok(qname(&segs)),
+            _ => {
+                self.pos -= 1;
+                self.unexpected("a name")
+            }
+        }
+    }
+
+    fn module(&mut self) -> Result<Module> {
+        let name = self.ident()?;
+        let decls = self.decls()?;
+        Ok(Module { name, decls })
+    }
+
+    fn decls(&mut self) -> Result<Vec<Decl>> {
+        let mut decls = Vec::new();
+        while !self.eat_kw("end") {
+            decls.push(self.decl()?);
+        }
+        Ok(decls)
+    }
+
+    fn decl(&mut self) -> Result<Decl> {
+        let kw = match self.peek() {
+            Tok::Name(segs) if segs.len() == 1 => segs[0].clone(),
+            _ => return self.unexpected("a declaration"),
+        };
+        self.bump();
+
+        match &*kw {
+            "module" => Ok(Decl::Module(self.module()?)),
+            "scope" => {
+                let name = self.ident()?;
+                Ok(Decl::Scope(Scope { name, decls: self.decls()? }))
+            }
+            "use" => Ok(Decl::UseDecl(Use { name: self.qname()? })),
+            "clone" => self.clone_decl(),
+            "type" => self.ty_decl(),
+            "val" => Ok(Decl::ValDecl(ValKind::Val { sig: self.signature()? })),
+            "function" => {
+                let sig = self.signature()?;
+                if self.eat_sym("=") {
+                    Ok(Decl::LogicDecl(Logic { sig, body: self.exp()? }))
+                } else {
+                    Ok(Decl::ValDecl(ValKind::Function { sig }))
+                }
+            }
+            "predicate" => {
+                let sig = self.signature()?;
+                if self.eat_sym("=") {
+                    Ok(Decl::PredDecl(Predicate { sig, body: self.exp()? }))
+                } else {
+                    Ok(Decl::ValDecl(ValKind::Predicate { sig }))
+                }
+            }
+            "let" => self.let_decl(),
+            "axiom" => {
+                let name = self.ident()?;
+                self.expect_sym(":")?;
+                Ok(Decl::Axiom(Axiom { name, axiom: self.exp()? }))
+            }
+            "goal" => {
+                let name = self.ident()?;
+                self.expect_sym(":")?;
+                Ok(Decl::Goal(Goal { name, goal: self.exp()? }))
+            }
+            _ => {
+                self.pos -= 1;
+                self.unexpected("a declaration")
+            }
+        }
+    }
+
+    fn clone_decl(&mut self) -> Result<Decl> {
+        let export = self.eat_kw("export");
+        let name = self.qname()?;
+        let kind = if export {
+            CloneKind::Export
+        } else if self.eat_kw("as") {
+            CloneKind::Named(self.ident()?)
+        } else {
+            CloneKind::Bare
+        };
+
+        let mut subst = Vec::new();
+        if self.eat_kw("with") {
+            loop {
+                subst.push(self.clone_subst()?);
+                if !self.eat_sym(",") {
+                    break;
+                }
+            }
+        }
+        Ok(Decl::Clone(DeclClone { name, subst, kind }))
+    }
+
+    fn clone_subst(&mut self) -> Result<CloneSubst> {
+        if self.eat_kw("axiom") {
+            let name = if self.eat_sym(".") { None } else { Some(self.qname()?) };
+            return Ok(CloneSubst::Axiom(name));
+        }
+
+        let kinds = ["type", "val", "predicate", "function"];
+        let kind = kinds.iter().copied().find(|kw| self.is_kw(kw));
+        let kind = match kind {
+            Some(kind) => kind,
+            None => return self.unexpected("a substitution"),
+        };
+        self.bump();
+        let name = self.qname()?;
+        self.expect_sym("=")?;
+        Ok(match kind {
+            "type" => CloneSubst::Type(name, self.ty()?),
+            "val" => CloneSubst::Val(name, self.qname()?),
+            "predicate" => CloneSubst::Predicate(name, self.qname()?),
+            _ => CloneSubst::Function(name, self.qname()?),
+        })
+    }
+
+    fn ty_params(&mut self) -> Vec<Ident> {
+        let mut params = Vec::new();
+        while let Tok::TyVar(v) = self.peek() {
+            params.push(Ident(v.clone()));
+            self.bump();
+        }
+        params
+    }
+
+    // Type names may be qualified, when they define a type declared by a clone
+    fn ty_name(&mut self) -> Result<Ident> {
+        match self.peek() {
+            Tok::Name(segs) if self.is_name() => {
+                let name = Ident(segs.join("."));
+                self.bump();
+                Ok(name)
+            }
+            _ => self.unexpected("a type name"),
+        }
+    }
+
+    fn ty_decl(&mut self) -> Result<Decl> {
+        let ty_name = self.ty_name()?;
+        let ty_params = self.ty_params();
+
+        if !self.eat_sym("=") {
+            return Ok(Decl::TyDecl(TyDecl::Opaque { ty_name, ty_params }));
+        }
+        if !self.is_sym("|") {
+            return Ok(Decl::TyDecl(TyDecl::Alias { ty_name, ty_params, alias: self.ty()? }));
+        }
+
+        let mut tys = vec![AdtDecl { ty_name, ty_params, constrs: self.constructors()? }];
+        while self.eat_kw("with") {
+            let ty_name = self.ty_name()?;
+            let ty_params = self.ty_params();
+            self.expect_sym("=")?;
+            tys.push(AdtDecl { ty_name, ty_params, constrs: self.constructors()? });
+        }
+        Ok(Decl::TyDecl(TyDecl::Adt { tys }))
+    }
+
+    fn constructors(&mut self) -> Result<Vec<ConstructorDecl>> {
+        let mut constrs = Vec::new();
+        while self.eat_sym("|") {
+            let name = self.ident()?;
+            let mut fields = Vec::new();
+            while self.starts_ty_atom() {
+                fields.push(self.ty_operand()?);
+            }
+            constrs.push(ConstructorDecl { name, fields });
+        }
+        Ok(constrs)
+    }
+
+    fn let_decl(&mut self) -> Result<Decl> {
+        let rec = self.eat_kw("rec");
+
+        if self.eat_kw("cfg") {
+            let constant = self.eat_kw("constant");
+            let sig = self.signature()?;
+            self.expect_sym("=")?;
+            return Ok(Decl::FunDecl(self.cfg_body(sig, rec, constant)?));
+        }
+
+        let ghost = self.eat_kw("ghost");
+        if ghost || self.is_kw("function") {
+            self.expect_kw("function")?;
+            let sig = self.signature()?;
+            self.expect_sym("=")?;
+            return Ok(Decl::LetFun(LetFun { sig, rec, ghost, body: self.exp()? }));
+        }
+
+        let constant = self.eat_kw("constant");
+        let sig = self.signature()?;
+        self.expect_sym("=")?;
+        Ok(Decl::Let(LetDecl { sig, rec, constant, body: self.exp()? }))
+    }
+
+    fn signature(&mut self) -> Result<Signature> {
+        let name = self.ident()?;
+
+        let mut attrs = Vec::new();
+        while let Tok::Attr(attr) = self.peek() {
+            attrs.push(attr.clone());
+            self.bump();
+        }
+
+        let mut args = Vec::new();
+        while self.eat_sym("(") {
+            let arg = self.ident()?;
+            self.expect_sym(":")?;
+            args.push((arg, self.ty()?));
+            self.expect_sym(")")?;
+        }
+
+        let retty = if self.eat_sym(":") { Some(self.ty()?) } else { None };
+
+        let mut contract = Contract::new();
+        loop {
+            let clause = if self.eat_kw("requires") {
+                &mut contract.requires
+            } else if self.eat_kw("ensures") {
+                &mut contract.ensures
+            } else if self.eat_kw("variant") {
+                &mut contract.variant
+            } else {
+                break;
+            };
+            clause.push(self.braced_exp()?);
+        }
+
+        Ok(Signature { name, attrs, retty, args, contract })
+    }
+
+    fn cfg_body(&mut self, sig: Signature, rec: bool, constant: bool) -> Result<CfgFunction> {
+        let mut vars = Vec::new();
+        loop {
+            let ghost = self.is_kw("ghost") && self.is_kw_at(1, "var");
+            if ghost {
+                self.bump();
+            }
+            if !self.eat_kw("var") {
+                break;
+            }
+            let var = self.ident()?;
+            self.expect_sym(":")?;
+            vars.push((ghost, var, self.ty()?));
+            self.expect_sym(";")?;
+        }
+
+        let entry = self.block()?;
+
+        let mut blocks = BTreeMap::new();
+        while let Some(id) = self.block_id() {
+            self.bump();
+            blocks.insert(id, self.block()?);
+        }
+
+        Ok(CfgFunction { sig, rec, constant, vars, entry, blocks })
+    }
+
+    fn block_id(&self) -> Option<BlockId> {
+        match self.peek() {
+            Tok::Name(segs) if segs.len() == 1 => {
+                segs[0].strip_prefix("BB")?.parse().ok().map(BlockId)
+            }
+            _ => None,
+        }
+    }
+
+    fn block(&mut self) -> Result<Block> {
+        self.expect_sym("{")?;
+        let mut statements = Vec::new();
+        let terminator = loop {
+            if ["goto", "absurd", "return", "switch"].iter().any(|kw| self.is_kw(kw)) {
+                break self.terminator()?;
+            }
+            statements.push(self.statement()?);
+            self.expect_sym(";")?;
+        };
+        self.expect_sym("}")?;
+        Ok(Block { statements, terminator })
+    }
+
+    fn statement(&mut self) -> Result<Statement> {
+        if self.eat_kw("invariant") {
+            let name = self.ident()?;
+            Ok(Statement::Invariant(name, self.braced_exp()?))
+        } else if self.eat_kw("assume") {
+            Ok(Statement::Assume(self.braced_exp()?))
+        } else if self.eat_kw("assert") {
+            Ok(Statement::Assert(self.braced_exp()?))
+        } else {
+            let lhs = self.ident()?;
+            self.expect_sym("<-")?;
+            Ok(Statement::Assign { lhs, rhs: self.exp()? })
+        }
+    }
+
+    fn terminator(&mut self) -> Result<Terminator> {
+        if self.eat_kw("goto") {
+            match self.block_id() {
+                Some(id) => {
+                    self.bump();
+                    Ok(Terminator::Goto(id))
+                }
+                None => self.unexpected("a block"),
+            }
+        } else if self.eat_kw("absurd") {
+            Ok(Terminator::Absurd)
+        } else if self.eat_kw("return") {
+            self.ident()?;
+            Ok(Terminator::Return)
+        } else if self.eat_kw("switch") {
+            let discr = self.parenthesized_exp()?;
+            let mut brs = Vec::new();
+            while self.eat_sym("|") {
+                let pat = self.pattern()?;
+                self.expect_sym("->")?;
+                brs.push((pat, self.terminator()?));
+            }
+            self.expect_kw("end")?;
+            Ok(Terminator::Switch(discr, brs))
+        } else {
+            self.unexpected("a terminator")
+        }
+    }
+
+    fn starts_ty_atom(&self) -> bool {
+        matches!(self.peek(), Tok::TyVar(_) | Tok::Sym("("))
+            || (self.is_name() && !self.is_kw("borrowed"))
+    }
+
+    fn ty(&mut self) -> Result<Type> {
+        let ty = if self.eat_kw("borrowed") {
+            Type::MutableBorrow(box self.ty_operand()?)
+        } else {
+            let head = self.ty_atom()?;
+            let mut args = Vec::new();
+            while self.starts_ty_atom() {
+                args.push(self.ty_operand()?);
+            }
+            if args.is_empty() { head } else { Type::TApp(box head, args) }
+        };
+
+        if self.eat_sym("->") {
+            Ok(Type::TFun(box unparenthesize(ty), box unparenthesize(self.ty()?)))
+        } else {
+            Ok(ty)
+        }
+    }
+
+    // The printer parenthesizes complex types in operand position, elsewhere a parenthesized type
+    // is a tuple with a single element
+    fn ty_operand(&mut self) -> Result<Type> {
+        Ok(unparenthesize(self.ty_atom()?))
+    }
+
+    fn ty_atom(&mut self) -> Result<Type> {
+        match self.bump() {
+            Tok::TyVar(v) => Ok(Type::TVar(Ident(v))),
+            Tok::Name(segs) if segs.len() == 1 && segs[0] == "bool" => Ok(Type::Bool),
+            Tok::Name(segs) if segs.len() == 1 && segs[0] == "char" => Ok(Type::Char),
+            Tok::Name(segs) if segs.len() == 1 && segs[0] == "int" => Ok(Type::Integer),
+            Tok::Name(segs) => Ok(Type::TConstructor(qname(&segs))),
+            Tok::Sym("(") => {
+                let mut tys = Vec::new();
+                while !self.eat_sym(")") {
+                    tys.push(self.ty()?);
+                    if !self.eat_sym(",") {
+                        self.expect_sym(")")?;
+                        break;
+                    }
+                }
+                Ok(Type::Tuple(tys))
+            }
+            _ => {
+                self.pos -= 1;
+                self.unexpected("a type")
+            }
+        }
+    }
+
+    fn pattern(&mut self) -> Result<Pattern> {
+        match self.peek().clone() {
+            Tok::Name(segs) if is_capitalized(&segs) => {
+                self.bump();
+                let mut args = Vec::new();
+                while self.is_sym("(") || self.is_name() {
+                    args.push(self.pattern_atom()?);
+                }
+                Ok(Pattern::ConsP(qname(&segs), args))
+            }
+            _ => self.pattern_atom(),
+        }
+    }
+
+    fn pattern_atom(&mut self) -> Result<Pattern> {
+        match self.bump() {
+            Tok::Name(segs) if segs.len() == 1 && segs[0] == "_" => Ok(Pattern::Wildcard),
+            Tok::Name(segs) if is_capitalized(&segs) => Ok(Pattern::ConsP(qname(&segs), vec![])),
+            Tok::Name(segs) if segs.len() == 1 => Ok(Pattern::VarP(Ident(segs[0].clone()))),
+            Tok::Sym("(") => {
+                let mut pats = Vec::new();
+                while !self.eat_sym(")") {
+                    pats.push(self.pattern()?);
+                    if !self.eat_sym(",") {
+                        self.expect_sym(")")?;
+                        if pats.len() == 1 && matches!(pats[0], Pattern::ConsP(..)) {
+                            return Ok(pats.pop().unwrap());
+                        }
+                        break;
+                    }
+                }
+                Ok(Pattern::TupleP(pats))
+            }
+            _ => {
+                self.pos -= 1;
+                self.unexpected("a pattern")
+            }
+        }
+    }
+
+    fn braced_exp(&mut self) -> Result<Exp> {
+        self.expect_sym("{")?;
+        let exp = self.exp()?;
+        self.expect_sym("}")?;
+        Ok(exp)
+    }
+
+    fn parenthesized_exp(&mut self) -> Result<Exp> {
+        self.expect_sym("(")?;
+        let exp = self.exp()?;
+        self.expect_sym(")")?;
+        Ok(exp)
+    }
+
+    // The binders and triggers of a quantifier, up to the `.` before its body
+    fn binders(&mut self) -> Result<(Vec<(Ident, Type)>, Vec<Trigger>)> {
+        let mut binders = Vec::new();
+        loop {
+            let name = self.ident()?;
+            self.expect_sym(":")?;
+            binders.push((name, self.ty()?));
+            if !self.eat_sym(",") {
+                break;
+            }
+        }
+
+        let mut triggers = Vec::new();
+        if self.eat_sym("[") {
+            loop {
+                let mut pats = vec![self.exp()?];
+                while self.eat_sym(",") {
+                    pats.push(self.exp()?);
+                }
+                triggers.push(Trigger(pats));
+                if !self.eat_sym("|") {
+                    break;
+                }
+            }
+            self.expect_sym("]")?;
+        }
+        self.expect_sym(".")?;
+        Ok((binders, triggers))
+    }
+
+    // The expression parsers follow `Precedence`, from the loosest binding forms to atoms
+    fn exp(&mut self) -> Result<Exp> {
+        if let Tok::Attr(attr) = self.peek() {
+            let attr = attr.clone();
+            self.bump();
+            return Ok(Exp::Attr(attr, box self.exp()?));
+        }
+
+        if self.eat_kw("let") {
+            let pattern = self.pattern()?;
+            self.expect_sym("=")?;
+            let arg = self.exp()?;
+            self.expect_kw("in")?;
+            return Ok(Exp::Let { pattern, arg: box arg, body: box self.exp()? });
+        }
+        if self.eat_kw("if") {
+            let cond = self.exp()?;
+            self.expect_kw("then")?;
+            let then = self.exp()?;
+            self.expect_kw("else")?;
+            return Ok(Exp::IfThenElse(box cond, box then, box self.exp()?));
+        }
+        if self.eat_kw("forall") {
+            let (binders, triggers) = self.binders()?;
+            return Ok(Exp::Forall(binders, triggers, box self.exp()?));
+        }
+        if self.eat_kw("exists") {
+            let (binders, triggers) = self.binders()?;
+            return Ok(Exp::Exists(binders, triggers, box self.exp()?));
+        }
+        if self.eat_kw("fun") {
+            let arg = self.ident()?;
+            self.expect_sym("->")?;
+            return Ok(Exp::Abs(arg, box self.exp()?));
+        }
+
+        let exp = self.implication()?;
+        if self.eat_sym(":") { Ok(Exp::Ascribe(box exp, self.ty()?)) } else { Ok(exp) }
+    }
+
+    fn implication(&mut self) -> Result<Exp> {
+        let hyp = self.disjunction()?;
+        if self.eat_sym("->") { Ok(Exp::Impl(box hyp, box self.implication()?)) } else { Ok(hyp) }
+    }
+
+    fn disjunction(&mut self) -> Result<Exp> {
+        let l = self.conjunction()?;
+        if self.eat_sym("||") {
+            Ok(Exp::BinaryOp(BinOp::Or, box l, box self.disjunction()?))
+        } else {
+            Ok(l)
+        }
+    }
+
+    fn conjunction(&mut self) -> Result<Exp> {
+        let l = self.negation()?;
+        if self.eat_sym("&&") {
+            Ok(Exp::BinaryOp(BinOp::And, box l, box self.conjunction()?))
+        } else {
+            Ok(l)
+        }
+    }
+
+    fn negation(&mut self) -> Result<Exp> {
+        if self.eat_kw("not") {
+            Ok(Exp::UnaryOp(UnOp::Not, box self.negation()?))
+        } else {
+            self.comparison()
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Exp> {
+        let l = self.additive()?;
+        let ops = [
+            ("=", BinOp::Eq),
+            ("<>", BinOp::Ne),
+            ("<", BinOp::Lt),
+            ("<=", BinOp::Le),
+            (">", BinOp::Gt),
+            (">=", BinOp::Ge),
+        ];
+        match ops.iter().find(|(sym, _)| self.is_sym(sym)) {
+            Some((_, op)) => {
+                self.bump();
+                Ok(Exp::BinaryOp(*op, box l, box self.additive()?))
+            }
+            None => Ok(l),
+        }
+    }
+
+    fn additive(&mut self) -> Result<Exp> {
+        let mut l = self.multiplicative()?;
+        let ops = [("+", BinOp::Add), ("-", BinOp::Sub)];
+        while let Some((_, op)) = ops.iter().find(|(sym, _)| self.is_sym(sym)) {
+            self.bump();
+            l = Exp::BinaryOp(*op, box l, box self.multiplicative()?);
+        }
+        Ok(l)
+    }
+
+    fn multiplicative(&mut self) -> Result<Exp> {
+        let mut l = self.prefix()?;
+        let ops = [("*", BinOp::Mul), ("/", BinOp::Div), ("%", BinOp::Mod)];
+        while let Some((_, op)) = ops.iter().find(|(sym, _)| self.is_sym(sym)) {
+            self.bump();
+            l = Exp::BinaryOp(*op, box l, box self.prefix()?);
+        }
+        Ok(l)
+    }
+
+    fn prefix(&mut self) -> Result<Exp> {
+        if self.eat_sym("-") {
+            Ok(Exp::UnaryOp(UnOp::Neg, box self.prefix()?))
+        } else if self.eat_sym("*") {
+            Ok(Exp::Current(box self.prefix()?))
+        } else if self.eat_sym("^") {
+            Ok(Exp::Final(box self.prefix()?))
+        } else if self.eat_kw("any") {
+            Ok(Exp::Any(self.ty()?))
+        } else {
+            self.application()
+        }
+    }
+
+    fn starts_atom(&self) -> bool {
+        matches!(self.peek(), Tok::Int(_) | Tok::Real(_) | Tok::Str(_) | Tok::Sym("("))
+            || self.is_name()
+            || ["true", "false", "absurd", "pure", "old", "match"].iter().any(|kw| self.is_kw(kw))
+    }
+
+    fn application(&mut self) -> Result<Exp> {
+        if self.eat_kw("borrow_mut") {
+            return Ok(Exp::BorrowMut(box self.atom()?));
+        }
+        if self.eat_kw("ghost") {
+            return Ok(Exp::Ghost(box self.atom()?));
+        }
+
+        let head = self.atom()?;
+        let mut args = Vec::new();
+        while self.starts_atom() {
+            args.push(self.atom()?);
+        }
+
+        // A call without arguments is printed as its function followed by a space
+        let nullary = matches!(head, Exp::Var(..) | Exp::QVar(..)) && self.is_sym(")");
+        if args.is_empty() && !(nullary && self.spaced()) {
+            return Ok(head);
+        }
+        Ok(match head {
+            Exp::Constructor { ctor, args: none } if none.is_empty() => {
+                Exp::Constructor { ctor, args }
+            }
+            _ => Exp::Call(box head, args),
+        })
+    }
+
+    fn atom(&mut self) -> Result<Exp> {
+        match self.bump() {
+            Tok::Int(i) => Ok(Exp::Const(self.int(&i, None)?)),
+            Tok::Real(r) => Ok(Exp::Const(Constant::Float(r, None))),
+            Tok::Str(s) => Ok(Exp::Const(Constant::String(s))),
+            Tok::Name(segs) if segs.len() == 1 && RESERVED.contains(&&*segs[0]) => {
+                match &*segs[0] {
+                    "true" => Ok(Exp::Const(Constant::Bool(true))),
+                    "false" => Ok(Exp::Const(Constant::Bool(false))),
+                    "absurd" => Ok(Exp::Absurd),
+                    "pure" => Ok(Exp::Pure(box self.braced_exp()?)),
+                    "old" => Ok(Exp::Old(box self.parenthesized_exp()?)),
+                    "match" => {
+                        let scrut = self.parenthesized_exp()?;
+                        self.expect_kw("with")?;
+                        let mut brs = Vec::new();
+                        while self.eat_sym("|") {
+                            let pat = self.pattern()?;
+                            self.expect_sym("->")?;
+                            brs.push((pat, self.exp()?));
+                        }
+                        self.expect_kw("end")?;
+                        Ok(Exp::Match(box scrut, brs))
+                    }
+                    _ => {
+                        self.pos -= 1;
+                        self.unexpected("an expression")
+                    }
+                }
+            }
+            Tok::Name(segs) if is_capitalized(&segs) => {
+                Ok(Exp::Constructor { ctor: qname(&segs), args: Vec::new() })
+            }
+            Tok::Name(mut segs) if segs.len() == 1 => {
+                Ok(Exp::Var(Ident(segs.pop().unwrap()), Purity::Program))
+            }
+            Tok::Name(segs) => Ok(Exp::QVar(qname(&segs), Purity::Program)),
+            Tok::Sym("{") => {
+                let record = self.atom()?;
+                self.expect_kw("with")?;
+                let label = self.ident()?.to_string();
+                self.expect_sym("=")?;
+                let val = self.exp()?;
+                self.expect_sym("}")?;
+                Ok(Exp::RecUp { record: box record, label, val: box val })
+            }
+            Tok::Sym("(") => self.parenthesized(),
+            _ => {
+                self.pos -= 1;
+                self.unexpected("an expression")
+            }
+        }
+    }
+
+    // Everything after an opening parenthesis: tuples, typed integer and character constants
+    fn parenthesized(&mut self) -> Result<Exp> {
+        if let (Tok::Int(i), Tok::Sym(":")) = (self.peek(), self.peek_at(1)) {
+            let i = i.clone();
+            self.pos += 2;
+            let ty = self.ty()?;
+            self.expect_sym(")")?;
+            return Ok(Exp::Const(self.int(&i, Some(ty))?));
+        }
+        if let (Tok::Real(r), Tok::Sym(":")) = (self.peek(), self.peek_at(1)) {
+            let r = r.clone();
+            self.pos += 2;
+            let ty = self.ty()?;
+            self.expect_sym(")")?;
+            return Ok(Exp::Const(Constant::Float(r, Some(ty))));
+        }
+        if let (Tok::Name(segs), Tok::Int(code), Tok::Sym(")")) =
+            (self.peek(), self.peek_at(1), self.peek_at(2))
+        {
+            if segs == &["Char", "chr"] {
+                let c = code.parse().ok().and_then(char::from_u32);
+                return match c {
+                    Some(c) => {
+                        self.pos += 3;
+                        Ok(Exp::Const(Constant::Char(c)))
+                    }
+                    None => self.error(format!("invalid character code {}", code)),
+                };
+            }
+        }
+
+        let mut exps = Vec::new();
+        while !self.eat_sym(")") {
+            exps.push(self.exp()?);
+            if !self.eat_sym(",") {
+                self.expect_sym(")")?;
+                if exps.len() == 1 && exps[0].precedence() != Precedence::Atom {
+                    return Ok(exps.pop().unwrap());
+                }
+                break;
+            }
+        }
+        Ok(Exp::Tuple(exps))
+    }
+
+    fn int(&self, i: &str, ty: Option<Type>) -> Result<Constant> {
+        match (i.parse(), i.parse()) {
+            (Ok(i), _) => Ok(Constant::Int(i, ty)),
+            (_, Ok(u)) => Ok(Constant::Uint(u, ty)),
+            _ => self.error(format!("integer literal {} out of range", i)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Print;
+
+    fn round_trip(exp: &str) -> String {
+        parse_exp(exp).unwrap().display().to_string()
+    }
+
+    #[test]
+    fn operators() {
+        for exp in ["a - b - c", "a - (b - c)", "a -> b -> c", "(a -> b) -> c", "not a = b && c"] {
+            assert_eq!(round_trip(exp), exp);
+        }
+    }
+
+    #[test]
+    fn triggers() {
+        let exps = [
+            "forall x : int [f x] . f x = 0",
+            "exists x : int, y : int [g x, g y | h x] . p x y",
+            "forall x : int . f x = 0",
+        ];
+        for exp in exps {
+            assert_eq!(round_trip(exp), exp);
+        }
+    }
+
+    #[test]
+    fn constants() {
+        let int32 = Type::TConstructor("int32".into());
+        assert_eq!(parse_exp("(-1 : int32)").unwrap(), Exp::Const(Constant::Int(-1, Some(int32))));
+        assert_eq!(parse_exp("(Char.chr 97)").unwrap(), Exp::Const(Constant::Char('a')));
+
+        let float64 = Type::TConstructor(QName::from_string("Float64.t").unwrap());
+        let floats = [
+            (1.5, "(0x1.8p0 : Float64.t)"),
+            (-0.1, "(-0x1.999999999999ap-4 : Float64.t)"),
+        ];
+        for (value, lit) in floats {
+            let constant = Exp::Const(Constant::float(value, Some(float64.clone())));
+            assert_eq!(constant.display().to_string(), lit);
+            assert_eq!(parse_exp(lit).unwrap(), constant);
+        }
+    }
+
+    #[test]
+    fn errors_report_the_line() {
+        let err = parse_modules("module M\n  use Foo\n  val\nend").unwrap_err();
+        assert_eq!(err.line, 4);
+    }
+}
@@ -1,9 +1,9 @@
-use std::{fmt::Display, iter::once};
+use std::{collections::HashMap, fmt::Display, iter::once, ops::Range};
 
 use super::*;
 use crate::{
     declaration::*,
-    exp::{AssocDir, BinOp, Constant, Precedence, UnOp},
+    exp::{AssocDir, BinOp, Constant, Precedence, Trigger, UnOp},
 };
 use pretty::*;
 
@@ -18,22 +18,87 @@ impl PrintEnv {
     }
 }
 
+/// The node a part of the output was printed from. Documents are annotated with it so that a
+/// [`SpanSink`] can record where each node was printed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NodeRef {
+    kind: &'static str,
+    addr: usize,
+}
+
+impl NodeRef {
+    pub fn of<T>(node: &T) -> Self {
+        NodeRef { kind: std::any::type_name::<T>(), addr: node as *const T as usize }
+    }
+}
+
+/// A text sink which records the byte range of the output printed from each declaration,
+/// block, statement, terminator and expression, giving a source map of the output
+pub struct SpanSink<W> {
+    out: W,
+    pos: usize,
+    open: Vec<(NodeRef, usize)>,
+    spans: HashMap<NodeRef, Range<usize>>,
+}
+
+impl<W: std::fmt::Write> SpanSink<W> {
+    pub fn new(out: W) -> Self {
+        SpanSink { out, pos: 0, open: Vec::new(), spans: HashMap::new() }
+    }
+
+    /// The byte range printed from `node`, if it was printed. A node printed several times is
+    /// mapped to the last of its occurrences.
+    pub fn span_of<T>(&self, node: &T) -> Option<Range<usize>> {
+        self.spans.get(&NodeRef::of(node)).cloned()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl<W: std::fmt::Write> Render for SpanSink<W> {
+    type Error = std::fmt::Error;
+
+    fn write_str(&mut self, s: &str) -> Result<usize, Self::Error> {
+        self.out.write_str(s)?;
+        self.pos += s.len();
+        Ok(s.len())
+    }
+
+    fn fail_doc(&self) -> Self::Error {
+        std::fmt::Error
+    }
+}
+
+impl<W: std::fmt::Write> RenderAnnotated<'_, NodeRef> for SpanSink<W> {
+    fn push_annotation(&mut self, node: &NodeRef) -> Result<(), Self::Error> {
+        self.open.push((*node, self.pos));
+        Ok(())
+    }
+
+    fn pop_annotation(&mut self) -> Result<(), Self::Error> {
+        if let Some((node, start)) = self.open.pop() {
+            self.spans.insert(node, start..self.pos);
+        }
+        Ok(())
+    }
+}
+
 pub struct PrintDisplay<'a, A: Print>(&'a A);
 
 impl<'a, A: Print> Display for PrintDisplay<'a, A> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let (alloc, mut env) = PrintEnv::new();
-        self.0.pretty(&alloc, &mut env).1.render_fmt(120, f)?;
-        Ok(())
+        self.0.render_fmt(120, f)
     }
 }
 
 pub trait Print {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone;
 
@@ -43,51 +108,102 @@ pub trait Print {
     {
         PrintDisplay(self)
     }
-}
 
-use itertools::*;
+    /// Render to a byte sink such as a file or stdout, breaking lines longer than `width`
+    fn render<W: std::io::Write>(&self, width: usize, out: &mut W) -> std::io::Result<()>
+    where
+        Self: Sized,
+    {
+        let (alloc, mut env) = PrintEnv::new();
+        let doc = self.pretty(&alloc, &mut env);
+        doc.1.render(width, out)
+    }
 
-// TODO: replace with functions
-macro_rules! parens {
-    ($alloc:ident, $env:ident, $parent:ident, $child:ident) => {
-        parens($alloc, $env, $parent.precedence(), $child)
-    };
-    ($alloc:ident, $env:ident, $par_prec:expr, $child:ident) => {
-        parens($alloc, $env, $par_prec, $child)
-    };
+    /// Render to a text sink such as a `String` or a `Formatter`
+    fn render_fmt<W: std::fmt::Write>(&self, width: usize, out: &mut W) -> std::fmt::Result
+    where
+        Self: Sized,
+    {
+        let (alloc, mut env) = PrintEnv::new();
+        let doc = self.pretty(&alloc, &mut env);
+        doc.1.render_fmt(width, out)
+    }
+
+    /// Render to a text sink, recording the byte range printed from each node in `sink`
+    fn render_spans<W: std::fmt::Write>(
+        &self,
+        width: usize,
+        sink: &mut SpanSink<W>,
+    ) -> std::fmt::Result
+    where
+        Self: Sized,
+    {
+        let (alloc, mut env) = PrintEnv::new();
+        let doc = self.pretty(&alloc, &mut env);
+        doc.1.render_raw(width, sink)
+    }
 }
 
-fn parens<'b, 'a: 'b, A: DocAllocator<'a>>(
+use itertools::*;
+
+// Prints `child` in a position which requires precedence `prec`, adding parentheses if it binds
+// less tightly
+fn parens<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
     alloc: &'a A,
     env: &mut PrintEnv,
     prec: Precedence,
     child: &'a Exp,
-) -> DocBuilder<'a, A>
+) -> DocBuilder<'a, A, NodeRef>
 where
     A::Doc: Clone,
 {
     let child_prec = child.precedence();
-    if child_prec == Precedence::Atom {
-        child.pretty(alloc, env)
-    } else if child_prec < prec {
-        child.pretty(alloc, env).parens()
-    } else if child_prec == prec && child.associativity() != child.associativity() {
+    if child_prec != Precedence::Atom && child_prec < prec {
         child.pretty(alloc, env).parens()
     } else {
         child.pretty(alloc, env)
     }
 }
 
+// The precedence required of the operands of an infix operator: the operand on the side the
+// operator associates to may be a chain of the same operator, the other one must bind tighter
+fn operand_precedence(op: &Exp, side: AssocDir) -> Precedence {
+    if op.associativity() == Some(side) {
+        op.precedence()
+    } else {
+        op.precedence().next()
+    }
+}
+
+// The triggers of a quantifier, as ` [t1, t2 | t3]`
+fn pretty_triggers<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
+    alloc: &'a A,
+    env: &mut PrintEnv,
+    triggers: &'a [Trigger],
+) -> DocBuilder<'a, A, NodeRef>
+where
+    A::Doc: Clone,
+{
+    if triggers.is_empty() {
+        return alloc.nil();
+    }
+
+    let triggers = triggers.iter().map(|Trigger(pats)| {
+        alloc.intersperse(pats.iter().map(|pat| pat.pretty(alloc, env)), alloc.text(", "))
+    });
+    alloc.space().append(alloc.intersperse(triggers, alloc.text(" | ")).brackets())
+}
+
 impl Print for Decl {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
-        match self {
+        let doc = match self {
             Decl::FunDecl(fun) => fun.pretty(alloc, env),
             Decl::LogicDecl(log) => log.pretty(alloc, env),
             Decl::Module(modl) => modl.pretty(alloc, env),
@@ -101,16 +217,17 @@ impl Print for Decl {
             Decl::Goal(g) => g.pretty(alloc, env),
             Decl::Let(l) => l.pretty(alloc, env),
             Decl::LetFun(l) => l.pretty(alloc, env),
-        }
+        };
+        doc.annotate(NodeRef::of(self))
     }
 }
 
 impl Print for Module {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
@@ -135,11 +252,11 @@ impl Print for Module {
 }
 
 impl Print for Scope {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
@@ -165,11 +282,11 @@ impl Print for Scope {
 }
 
 impl Print for Axiom {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
@@ -182,11 +299,11 @@ impl Print for Axiom {
 }
 
 impl Print for Goal {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
@@ -199,11 +316,11 @@ impl Print for Goal {
 }
 
 impl Print for LetDecl {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
@@ -228,11 +345,11 @@ impl Print for LetDecl {
 }
 
 impl Print for LetFun {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
@@ -258,11 +375,11 @@ impl Print for LetFun {
 }
 
 impl Print for Attribute {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         _: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
@@ -283,11 +400,11 @@ impl Print for Attribute {
 }
 
 impl Print for Signature {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
@@ -314,11 +431,11 @@ impl Print for Signature {
 }
 
 impl Print for Predicate {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
@@ -331,11 +448,11 @@ impl Print for Predicate {
     }
 }
 
-fn arg_list<'b: 'a, 'a, A: DocAllocator<'a>>(
+fn arg_list<'b: 'a, 'a, A: DocAllocator<'a, NodeRef>>(
     alloc: &'a A,
     env: &mut PrintEnv,
     args: &'a [(Ident, Type)],
-) -> DocBuilder<'a, A>
+) -> DocBuilder<'a, A, NodeRef>
 where
     A::Doc: Clone,
 {
@@ -350,11 +467,11 @@ where
 }
 
 impl Print for Logic {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
@@ -368,11 +485,11 @@ impl Print for Logic {
 }
 
 impl Print for DeclClone {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
@@ -400,11 +517,11 @@ impl Print for DeclClone {
 }
 
 impl Print for CloneSubst {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
@@ -438,11 +555,11 @@ impl Print for CloneSubst {
 }
 
 impl Print for Use {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
@@ -451,11 +568,11 @@ impl Print for Use {
 }
 
 impl Print for ValKind {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
@@ -468,11 +585,11 @@ impl Print for ValKind {
 }
 
 impl Print for Contract {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
@@ -512,11 +629,11 @@ impl Print for Contract {
 }
 
 impl Print for CfgFunction {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
@@ -551,11 +668,11 @@ impl Print for CfgFunction {
 }
 
 impl Print for Type {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
@@ -599,35 +716,37 @@ impl Print for Type {
 }
 
 impl Print for Exp {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
-        match self {
+        let doc = match self {
             Exp::Any(ty) => alloc.text("any ").append(ty.pretty(alloc, env)),
-            Exp::Current(box e) => alloc.text(" * ").append(parens!(alloc, env, self, e)),
-            Exp::Final(box e) => alloc.text(" ^ ").append(parens!(alloc, env, self, e)),
+            Exp::Current(box e) => {
+                alloc.text(" * ").append(parens(alloc, env, self.precedence(), e))
+            }
+            Exp::Final(box e) => alloc.text(" ^ ").append(parens(alloc, env, self.precedence(), e)),
             // TODO parenthesization
             Exp::Let { pattern, box arg, box body } => alloc
                 .text("let ")
                 .append(pattern.pretty(alloc, env))
                 .append(" = ")
-                .append(parens!(alloc, env, self, arg))
+                .append(parens(alloc, env, self.precedence(), arg))
                 .append(" in ")
                 .append(body.pretty(alloc, env)),
             Exp::Var(v, _) => v.pretty(alloc, env),
             Exp::QVar(v, _) => v.pretty(alloc, env),
             Exp::RecUp { box record, label, box val } => alloc
                 .space()
-                .append(parens!(alloc, env, self.precedence().next(), record))
+                .append(parens(alloc, env, self.precedence().next(), record))
                 .append(" with ")
                 .append(alloc.text(label))
                 .append(" = ")
-                .append(parens!(alloc, env, self, val))
+                .append(parens(alloc, env, self.precedence(), val))
                 .append(alloc.space())
                 .braces(),
             Exp::RecField { box record, label } => {
@@ -642,38 +761,38 @@ impl Print for Exp {
                 alloc.nil()
             } else {
                 alloc.space().append(alloc.intersperse(
-                    args.iter().map(|a| parens!(alloc, env, Precedence::Brackets, a)),
+                    args.iter().map(|a| parens(alloc, env, Precedence::Brackets, a)),
                     " ",
                 ))
             }),
 
             Exp::BorrowMut(box exp) => {
-                alloc.text("borrow_mut ").append(parens!(alloc, env, self.precedence().next(), exp))
+                alloc.text("borrow_mut ").append(parens(alloc, env, self.precedence().next(), exp))
             }
 
             Exp::Const(c) => c.pretty(alloc, env),
 
             Exp::UnaryOp(UnOp::Not, box op) => {
-                alloc.text("not ").append(parens!(alloc, env, self, op))
+                alloc.text("not ").append(parens(alloc, env, self.precedence(), op))
             }
 
-            Exp::UnaryOp(UnOp::Neg, box op) => alloc.text("- ").append(op.pretty(alloc, env)),
-            Exp::BinaryOp(op, box l, box r) => match self.associativity() {
-                Some(AssocDir::Left) => parens!(alloc, env, self, l),
-                Some(AssocDir::Right) | None => parens!(alloc, env, self.precedence().next(), l),
+            Exp::UnaryOp(UnOp::Neg, box op) => {
+                alloc.text("- ").append(parens(alloc, env, self.precedence(), op))
+            }
+            Exp::BinaryOp(op, box l, box r) => {
+                parens(alloc, env, operand_precedence(self, AssocDir::Left), l)
+                    .append(alloc.space())
+                    .append(bin_op_to_string(op))
+                    .append(alloc.space())
+                    .append(parens(alloc, env, operand_precedence(self, AssocDir::Right), r))
             }
-            .append(alloc.space())
-            .append(bin_op_to_string(op))
-            .append(alloc.space())
-            .append(match self.associativity() {
-                Some(AssocDir::Right) => parens!(alloc, env, self, r),
-                Some(AssocDir::Left) | None => parens!(alloc, env, self.precedence().next(), r),
-            }),
             Exp::Call(box fun, args) => {
-                parens!(alloc, env, self, fun).append(alloc.space()).append(alloc.intersperse(
-                    args.iter().map(|a| parens!(alloc, env, Precedence::App.next(), a)),
-                    alloc.space(),
-                ))
+                parens(alloc, env, self.precedence(), fun).append(alloc.space()).append(
+                    alloc.intersperse(
+                        args.iter().map(|a| parens(alloc, env, Precedence::App.next(), a)),
+                        alloc.space(),
+                    ),
+                )
             }
 
             Exp::Verbatim(verb) => alloc.text(verb),
@@ -714,7 +833,7 @@ impl Print for Exp {
                 .append("else")
                 .append(alloc.line().append(e.pretty(alloc, env)).nest(2).append(alloc.line_()))
                 .group(),
-            Exp::Forall(binders, box exp) => alloc
+            Exp::Forall(binders, triggers, box exp) => alloc
                 .text("forall ")
                 .append(alloc.intersperse(
                     binders.iter().map(|(b, t)| {
@@ -722,9 +841,10 @@ impl Print for Exp {
                     }),
                     alloc.text(", "),
                 ))
+                .append(pretty_triggers(alloc, env, triggers))
                 .append(" . ")
                 .append(exp.pretty(alloc, env)),
-            Exp::Exists(binders, box exp) => alloc
+            Exp::Exists(binders, triggers, box exp) => alloc
                 .text("exists ")
                 .append(alloc.intersperse(
                     binders.iter().map(|(b, t)| {
@@ -732,38 +852,42 @@ impl Print for Exp {
                     }),
                     alloc.text(", "),
                 ))
+                .append(pretty_triggers(alloc, env, triggers))
                 .append(" . ")
                 .append(exp.pretty(alloc, env)),
             Exp::Impl(box hyp, box exp) => {
-                parens!(alloc, env, self, hyp).append(" -> ").append(parens!(alloc, env, self, exp))
+                parens(alloc, env, operand_precedence(self, AssocDir::Left), hyp)
+                    .append(" -> ")
+                    .append(parens(alloc, env, operand_precedence(self, AssocDir::Right), exp))
             }
             Exp::Ascribe(e, t) => {
                 e.pretty(alloc, env).append(" : ").append(t.pretty(alloc, env)).group()
             }
             Exp::Pure(e) => alloc.text("pure ").append(e.pretty(alloc, env).braces()),
             Exp::Ghost(e) => {
-                alloc.text("ghost ").append(parens!(alloc, env, Precedence::App.next(), e))
+                alloc.text("ghost ").append(parens(alloc, env, Precedence::App.next(), e))
             }
             Exp::Absurd => alloc.text("absurd"),
             Exp::Old(e) => alloc.text("old").append(e.pretty(alloc, env).parens()),
-        }
+        };
+        doc.annotate(NodeRef::of(self))
     }
 }
 
 impl Print for Statement {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
-        match self {
+        let doc = match self {
             Statement::Assign { lhs, rhs } => lhs
                 .pretty(alloc, env)
                 .append(" <- ")
-                .append(parens!(alloc, env, Precedence::Impl, rhs)),
+                .append(parens(alloc, env, Precedence::Impl, rhs)),
             Statement::Invariant(nm, e) => {
                 let doc =
                     alloc.text("invariant ").append(alloc.text(nm)).append(alloc.space()).append(
@@ -783,21 +907,22 @@ impl Print for Statement {
                 );
                 doc
             }
-        }
+        };
+        doc.annotate(NodeRef::of(self))
     }
 }
 
 impl Print for Terminator {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
         use Terminator::*;
-        match self {
+        let doc = match self {
             Goto(tgt) => alloc.text("goto ").append(tgt.pretty(alloc, env)),
             Absurd => alloc.text("absurd"),
             Return => alloc.text("return _0"),
@@ -820,16 +945,17 @@ impl Print for Terminator {
                     .indent(2),
                 )
                 .append("end"),
-        }
+        };
+        doc.annotate(NodeRef::of(self))
     }
 }
 
 impl Print for Pattern {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
@@ -861,11 +987,11 @@ impl Print for Pattern {
 }
 
 impl Print for BlockId {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         _: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
@@ -892,11 +1018,11 @@ where
 }
 
 impl Print for Block {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
@@ -913,6 +1039,7 @@ impl Print for Block {
             .nest(2)
             .append(alloc.hardline())
             .braces()
+            .annotate(NodeRef::of(self))
     }
 }
 
@@ -936,16 +1063,15 @@ fn bin_op_to_string(op: &BinOp) -> &str {
 }
 
 impl Print for Constant {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
         match self {
-            Constant::Other(o) => alloc.text(o),
             Constant::Bool(b) => {
                 if *b {
                     alloc.text("true")
@@ -962,16 +1088,25 @@ impl Print for Constant {
             }
             Constant::String(s) => alloc.text(s).double_quotes(),
             Constant::Uint(i, None) => alloc.as_string(i),
+            Constant::Float(f, Some(t)) => {
+                alloc.text(f).append(" : ").append(t.pretty(alloc, env)).parens()
+            }
+            Constant::Float(f, None) => alloc.text(f),
+            // Why3 has no character literals, build it from its code point instead
+            Constant::Char(c) => {
+                alloc.text("Char.chr ").append(alloc.as_string(*c as u32)).parens()
+            }
+            Constant::Unit => alloc.text("()"),
         }
     }
 }
 
 impl Print for TyDecl {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
@@ -1048,11 +1183,11 @@ impl Print for TyDecl {
 }
 
 impl Print for ConstructorDecl {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
@@ -1076,11 +1211,11 @@ impl Print for ConstructorDecl {
 }
 
 // impl Print for TyDeclKind {
-//     fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+//     fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
 //         &'a self,
 //         alloc: &'a A,
 //         env: &mut PrintEnv,
-//     ) -> DocBuilder<'a, A>
+//     ) -> DocBuilder<'a, A, NodeRef>
 //     where
 //         A::Doc: Clone,
 //     {
@@ -1115,11 +1250,11 @@ impl Print for ConstructorDecl {
 // }
 
 impl Print for Ident {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         _env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
@@ -1128,11 +1263,11 @@ impl Print for Ident {
 }
 
 impl Print for QName {
-    fn pretty<'b, 'a: 'b, A: DocAllocator<'a>>(
+    fn pretty<'b, 'a: 'b, A: DocAllocator<'a, NodeRef>>(
         &'a self,
         alloc: &'a A,
         env: &mut PrintEnv,
-    ) -> DocBuilder<'a, A>
+    ) -> DocBuilder<'a, A, NodeRef>
     where
         A::Doc: Clone,
     {
@@ -1160,3 +1295,43 @@ impl Print for QName {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(qname: &QName, env: &mut PrintEnv) -> String {
+        let alloc = BoxAllocator;
+        let mut out = String::new();
+        qname.pretty(&alloc, env).1.render_fmt(80, &mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn qname_strips_open_scopes() {
+        let qname = QName::from_string("A.B.c").unwrap();
+        let mut env = PrintEnv { scopes: vec!["A".into()] };
+        assert_eq!(render(&qname, &mut env), "B.c");
+
+        env.scopes = vec!["A".into(), "D".into()];
+        assert_eq!(render(&qname, &mut env), "B.c");
+
+        env.scopes = vec!["E".into()];
+        assert_eq!(render(&qname, &mut env), "A.B.c");
+    }
+
+    #[test]
+    fn span_sink() {
+        let lhs = Exp::pure_var("x".into());
+        let rhs = Exp::Const(Constant::Int(1, None));
+        let exp = Exp::BinaryOp(BinOp::Add, box lhs, box rhs);
+
+        let mut sink = SpanSink::new(String::new());
+        exp.render_spans(80, &mut sink).unwrap();
+        let Exp::BinaryOp(_, box lhs, box rhs) = &exp else { unreachable!() };
+        assert_eq!(sink.span_of(&exp), Some(0..5));
+        assert_eq!(sink.span_of(lhs), Some(0..1));
+        assert_eq!(sink.span_of(rhs), Some(4..5));
+        assert_eq!(sink.into_inner(), "x + 1");
+    }
+}
@@ -1,4 +1,4 @@
-use std::{borrow::Cow, ops::Deref};
+use std::{borrow::Cow, lazy::SyncOnceCell, ops::Deref};
 
 use indexmap::Equivalent;
 #[cfg(feature = "serialize")]
@@ -13,7 +13,7 @@ pub struct Ident(pub(crate) String);
 impl Ident {
     // Constructs a valid why3 identifier representing a given string
     pub fn build(name: &str) -> Self {
-        if RESERVED.contains(&name) {
+        if is_reserved(name) {
             return Ident(format!("{}'", name));
         }
         // TODO: ensure that all characters are valid
@@ -138,14 +138,32 @@ impl From<Ident> for QName {
     }
 }
 
-const RESERVED: &[&str] = &[
+static EXTRA_RESERVED: SyncOnceCell<Vec<String>> = SyncOnceCell::new();
+
+/// Register identifiers to escape in addition to [`RESERVED`], for instance keywords of a newer
+/// Why3. Only the first call has an effect, and it should happen before any identifier is built.
+pub fn reserve_identifiers(idents: Vec<String>) {
+    let _ = EXTRA_RESERVED.set(idents);
+}
+
+/// Whether `name` must be escaped to be used as a Why3 identifier
+pub fn is_reserved(name: &str) -> bool {
+    RESERVED.contains(&name)
+        || EXTRA_RESERVED.get().map_or(false, |extra| extra.iter().any(|id| id == name))
+}
+
+/// The keywords of Why3, which cannot be used as identifiers
+pub const RESERVED: &[&str] = &[
     "abstract",
+    "absurd",
     "alias",
     "any",
+    "as",
     "assert",
     "assume",
     "at",
     "axiom",
+    "begin",
     "break",
     "by",
     "check",
@@ -155,39 +173,54 @@ const RESERVED: &[&str] = &[
     "continue",
     "diverges",
     "do",
+    "done",
+    "downto",
     "else",
     "end",
     "ensures",
     "epsilon",
     "exception",
+    "exists",
     "export",
     "false",
+    "float",
     "for",
+    "forall",
     "fun",
     "function",
     "ghost",
     "goal",
     "if",
     "import",
+    "in",
     "inductive",
     "invariant",
     "label",
     "lemma",
     "let",
     "match",
+    "meta",
     "module",
     "mutable",
     "not",
     "old",
+    "partial",
     "predicate",
     "private",
+    "pure",
     "raise",
+    "raises",
+    "range",
     "reads",
     "rec",
+    "ref",
     "requires",
     "return",
     "returns",
     "scope",
+    "so",
+    "then",
+    "theory",
     "to",
     "true",
     "try",
@@ -207,4 +240,10 @@ mod tests {
     fn reserved_idents_made_valid() {
         assert_eq!(Ident::build("clone").0, "clone'")
     }
+
+    #[test]
+    fn extra_reserved_idents_made_valid() {
+        reserve_identifiers(vec!["reserved_for_test".into()]);
+        assert_eq!(Ident::build("reserved_for_test").0, "reserved_for_test'")
+    }
 }
@@ -0,0 +1,398 @@
+// Generates random expressions, prints them and parses the output back with a small reference
+// parser following the Why3 grammar. Any expression which is printed with missing parentheses, or
+// with an identifier which collides with a Why3 keyword, fails to read back as the same tree.
+
+use proptest::prelude::*;
+use why3::{
+    exp::{BinOp, Constant, Exp, UnOp},
+    Ident, Print,
+};
+
+const WHY3_KEYWORDS: &[&str] = &[
+    "abstract",
+    "absurd",
+    "alias",
+    "any",
+    "as",
+    "assert",
+    "assume",
+    "at",
+    "axiom",
+    "begin",
+    "break",
+    "by",
+    "check",
+    "clone",
+    "coinductive",
+    "constant",
+    "continue",
+    "diverges",
+    "do",
+    "done",
+    "downto",
+    "else",
+    "end",
+    "ensures",
+    "epsilon",
+    "exception",
+    "exists",
+    "export",
+    "false",
+    "float",
+    "for",
+    "forall",
+    "fun",
+    "function",
+    "ghost",
+    "goal",
+    "if",
+    "import",
+    "in",
+    "inductive",
+    "invariant",
+    "label",
+    "lemma",
+    "let",
+    "match",
+    "meta",
+    "module",
+    "mutable",
+    "not",
+    "old",
+    "partial",
+    "predicate",
+    "private",
+    "pure",
+    "raise",
+    "raises",
+    "range",
+    "reads",
+    "rec",
+    "ref",
+    "requires",
+    "return",
+    "returns",
+    "scope",
+    "so",
+    "then",
+    "theory",
+    "to",
+    "true",
+    "try",
+    "type",
+    "use",
+    "val",
+    "variant",
+    "while",
+    "with",
+    "writes",
+];
+
+const NAMES: &[&str] = &["x", "y", "z", "a_1", "b'", "f", "g"];
+
+fn arb_ident() -> impl Strategy<Value = Ident> {
+    prop_oneof![prop::sample::select(NAMES), prop::sample::select(WHY3_KEYWORDS),]
+        .prop_map(Ident::build)
+}
+
+fn arb_binop() -> impl Strategy<Value = BinOp> {
+    use BinOp::*;
+    prop::sample::select(&[And, Or, Add, Sub, Mul, Div, Mod, Eq, Lt, Le, Gt, Ge, Ne][..])
+}
+
+fn arb_exp() -> impl Strategy<Value = Exp> {
+    let leaf = prop_oneof![
+        any::<i64>().prop_map(|i| Exp::Const(Constant::Int(i as i128, None))),
+        any::<bool>().prop_map(|b| Exp::Const(Constant::Bool(b))),
+        arb_ident().prop_map(Exp::pure_var),
+    ];
+
+    leaf.prop_recursive(5, 64, 3, |inner| {
+        prop_oneof![
+            (arb_binop(), inner.clone(), inner.clone()).prop_map(|(op, l, r)| Exp::BinaryOp(
+                op,
+                Box::new(l),
+                Box::new(r)
+            )),
+            inner.clone().prop_map(|e| Exp::UnaryOp(UnOp::Not, Box::new(e))),
+            inner.clone().prop_map(|e| Exp::UnaryOp(UnOp::Neg, Box::new(e))),
+            (inner.clone(), inner.clone()).prop_map(|(l, r)| Exp::Impl(Box::new(l), Box::new(r))),
+            (inner.clone(), inner.clone(), inner.clone())
+                .prop_map(|(c, t, e)| { Exp::IfThenElse(Box::new(c), Box::new(t), Box::new(e)) }),
+            (arb_ident(), prop::collection::vec(inner, 1..3))
+                .prop_map(|(f, args)| Exp::Call(Box::new(Exp::pure_var(f)), args)),
+        ]
+    })
+}
+
+fn op_str(op: BinOp) -> &'static str {
+    use BinOp::*;
+    match op {
+        And => "&&",
+        Or => "||",
+        Add => "+",
+        Sub => "-",
+        Mul => "*",
+        Div => "/",
+        Mod => "%",
+        Eq => "=",
+        Ne => "<>",
+        Gt => ">",
+        Ge => ">=",
+        Lt => "<",
+        Le => "<=",
+    }
+}
+
+// The s-expression of the tree we expect to read back. Negative literals are lexed by Why3 as a
+// prefix minus applied to a natural number.
+fn sexp(e: &Exp) -> String {
+    match e {
+        Exp::Const(Constant::Int(i, None)) if *i < 0 => format!("(- {})", i.unsigned_abs()),
+        Exp::Const(Constant::Int(i, None)) => i.to_string(),
+        Exp::Const(Constant::Bool(b)) => b.to_string(),
+        Exp::Var(v, _) => String::from(&**v),
+        Exp::BinaryOp(op, l, r) => format!("({} {} {})", op_str(*op), sexp(l), sexp(r)),
+        Exp::UnaryOp(UnOp::Not, e) => format!("(not {})", sexp(e)),
+        Exp::UnaryOp(UnOp::Neg, e) => format!("(- {})", sexp(e)),
+        Exp::Impl(l, r) => format!("(-> {} {})", sexp(l), sexp(r)),
+        Exp::IfThenElse(c, t, e) => format!("(if {} {} {})", sexp(c), sexp(t), sexp(e)),
+        Exp::Call(f, args) => {
+            let args: Vec<_> = args.iter().map(sexp).collect();
+            format!("(app {} {})", sexp(f), args.join(" "))
+        }
+        _ => unreachable!("not generated: {:?}", e),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Int(String),
+    Ident(String),
+    Sym(&'static str),
+}
+
+const SYMBOLS: &[&str] =
+    &["->", "<>", "<=", ">=", "||", "&&", "(", ")", "=", "<", ">", "+", "-", "*", "/", "%"];
+
+fn lex(s: &str) -> Result<Vec<Token>, String> {
+    let mut toks = Vec::new();
+    let mut rest = s.trim_start();
+
+    while !rest.is_empty() {
+        let c = rest.chars().next().unwrap();
+        let len;
+        if c.is_ascii_digit() {
+            len = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+            toks.push(Token::Int(rest[..len].into()));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '\''))
+                .unwrap_or(rest.len());
+            toks.push(Token::Ident(rest[..len].into()));
+        } else if let Some(sym) = SYMBOLS.iter().find(|sym| rest.starts_with(**sym)) {
+            len = sym.len();
+            toks.push(Token::Sym(sym));
+        } else {
+            return Err(format!("unexpected character {:?}", c));
+        }
+        rest = rest[len..].trim_start();
+    }
+    Ok(toks)
+}
+
+struct Parser {
+    toks: Vec<Token>,
+    pos: usize,
+}
+
+type ParseResult = Result<String, String>;
+
+impl Parser {
+    fn peek_sym(&self, sym: &str) -> bool {
+        matches!(self.toks.get(self.pos), Some(Token::Sym(s)) if *s == sym)
+    }
+
+    fn peek_kw(&self, kw: &str) -> bool {
+        matches!(self.toks.get(self.pos), Some(Token::Ident(s)) if s == kw)
+    }
+
+    fn eat_sym(&mut self, syms: &[&'static str]) -> Option<&'static str> {
+        let sym = syms.iter().find(|s| self.peek_sym(s))?;
+        self.pos += 1;
+        Some(sym)
+    }
+
+    fn expect_kw(&mut self, kw: &str) -> Result<(), String> {
+        if self.peek_kw(kw) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(format!("expected `{}` at token {}", kw, self.pos))
+        }
+    }
+
+    fn expr(&mut self) -> ParseResult {
+        if self.peek_kw("if") {
+            self.pos += 1;
+            let c = self.expr()?;
+            self.expect_kw("then")?;
+            let t = self.expr()?;
+            self.expect_kw("else")?;
+            let e = self.expr()?;
+            return Ok(format!("(if {} {} {})", c, t, e));
+        }
+
+        let l = self.disj()?;
+        if self.eat_sym(&["->"]).is_some() {
+            let r = self.expr()?;
+            return Ok(format!("(-> {} {})", l, r));
+        }
+        Ok(l)
+    }
+
+    fn disj(&mut self) -> ParseResult {
+        let l = self.conj()?;
+        if self.eat_sym(&["||"]).is_some() {
+            return Ok(format!("(|| {} {})", l, self.disj()?));
+        }
+        Ok(l)
+    }
+
+    fn conj(&mut self) -> ParseResult {
+        let l = self.not()?;
+        if self.eat_sym(&["&&"]).is_some() {
+            return Ok(format!("(&& {} {})", l, self.conj()?));
+        }
+        Ok(l)
+    }
+
+    fn not(&mut self) -> ParseResult {
+        if self.peek_kw("not") {
+            self.pos += 1;
+            return Ok(format!("(not {})", self.not()?));
+        }
+        self.infix1()
+    }
+
+    // Level 1 infix operators are not associative: `a = b = c` is rejected
+    fn infix1(&mut self) -> ParseResult {
+        const OPS: &[&str] = &["=", "<>", "<=", ">=", "<", ">"];
+        let l = self.infix2()?;
+        if let Some(op) = self.eat_sym(OPS) {
+            let r = self.infix2()?;
+            if self.eat_sym(OPS).is_some() {
+                return Err("chained comparison".into());
+            }
+            return Ok(format!("({} {} {})", op, l, r));
+        }
+        Ok(l)
+    }
+
+    fn infix2(&mut self) -> ParseResult {
+        let mut l = self.infix3()?;
+        while let Some(op) = self.eat_sym(&["+", "-"]) {
+            l = format!("({} {} {})", op, l, self.infix3()?);
+        }
+        Ok(l)
+    }
+
+    fn infix3(&mut self) -> ParseResult {
+        let mut l = self.prefix()?;
+        while let Some(op) = self.eat_sym(&["*", "/", "%"]) {
+            l = format!("({} {} {})", op, l, self.prefix()?);
+        }
+        Ok(l)
+    }
+
+    fn prefix(&mut self) -> ParseResult {
+        if self.eat_sym(&["-"]).is_some() {
+            return Ok(format!("(- {})", self.prefix()?));
+        }
+        self.app()
+    }
+
+    fn app(&mut self) -> ParseResult {
+        let head = self.atom()?;
+        let mut args = Vec::new();
+        while self.starts_atom() {
+            args.push(self.atom()?);
+        }
+
+        if args.is_empty() {
+            Ok(head)
+        } else {
+            Ok(format!("(app {} {})", head, args.join(" ")))
+        }
+    }
+
+    fn starts_atom(&self) -> bool {
+        match self.toks.get(self.pos) {
+            Some(Token::Int(_)) | Some(Token::Sym("(")) => true,
+            Some(Token::Ident(id)) => {
+                !WHY3_KEYWORDS.contains(&&**id) || id == "true" || id == "false"
+            }
+            _ => false,
+        }
+    }
+
+    fn atom(&mut self) -> ParseResult {
+        let tok = self.toks.get(self.pos).ok_or("unexpected end of input")?;
+        self.pos += 1;
+        match tok {
+            Token::Int(i) => Ok(i.clone()),
+            Token::Ident(id) if id == "true" || id == "false" => Ok(id.clone()),
+            Token::Ident(id) if WHY3_KEYWORDS.contains(&&**id) => {
+                Err(format!("keyword `{}` used as an identifier", id))
+            }
+            Token::Ident(id) => Ok(id.clone()),
+            Token::Sym("(") => {
+                let e = self.expr()?;
+                self.eat_sym(&[")"]).ok_or("unclosed parenthesis")?;
+                Ok(e)
+            }
+            Token::Sym(s) => Err(format!("unexpected `{}`", s)),
+        }
+    }
+}
+
+fn parse(s: &str) -> ParseResult {
+    let mut parser = Parser { toks: lex(s)?, pos: 0 };
+    let e = parser.expr()?;
+    if parser.pos != parser.toks.len() {
+        return Err(format!("trailing tokens from {:?}", parser.toks[parser.pos]));
+    }
+    Ok(e)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig { cases: 2048, ..ProptestConfig::default() })]
+
+    #[test]
+    fn printed_exp_reparses(e in arb_exp()) {
+        let printed = e.display().to_string();
+        prop_assert_eq!(parse(&printed), Ok(sexp(&e)), "printed as: {}", printed);
+    }
+}
+
+#[test]
+fn reference_parser_sanity() {
+    assert_eq!(parse("a - b - c"), Ok("(- (- a b) c)".into()));
+    assert_eq!(parse("a -> b -> c"), Ok("(-> a (-> b c))".into()));
+    assert_eq!(parse("- f x * 2"), Ok("(* (- (app f x)) 2)".into()));
+    assert!(parse("a = b = c").is_err());
+    assert!(parse("f then").is_err());
+}
+
+#[test]
+fn minimal_parentheses() {
+    let var = |n: &str| Box::new(Exp::pure_var(n.into()));
+    let sub = |l, r| Box::new(Exp::BinaryOp(BinOp::Sub, l, r));
+    let imp = |l, r| Box::new(Exp::Impl(l, r));
+
+    let print = |e: Box<Exp>| e.display().to_string();
+    assert_eq!(print(sub(sub(var("a"), var("b")), var("c"))), "a - b - c");
+    assert_eq!(print(sub(var("a"), sub(var("b"), var("c")))), "a - (b - c)");
+    assert_eq!(print(imp(var("a"), imp(var("b"), var("c")))), "a -> b -> c");
+    assert_eq!(print(imp(imp(var("a"), var("b")), var("c"))), "(a -> b) -> c");
+}
@@ -0,0 +1,65 @@
+// Parses every expected MLCFG output of the Creusot test suite and prints it again, which must give
+// back the file unchanged.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use why3::{mlcfg::parser::parse_modules, Print};
+
+fn mlcfg_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            mlcfg_files(&path, files);
+        } else if path.extension().map_or(false, |ext| ext == "mlcfg") {
+            files.push(path);
+        }
+    }
+}
+
+fn reprint(source: &str) -> Result<String, String> {
+    let (header, body) = source.split_once('\n').ok_or("empty file")?;
+    let modules = parse_modules(body).map_err(|err| err.to_string())?;
+
+    let mut out = Vec::new();
+    out.extend_from_slice(header.as_bytes());
+    out.push(b'\n');
+    for modl in &modules {
+        modl.render(120, &mut out).unwrap();
+        out.push(b'\n');
+    }
+    Ok(String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn expected_outputs_round_trip() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../creusot/tests/should_succeed");
+    let mut files = Vec::new();
+    mlcfg_files(&root, &mut files);
+    files.sort();
+    assert!(!files.is_empty());
+
+    let mut failures = Vec::new();
+    for file in &files {
+        let source = fs::read_to_string(file).unwrap();
+        match reprint(&source) {
+            Ok(printed) if printed == source => {}
+            Ok(printed) => {
+                let common = source.lines().count().min(printed.lines().count());
+                let mut lines = source.lines().zip(printed.lines());
+                let line = lines.position(|(a, b)| a != b).unwrap_or(common);
+                failures.push(format!("{}: differs at line {}", file.display(), line + 1));
+            }
+            Err(err) => failures.push(format!("{}: {}", file.display(), err)),
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} of {} files:\n{}",
+        failures.len(),
+        files.len(),
+        failures.join("\n")
+    );
+}
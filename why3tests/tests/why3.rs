@@ -4,6 +4,9 @@ use std::io::{BufRead, BufReader, Write};
 use std::process::{exit, Command};
 use termcolor::*;
 
+// Must match `MLCFG_FORMAT_VERSION` in creusot/src/translation.rs
+const MLCFG_FORMAT_VERSION: u32 = 1;
+
 fn main() {
     let why3_path = std::env::var("WHY3_PATH").unwrap_or_else(|_| "why3".into());
     let config_path = std::env::var("WHY3_CONFIG");
@@ -15,6 +18,13 @@ fn main() {
 
     let filter = std::env::args().nth(1);
 
+    let prelude_header = BufReader::new(File::open("../prelude/prelude.mlw").unwrap())
+        .lines()
+        .nth(0)
+        .unwrap()
+        .unwrap();
+    let prelude_version = prelude_version(&prelude_header).expect("missing prelude version");
+
     let mut success = true;
     let mut obsolete = false;
     for file in glob::glob("../creusot/tests/should_succeed/**/*.rs").unwrap() {
@@ -41,6 +51,33 @@ fn main() {
             continue;
         }
 
+        let mlcfg_header = File::open(&file)
+            .ok()
+            .and_then(|f| BufReader::new(f).lines().nth(0))
+            .and_then(|line| line.ok());
+        match mlcfg_header.as_deref().and_then(header_versions) {
+            Some((MLCFG_FORMAT_VERSION, prelude)) if prelude == prelude_version => {}
+            versions => {
+                out.set_color(ColorSpec::new().set_fg(Some(Color::Red))).unwrap();
+                writeln!(&mut out, "incompatible").unwrap();
+                out.reset().unwrap();
+                match versions {
+                    Some((format, prelude)) => writeln!(
+                        &mut out,
+                        "generated with MLCFG format {format} for prelude {prelude}, expected format {MLCFG_FORMAT_VERSION} for prelude {prelude_version}"
+                    )
+                    .unwrap(),
+                    None => writeln!(&mut out, "missing version header").unwrap(),
+                }
+
+                success = false;
+                if lazy {
+                    break;
+                }
+                continue;
+            }
+        }
+
         let mut sessiondir = file.clone();
         sessiondir.set_file_name(file.file_stem().unwrap());
 
@@ -138,3 +175,15 @@ fn main() {
         exit(1)
     }
 }
+
+// Reads the versions from the header Creusot writes at the top of every generated file
+fn header_versions(line: &str) -> Option<(u32, u32)> {
+    let versions = line.strip_prefix("(* Creusot MLCFG format ")?.strip_suffix(" *)")?;
+    let (format, prelude) = versions.split_once(", prelude ")?;
+    Some((format.parse().ok()?, prelude.parse().ok()?))
+}
+
+fn prelude_version(line: &str) -> Option<u32> {
+    let version = line.strip_prefix("(* Creusot prelude version ")?;
+    version.split(|c: char| !c.is_ascii_digit()).next()?.parse().ok()
+}